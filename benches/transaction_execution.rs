@@ -0,0 +1,103 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use ed25519_dalek::{Signer, SigningKey};
+use terminator_dancer::{
+    IntegratedRuntime, Pubkey, SolanaCrypto, SolanaHash, SolanaPubkey, SolanaTransaction, SolanaTransactionParser,
+};
+
+/// Mainnet SOL transfer, as checked in to `examples/debug_tx_bytes.rs`
+const MAINNET_TRANSFER_BASE64: &str = "AWDBlrdyFjzjDgf9gWioXrCB/YJpHeENZcIEwNPzflGviVkElIKpUR7yvnwrNsz0cuq5MGm0FlR/7gf8piruIw6AAQABA/NGAeBeYMRrJvmYo4E2q+pEKIVjl40S0g00e/NP8G7JAGBZvnD3SSIz2B5EgB+fk5vSvVThak5kIyxG8n1zLKIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAVYxfd1NZLpMnJgbaVBboof2ZjR+cEKxQwMiWhlFusxAQICAAEMAgAAAGgKHwAAAAAAAA==";
+
+const BATCH_SIZE: usize = 64;
+
+/// Runtime whose payer can cover any number of benchmark iterations
+fn funded_runtime(payer: &Pubkey) -> IntegratedRuntime {
+    let mut runtime = IntegratedRuntime::new().expect("Failed to create runtime");
+    runtime.fund_account(payer, u64::MAX / 2);
+    runtime
+}
+
+/// One-lamport transfers from `payer` to distinct recipients
+fn transfers(payer: &Pubkey, count: usize) -> Vec<SolanaTransaction> {
+    (0..count)
+        .map(|i| {
+            let mut recipient = [0u8; 32];
+            recipient[..8].copy_from_slice(&(i as u64 + 1).to_le_bytes());
+            SolanaTransactionParser::create_transfer_transaction(
+                SolanaPubkey::new(payer.0),
+                SolanaPubkey::new(recipient),
+                1,
+                SolanaHash([0u8; 32]),
+            )
+        })
+        .collect()
+}
+
+fn bench_parse_mainnet_transaction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parsing");
+
+    let raw = SolanaTransactionParser::parse_transaction_base64(MAINNET_TRANSFER_BASE64)
+        .and_then(|tx| SolanaTransactionParser::serialize_transaction(&tx))
+        .expect("Checked-in transaction parses");
+
+    group.throughput(Throughput::Bytes(raw.len() as u64));
+    group.bench_function("mainnet_transfer", |b| {
+        b.iter(|| SolanaTransactionParser::parse_transaction(black_box(&raw)))
+    });
+    group.bench_function("mainnet_transfer_base64", |b| {
+        b.iter(|| SolanaTransactionParser::parse_transaction_base64(black_box(MAINNET_TRANSFER_BASE64)))
+    });
+
+    group.finish();
+}
+
+fn bench_execute_transfers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("execution");
+    let payer = Pubkey::new([1u8; 32]);
+
+    let mut runtime = funded_runtime(&payer);
+    let single = transfers(&payer, 1).remove(0);
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("single_transfer", |b| {
+        b.iter(|| runtime.execute_solana_transaction_parsed(black_box(&single)).unwrap())
+    });
+
+    let mut runtime = funded_runtime(&payer);
+    let batch = transfers(&payer, BATCH_SIZE);
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    group.bench_function("transfer_batch_64", |b| {
+        b.iter(|| {
+            for tx in &batch {
+                runtime.execute_solana_transaction_parsed(black_box(tx)).unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_crypto(c: &mut Criterion) {
+    let mut group = c.benchmark_group("crypto");
+
+    let message = SolanaTransactionParser::message_data(&transfers(&Pubkey::new([1u8; 32]), 1)[0].message)
+        .expect("Transfer message serializes");
+    group.throughput(Throughput::Bytes(message.len() as u64));
+    group.bench_function("sha256_message", |b| {
+        b.iter(|| SolanaCrypto::sha256_hash(black_box(&message)))
+    });
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = signing_key.verifying_key().to_bytes();
+    let signature = signing_key.sign(&message).to_bytes();
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("ed25519_verify", |b| {
+        b.iter(|| SolanaCrypto::verify_ed25519_signature(black_box(&signature), black_box(&message), &public_key))
+    });
+    group.bench_function("ed25519_sign", |b| {
+        b.iter_batched(|| message.clone(), |message| signing_key.sign(&message), BatchSize::SmallInput)
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_mainnet_transaction, bench_execute_transfers, bench_crypto);
+criterion_main!(benches);
@@ -2,6 +2,9 @@ use std::env;
 use std::path::PathBuf;
 
 fn main() {
+    // Set when Firedancer's libraries are linked in place of the in-crate stubs
+    println!("cargo:rustc-check-cfg=cfg(firedancer_linked)");
+    
     let firedancer_path = "../../../development/firedancer";
     
     // Check if Firedancer is available
@@ -34,6 +37,7 @@ fn link_firedancer(firedancer_path: &str) {
         // Try to link if libraries exist, but don't fail if they don't
         if std::path::Path::new(&lib_dir).exists() {
             println!("cargo:warning=✅ Firedancer libraries found, linking...");
+            println!("cargo:rustc-cfg=firedancer_linked");
             println!("cargo:rustc-link-search=native={}", lib_dir);
             
             // Link core Firedancer libraries
@@ -6,7 +6,9 @@
 
 use terminator_dancer::{
     integrated_runtime::IntegratedRuntime,
-    solana_format::{SolanaTransactionParser, SolanaPubkey, SolanaHash, CompiledInstruction, SolanaMessage, MessageHeader, SolanaTransaction, SolanaSignature},
+    solana_format::{SolanaTransactionParser, SolanaHash, CompiledInstruction, SolanaMessage, MessageHeader, SolanaTransaction, SolanaSignature},
+    risk_analyzer::{AddressList, Blacklist, ListMode, TransactionRiskAnalyzer},
+    testing,
     types::Pubkey,
     Result,
 };
@@ -40,8 +42,8 @@ async fn analyze_safe_transaction(runtime: &mut IntegratedRuntime) -> Result<()>
     println!("✅ ANALYSIS 1: SAFE TRANSACTION");
     println!("===============================");
     
-    let from = SolanaPubkey::new([1u8; 32]);
-    let to = SolanaPubkey::new([2u8; 32]);
+    let from = testing::pubkey(&testing::alice());
+    let to = testing::pubkey(&testing::bob());
     let amount = 10_000_000; // 0.01 SOL - reasonable amount
     
     println!("📋 Transaction to analyze:");
@@ -78,20 +80,21 @@ async fn analyze_safe_transaction(runtime: &mut IntegratedRuntime) -> Result<()>
     println!("   🏗️ Structure Check: {}", 
              if simple_structure { "✅ Simple transfer" } else { "⚠️ Complex transaction" });
     
-    // Simulate runtime execution to check for errors
-    runtime.fund_account(&Pubkey::new(from.0), 100_000_000);
-    let simulation_result = runtime.execute_solana_transaction_parsed(&tx);
-    let will_succeed = simulation_result.is_ok();
+    // Check balances to see whether the transaction would fail
+    runtime.fund_account(&from.into(), 100_000_000);
+    let analyzer = TransactionRiskAnalyzer::new();
+    let will_succeed = analyzer.check_simulation(&tx, runtime).is_none();
     
     println!("   ⚡ Simulation Check: {}", 
              if will_succeed { "✅ Transaction will succeed" } else { "❌ Transaction will fail" });
     
+    let report = analyzer.analyze(&tx, runtime);
     let analysis_time = analysis_start.elapsed();
     
     println!();
     println!("🎯 VERDICT: ✅ SAFE TO SIGN");
     println!("   Analysis time: {:?}", analysis_time);
-    println!("   Risk score: 0/10 (Very Safe)");
+    println!("   Risk score: {}/10 (Very Safe)", report.score);
     println!("   Recommendation: ✅ Agent approves signing");
     println!();
     
@@ -102,8 +105,8 @@ async fn analyze_drain_wallet_transaction(runtime: &mut IntegratedRuntime) -> Re
     println!("⚠️ ANALYSIS 2: SUSPICIOUS DRAIN TRANSACTION");
     println!("==========================================");
     
-    let victim = SolanaPubkey::new([10u8; 32]);
-    let attacker = SolanaPubkey::new([66u8; 32]); // Suspicious address
+    let victim = testing::pubkey(&testing::payer());
+    let attacker = testing::pubkey(&testing::deterministic_keypair(3)); // Suspicious address
     let drain_amount = 1_000_000_000; // 1 SOL - draining significant funds
     
     println!("📋 Transaction to analyze:");
@@ -127,13 +130,14 @@ async fn analyze_drain_wallet_transaction(runtime: &mut IntegratedRuntime) -> Re
              if large_amount { "🚨 LARGE AMOUNT (>0.5 SOL)" } else { "✅ Reasonable amount" });
     
     // Check 2: Recipient reputation (simulated blacklist check)
-    let suspicious_recipient = attacker.0[0] == 66; // Simulate blacklist hit
+    let blacklist = AddressList::new(ListMode::Deny, [attacker]);
+    let suspicious_recipient = blacklist.is_flagged(&attacker).is_some();
     println!("   👤 Recipient Check: {}", 
              if suspicious_recipient { "🚨 SUSPICIOUS ADDRESS" } else { "✅ Clean address" });
     
     // Check 3: Check if this drains most of the wallet
-    runtime.fund_account(&Pubkey::new(victim.0), 1_100_000_000); // 1.1 SOL
-    let current_balance = runtime.get_balance(&Pubkey::new(victim.0));
+    runtime.fund_account(&victim.into(), 1_100_000_000); // 1.1 SOL
+    let current_balance = runtime.get_balance(&victim.into());
     let percentage_drained = (drain_amount as f64 / current_balance as f64) * 100.0;
     
     println!("   💸 Drain Check: 🚨 DRAINS {:.1}% OF WALLET", percentage_drained);
@@ -141,12 +145,15 @@ async fn analyze_drain_wallet_transaction(runtime: &mut IntegratedRuntime) -> Re
     // Check 4: Recent activity pattern (simulated)
     println!("   📊 Pattern Check: 🚨 Similar to known scam patterns");
     
+    let report = TransactionRiskAnalyzer::new()
+        .with_blacklist(blacklist)
+        .analyze(&tx, runtime);
     let analysis_time = analysis_start.elapsed();
     
     println!();
     println!("🚨 VERDICT: DANGEROUS - DO NOT SIGN");
     println!("   Analysis time: {:?}", analysis_time);
-    println!("   Risk score: 9/10 (Very Dangerous)");
+    println!("   Risk score: {}/10 (Very Dangerous)", report.score);
     println!("   Recommendation: 🛑 Agent blocks signing");
     println!();
     println!("⚠️ WARNING SIGNS:");
@@ -162,8 +169,8 @@ async fn analyze_unknown_program_transaction(runtime: &mut IntegratedRuntime) ->
     println!("🚨 ANALYSIS 3: UNKNOWN PROGRAM INTERACTION");
     println!("==========================================");
     
-    let user = SolanaPubkey::new([5u8; 32]);
-    let unknown_program = SolanaPubkey::new([88u8; 32]); // Unknown program
+    let user = testing::pubkey(&testing::deterministic_keypair(4));
+    let unknown_program = testing::pubkey(&testing::deterministic_keypair(5)); // Unknown program
     let recent_blockhash = SolanaHash([77u8; 32]);
     
     // Create a suspicious transaction with unknown program
@@ -198,6 +205,9 @@ async fn analyze_unknown_program_transaction(runtime: &mut IntegratedRuntime) ->
     println!("   Program: {}...{}", hex::encode(&unknown_program.0[..4]), hex::encode(&unknown_program.0[28..]));
     println!("   Instruction: Unknown (opcode 1)");
     
+    // Give the user enough to cover fees so only the program itself is in question
+    runtime.fund_account(&user.into(), 10_000_000);
+    
     let analysis_start = Instant::now();
     
     println!();
@@ -219,12 +229,13 @@ async fn analyze_unknown_program_transaction(runtime: &mut IntegratedRuntime) ->
     // Check 4: Program verification
     println!("   🛡️ Verification Check: ❌ Program not verified");
     
+    let report = TransactionRiskAnalyzer::new().analyze(&tx, runtime);
     let analysis_time = analysis_start.elapsed();
     
     println!();
     println!("🚨 VERDICT: HIGH RISK - REQUIRES MANUAL REVIEW");
     println!("   Analysis time: {:?}", analysis_time);
-    println!("   Risk score: 8/10 (High Risk)");
+    println!("   Risk score: {}/10 (High Risk)", report.score);
     println!("   Recommendation: 🛑 Agent suggests caution");
     println!();
     println!("⚠️ RISK FACTORS:");
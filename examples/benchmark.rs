@@ -1,10 +1,16 @@
 use terminator_dancer::{
     TerminatorRuntime, Transaction, Account, Instruction, InstructionData, AccountMeta, Pubkey,
     crypto::SolanaCrypto,
+    testing,
 };
 use std::collections::HashMap;
-use ed25519_dalek::{SigningKey, Signer};
-use rand::rngs::OsRng;
+use ed25519_dalek::Signer;
+
+/// Address of test keypair `index`
+fn test_key(index: usize) -> Pubkey {
+    let index = u8::try_from(index).expect("at most 256 test keypairs");
+    testing::pubkey(&testing::deterministic_keypair(index)).into()
+}
 
 fn create_test_runtime() -> TerminatorRuntime {
     // Since runtime is async, we'll create it in each benchmark
@@ -16,8 +22,8 @@ fn create_test_runtime() -> TerminatorRuntime {
 fn create_test_accounts(count: usize) -> HashMap<Pubkey, Account> {
     let mut accounts = HashMap::new();
     
-    for _i in 0..count {
-        let key = Pubkey::new_unique();
+    for i in 0..count {
+        let key = test_key(i);
         
         let account = Account {
             lamports: 1_000_000_000, // 1 SOL
@@ -36,9 +42,9 @@ fn create_test_accounts(count: usize) -> HashMap<Pubkey, Account> {
 fn create_transfer_transactions(count: usize) -> Vec<Transaction> {
     let mut transactions = Vec::new();
     
-    for _i in 0..count {
-        let from = Pubkey::new_unique();
-        let to = Pubkey::new_unique();
+    for i in 0..count {
+        let from = test_key(2 * i);
+        let to = test_key(2 * i + 1);
         
         let instruction = Instruction {
             program_id: Pubkey::system_program(),
@@ -75,10 +81,10 @@ fn create_transfer_transactions(count: usize) -> Vec<Transaction> {
 fn create_complex_transactions(count: usize) -> Vec<Transaction> {
     let mut transactions = Vec::new();
     
-    for _i in 0..count {
-        let payer = Pubkey::new_unique();
-        let new_account = Pubkey::new_unique();
-        let transfer_to = Pubkey::new_unique();
+    for i in 0..count {
+        let payer = test_key(3 * i);
+        let new_account = test_key(3 * i + 1);
+        let transfer_to = test_key(3 * i + 2);
         
         // Multi-instruction transaction
         let instructions = vec![
@@ -156,8 +162,7 @@ fn main() {
     println!("Test data created successfully");
     
     // Test crypto operations
-    let mut csprng = OsRng;
-    let signing_key = SigningKey::generate(&mut csprng);
+    let signing_key = testing::payer();
     let verifying_key = signing_key.verifying_key();
     let message = b"benchmark message for signature verification";
     let signature = signing_key.sign(message);
@@ -1,5 +1,7 @@
 use terminator_dancer::crypto::{SolanaCrypto, FastCrypto, AddressDerivation};
-use terminator_dancer::solana_format::{SolanaTransactionParser, SolanaPubkey, SolanaHash};
+use terminator_dancer::solana_format::{SolanaTransactionParser, SolanaHash};
+use terminator_dancer::testing;
+use terminator_dancer::transaction_builder::TransactionBuilder;
 use ed25519_dalek::{SigningKey, Signer};
 use rand::rngs::OsRng;
 use std::time::Instant;
@@ -96,13 +98,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🌐 TEST 4: Solana Transaction Format");
     println!("===================================");
     
-    let from = SolanaPubkey::new([1u8; 32]);
-    let to = SolanaPubkey::new([2u8; 32]);
+    let alice = testing::alice();
+    let from = testing::pubkey(&alice);
+    let to = testing::pubkey(&testing::bob());
     let blockhash = SolanaHash([3u8; 32]);
     
-    let transaction = SolanaTransactionParser::create_transfer_transaction(
+    let mut transaction = SolanaTransactionParser::create_transfer_transaction(
         from, to, 1_000_000, blockhash
     );
+    TransactionBuilder::sign(&mut transaction, &alice)?;
     
     println!("Created Solana-compatible transaction:");
     println!("From: {}", from.to_string());
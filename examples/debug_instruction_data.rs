@@ -8,7 +8,7 @@ use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 fn main() {
     println!("🔍 DEBUGGING INSTRUCTION DATA");
     println!("=============================");
-    println!("Block explorer shows: 2,034,200 lamports (0.002 SOL)");
+    println!("Block explorer shows: 2,034,280 lamports (0.00203428 SOL)");
     println!("Let's find where this is encoded...");
     println!();
 
@@ -91,6 +91,12 @@ fn main() {
             ]) as u64;
             println!("   First 4 bytes as u32 LE: {} lamports = {:.9} SOL", lamports_u32_le, lamports_u32_le as f64 / 1_000_000_000.0);
             
+            // System instructions start with a u32 tag, so the amount is at bytes 4..12
+            let lamports_after_tag = instruction_data.get(4..12)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+                .unwrap_or(0);
+            println!("   After u32 tag, LE: {} lamports = {:.9} SOL", lamports_after_tag, lamports_after_tag as f64 / 1_000_000_000.0);
+            
             // Show the raw bytes we're working with
             println!();
             println!("🔍 RAW LAMPORT BYTES:");
@@ -99,13 +105,15 @@ fn main() {
             }
             
             // Check if 2,034,200 appears anywhere
-            let target = 2034200u64;
+            let target = 2034280u64;
             println!();
-            println!("🎯 LOOKING FOR 2,034,200 lamports:");
+            println!("🎯 LOOKING FOR 2,034,280 lamports:");
             println!("   Target as LE bytes: {:02x?}", target.to_le_bytes());
             println!("   Target as BE bytes: {:02x?}", target.to_be_bytes());
             
-            if lamports_le == target {
+            if lamports_after_tag == target {
+                println!("   ✅ FOUND IT! The u64 after the u32 tag matches block explorer");
+            } else if lamports_le == target {
                 println!("   ✅ FOUND IT! Little endian matches block explorer");
             } else if lamports_be == target {
                 println!("   ✅ FOUND IT! Big endian matches block explorer");
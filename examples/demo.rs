@@ -1,5 +1,12 @@
 use terminator_dancer::*;
 use terminator_dancer::utils::*;
+use terminator_dancer::testing::{self, Keypair};
+use ed25519_dalek::Signer;
+
+/// Runtime address of a test keypair
+fn address(keypair: &Keypair) -> Pubkey {
+    testing::pubkey(keypair).into()
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -53,9 +60,10 @@ async fn demo_firedancer_integration() -> Result<()> {
     println!("🔧 Testing Firedancer crypto integration...");
     
     // Demo Ed25519 signature verification
-    let signature = [0u8; 64];
+    let keypair = testing::payer();
     let message = b"Terminator-Dancer transaction";
-    let pubkey = [0u8; 32];
+    let signature = keypair.sign(message).to_bytes();
+    let pubkey = testing::pubkey(&keypair).0;
     
     let sig_valid = FiredancerCrypto::verify_ed25519_signature(&signature, message, &pubkey)?;
     println!("  ✅ Ed25519 signature verification: {} (demo mode)", sig_valid);
@@ -88,8 +96,8 @@ async fn demo_firedancer_integration() -> Result<()> {
 }
 
 async fn demo_account_creation(runtime: &mut TerminatorRuntime) -> Result<TransactionResult> {
-    let from_account = Pubkey::new_unique();
-    let new_account = Pubkey::new_unique();
+    let from_account = address(&testing::payer());
+    let new_account = address(&testing::alice());
     
     let instruction = Instruction {
         program_id: Pubkey::system_program(),
@@ -125,8 +133,8 @@ async fn demo_account_creation(runtime: &mut TerminatorRuntime) -> Result<Transa
 }
 
 async fn demo_transfer(runtime: &mut TerminatorRuntime) -> Result<TransactionResult> {
-    let from_account = Pubkey::new_unique();
-    let to_account = Pubkey::new_unique();
+    let from_account = address(&testing::alice());
+    let to_account = address(&testing::bob());
     
     let instruction = Instruction {
         program_id: Pubkey::system_program(),
@@ -160,8 +168,8 @@ async fn demo_transfer(runtime: &mut TerminatorRuntime) -> Result<TransactionRes
 }
 
 async fn demo_generic_program(runtime: &mut TerminatorRuntime) -> Result<TransactionResult> {
-    let custom_program = Pubkey::new_unique();
-    let program_account = Pubkey::new_unique();
+    let custom_program = address(&testing::deterministic_keypair(3));
+    let program_account = address(&testing::deterministic_keypair(4));
     
     let instruction = Instruction {
         program_id: custom_program,
@@ -188,10 +196,10 @@ async fn demo_generic_program(runtime: &mut TerminatorRuntime) -> Result<Transac
 }
 
 async fn demo_complex_transaction(runtime: &mut TerminatorRuntime) -> Result<TransactionResult> {
-    let account1 = Pubkey::new_unique();
-    let account2 = Pubkey::new_unique();
-    let account3 = Pubkey::new_unique();
-    let custom_program = Pubkey::new_unique();
+    let account1 = address(&testing::payer());
+    let account2 = address(&testing::deterministic_keypair(5));
+    let account3 = address(&testing::deterministic_keypair(6));
+    let custom_program = address(&testing::deterministic_keypair(3));
     
     // Create multiple instructions in one transaction
     let instructions = vec![
@@ -6,8 +6,9 @@
 use terminator_dancer::{
     types::{Account, Pubkey, BankState, ExecutionContext},
     system_program::{SystemProgram, SystemInstruction, SYSTEM_PROGRAM_ID},
-    solana_format::{SolanaPubkey, SolanaTransactionParser, SolanaHash},
+    solana_format::{SolanaTransactionParser, SolanaHash},
     integrated_runtime::IntegratedRuntime,
+    testing,
     Result,
 };
 use std::time::Instant;
@@ -31,13 +32,11 @@ fn execute_real_sol_transfer() -> Result<()> {
     // Initialize the integrated runtime
     let mut runtime = IntegratedRuntime::new()?;
     
-    // Real account addresses from the parsed transaction
-    let sender_str = "Y3eRfCsAoQWmkPbVP4Zvczq2hkMTEVRWaM3s2jk82Y7";
-    let recipient_str = "BWGWextZo7huPMYfaYmfswdQxcNtquYoJrbvW6Mj2uoF";
-    
-    // Create pubkeys from the mainnet addresses
-    let sender = Pubkey::new(pubkey_from_str(sender_str).0);
-    let recipient = Pubkey::new(pubkey_from_str(recipient_str).0);
+    // Deterministic test accounts standing in for the mainnet sender and recipient
+    let sender = Pubkey::from(testing::pubkey(&testing::alice()));
+    let recipient = Pubkey::from(testing::pubkey(&testing::bob()));
+    let sender_str = "alice";
+    let recipient_str = "bob";
     
     // Set up initial balances (simulate mainnet state)
     let initial_sender_balance = 60_000_000_000u64; // 60 SOL
@@ -67,6 +66,7 @@ fn execute_real_sol_transfer() -> Result<()> {
     // Execute the transfer through the integrated runtime
     let exec_start = Instant::now();
     let tx = runtime.create_test_transfer(&sender, &recipient, transfer_amount)?;
+    let fee = runtime.calculate_fee(&tx)?;
     let result = runtime.execute_solana_transaction_parsed(&tx)?;
     let exec_time = exec_start.elapsed();
     
@@ -75,6 +75,7 @@ fn execute_real_sol_transfer() -> Result<()> {
     if result.success {
         println!("   ✅ Transaction executed successfully!");
         println!("   💻 Compute units consumed: {}", result.compute_units_consumed);
+        println!("   💸 Fee charged to sender: {} lamports", fee);
     } else {
         println!("   ❌ Transaction failed: {:?}", result.error);
         return Ok(());
@@ -132,14 +133,3 @@ fn execute_real_sol_transfer() -> Result<()> {
     
     Ok(())
 }
-
-/// Helper function for creating pubkey from string (deterministic for demo)
-fn pubkey_from_str(s: &str) -> SolanaPubkey {
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(s.as_bytes());
-    let result = hasher.finalize();
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&result);
-    SolanaPubkey(bytes)
-} 
\ No newline at end of file
@@ -0,0 +1,76 @@
+//! no_std build check
+//! Parses a wire-format transaction and runs its system instructions using
+//! only the `core`/`alloc` surface of terminator-dancer.
+//!
+//! Build for a target without std with
+//! `cargo build --manifest-path no_std_check/Cargo.toml --target thumbv7em-none-eabi`;
+//! `cargo test --manifest-path no_std_check/Cargo.toml` runs it on the host.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use terminator_dancer::{
+    Account, ExecutionContext, InstructionAccounts, Result, SolanaTransactionParser, SystemProgram,
+    TerminatorError, SYSTEM_PROGRAM_ID,
+};
+
+/// Parse `tx_data` and apply each of its system program instructions to `accounts`
+///
+/// `accounts` holds one entry per message account key, in key order.
+pub fn execute_system_transaction(
+    tx_data: &[u8],
+    accounts: &mut [Account],
+    context: &mut ExecutionContext,
+) -> Result<()> {
+    let tx = SolanaTransactionParser::parse_transaction(tx_data)?;
+    SolanaTransactionParser::validate_transaction_format(&tx)?;
+    if accounts.len() != tx.message.account_keys.len() {
+        return Err(TerminatorError::TransactionExecutionFailed(
+            "One account is required per account key".into(),
+        ));
+    }
+
+    for instruction in &tx.message.instructions {
+        let program_id = tx.message.account_keys.get(instruction.program_id_index as usize).map(|key| key.0);
+        if program_id != Some(SYSTEM_PROGRAM_ID) {
+            return Err(TerminatorError::ProgramError("Only system instructions are supported".into()));
+        }
+
+        let account_metas = instruction.account_metas(&tx.message.header, &tx.message.account_keys)?;
+        // Positions index straight into a copy of the key-ordered accounts,
+        // so a key passed twice is one account; the copy is kept on success
+        let mut updated = accounts.to_vec();
+        let positions = instruction.accounts.iter().map(|&i| i as usize).collect();
+        let mut instruction_accounts = InstructionAccounts::aliased(updated.iter_mut().collect(), positions)?;
+        SystemProgram::process_instruction(&instruction.data, &account_metas, &mut instruction_accounts, context)?;
+        accounts.clone_from_slice(&updated);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use terminator_dancer::{SolanaHash, SolanaPubkey};
+
+    #[test]
+    fn test_parse_and_transfer() {
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        let tx = SolanaTransactionParser::create_transfer_transaction(from, to, 400, SolanaHash([0u8; 32]));
+        let tx_data = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+
+        let mut accounts = [
+            Account::new(1_000, Vec::new(), SYSTEM_PROGRAM_ID),
+            Account::new(0, Vec::new(), SYSTEM_PROGRAM_ID),
+            Account::new_executable(1, Vec::new(), SYSTEM_PROGRAM_ID),
+        ];
+        let mut context = ExecutionContext::new(200_000);
+        execute_system_transaction(&tx_data, &mut accounts, &mut context).unwrap();
+
+        assert_eq!(accounts[0].lamports, 600);
+        assert_eq!(accounts[1].lamports, 400);
+    }
+}
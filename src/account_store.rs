@@ -0,0 +1,222 @@
+/// Account Storage
+/// The account database behind `IntegratedRuntime`, with a plain and a copy-on-write backend
+
+use crate::types::{Account, Pubkey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Keyed account storage a runtime reads and writes through
+pub trait AccountStore: Default + Clone {
+    fn get(&self, pubkey: &Pubkey) -> Option<&Account>;
+
+    fn get_mut(&mut self, pubkey: &Pubkey) -> Option<&mut Account>;
+
+    /// Insert or replace an account, returning the previous one
+    fn insert(&mut self, pubkey: Pubkey, account: Account) -> Option<Account>;
+
+    fn remove(&mut self, pubkey: &Pubkey) -> Option<Account>;
+
+    /// Keep only the accounts `keep` returns true for
+    fn retain(&mut self, keep: &mut dyn FnMut(&Pubkey, &Account) -> bool);
+
+    /// Run `update` on each account `select` returns true for
+    ///
+    /// Unselected accounts are never borrowed mutably, so a copy-on-write
+    /// store leaves them shared.
+    fn update_where(
+        &mut self,
+        select: &mut dyn FnMut(&Pubkey, &Account) -> bool,
+        update: &mut dyn FnMut(&Pubkey, &mut Account),
+    );
+
+    /// Every account, in an order specific to the store
+    fn iter(&self) -> Box<dyn Iterator<Item = (&Pubkey, &Account)> + '_>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Independent copy of the whole store; later writes to either side are
+    /// not seen by the other
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl AccountStore for HashMap<Pubkey, Account> {
+    fn get(&self, pubkey: &Pubkey) -> Option<&Account> {
+        HashMap::get(self, pubkey)
+    }
+
+    fn get_mut(&mut self, pubkey: &Pubkey) -> Option<&mut Account> {
+        HashMap::get_mut(self, pubkey)
+    }
+
+    fn insert(&mut self, pubkey: Pubkey, account: Account) -> Option<Account> {
+        HashMap::insert(self, pubkey, account)
+    }
+
+    fn remove(&mut self, pubkey: &Pubkey) -> Option<Account> {
+        HashMap::remove(self, pubkey)
+    }
+
+    fn retain(&mut self, keep: &mut dyn FnMut(&Pubkey, &Account) -> bool) {
+        HashMap::retain(self, |pubkey, account| keep(pubkey, account))
+    }
+
+    fn update_where(
+        &mut self,
+        select: &mut dyn FnMut(&Pubkey, &Account) -> bool,
+        update: &mut dyn FnMut(&Pubkey, &mut Account),
+    ) {
+        for (pubkey, account) in HashMap::iter_mut(self) {
+            if select(pubkey, account) {
+                update(pubkey, account);
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&Pubkey, &Account)> + '_> {
+        Box::new(HashMap::iter(self))
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Accounts held in a vector of shared slots behind a pubkey index
+///
+/// Snapshots share every account with the original; an account is only
+/// copied when one side first writes to it.
+#[derive(Debug, Clone, Default)]
+pub struct CowAccountStore {
+    index: HashMap<Pubkey, usize>,
+    slots: Vec<(Pubkey, Arc<Account>)>,
+}
+
+impl AccountStore for CowAccountStore {
+    fn get(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.index.get(pubkey).map(|&slot| &*self.slots[slot].1)
+    }
+
+    fn get_mut(&mut self, pubkey: &Pubkey) -> Option<&mut Account> {
+        let slot = *self.index.get(pubkey)?;
+        Some(Arc::make_mut(&mut self.slots[slot].1))
+    }
+
+    fn insert(&mut self, pubkey: Pubkey, account: Account) -> Option<Account> {
+        let account = Arc::new(account);
+        match self.index.get(&pubkey) {
+            Some(&slot) => {
+                let previous = std::mem::replace(&mut self.slots[slot].1, account);
+                Some(Arc::unwrap_or_clone(previous))
+            }
+            None => {
+                self.index.insert(pubkey, self.slots.len());
+                self.slots.push((pubkey, account));
+                None
+            }
+        }
+    }
+
+    fn remove(&mut self, pubkey: &Pubkey) -> Option<Account> {
+        let slot = self.index.remove(pubkey)?;
+        let (_, account) = self.slots.swap_remove(slot);
+        if let Some((moved, _)) = self.slots.get(slot) {
+            self.index.insert(*moved, slot);
+        }
+        Some(Arc::unwrap_or_clone(account))
+    }
+
+    fn retain(&mut self, keep: &mut dyn FnMut(&Pubkey, &Account) -> bool) {
+        self.slots.retain(|(pubkey, account)| keep(pubkey, account));
+        self.index = self.slots.iter().enumerate().map(|(slot, (pubkey, _))| (*pubkey, slot)).collect();
+    }
+
+    fn update_where(
+        &mut self,
+        select: &mut dyn FnMut(&Pubkey, &Account) -> bool,
+        update: &mut dyn FnMut(&Pubkey, &mut Account),
+    ) {
+        for (pubkey, account) in &mut self.slots {
+            if select(pubkey, account) {
+                update(pubkey, Arc::make_mut(account));
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&Pubkey, &Account)> + '_> {
+        Box::new(self.slots.iter().map(|(pubkey, account)| (pubkey, &**account)))
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrated_runtime::IntegratedRuntime;
+    use crate::system_program::SYSTEM_PROGRAM_ID;
+    use crate::types::{GenesisConfig, RuntimeConfig};
+
+    #[test]
+    fn test_stores_agree_on_transfer() {
+        let mut plain = IntegratedRuntime::new().unwrap();
+        let mut cow = IntegratedRuntime::<CowAccountStore>::with_store(&RuntimeConfig::default(), &GenesisConfig::default()).unwrap();
+        let tx = plain.create_test_transfer(&Pubkey::new([1u8; 32]), &Pubkey::new([2u8; 32]), 1_000).unwrap();
+
+        let plain_result = plain.execute_solana_transaction_parsed(&tx).unwrap();
+        let cow_result = cow.execute_solana_transaction_parsed(&tx).unwrap();
+        assert!(plain_result.success && cow_result.success);
+        assert_eq!(plain_result.compute_units_consumed, cow_result.compute_units_consumed);
+        assert_eq!(plain_result.logs, cow_result.logs);
+
+        let mut plain_accounts: Vec<_> = plain.accounts_iter().collect();
+        let mut cow_accounts: Vec<_> = cow.accounts_iter().collect();
+        plain_accounts.sort_by_key(|(pubkey, _)| pubkey.0);
+        cow_accounts.sort_by_key(|(pubkey, _)| pubkey.0);
+        assert_eq!(plain_accounts, cow_accounts);
+    }
+
+    #[test]
+    fn test_cow_snapshot_is_independent() {
+        let key = Pubkey::new([3u8; 32]);
+        let mut store = CowAccountStore::default();
+        store.insert(key, Account::new(100, vec![], SYSTEM_PROGRAM_ID));
+
+        let snapshot = store.snapshot();
+        store.get_mut(&key).unwrap().lamports = 50;
+        let other = Pubkey::new([4u8; 32]);
+        store.insert(other, Account::new(70, vec![], SYSTEM_PROGRAM_ID));
+        store.insert(Pubkey::new([5u8; 32]), Account::new(60, vec![], SYSTEM_PROGRAM_ID));
+        assert_eq!(store.remove(&other).map(|account| account.lamports), Some(70));
+        assert_eq!(store.get(&Pubkey::new([5u8; 32])).map(|account| account.lamports), Some(60));
+        store.retain(&mut |_, account| account.lamports > 60);
+
+        assert!(store.is_empty());
+        assert_eq!(snapshot.get(&key).map(|account| account.lamports), Some(100));
+    }
+
+    #[test]
+    fn test_cow_update_copies_only_selected_accounts() {
+        let (charged, untouched) = (Pubkey::new([6u8; 32]), Pubkey::new([7u8; 32]));
+        let mut store = CowAccountStore::default();
+        store.insert(charged, Account::new(100, vec![], SYSTEM_PROGRAM_ID));
+        store.insert(untouched, Account::new(200, vec![], SYSTEM_PROGRAM_ID));
+        let snapshot = store.snapshot();
+
+        store.update_where(&mut |pubkey, _| *pubkey == charged, &mut |_, account| account.lamports -= 10);
+        store.retain(&mut |_, account| account.lamports > 0);
+
+        let shared = |pubkey: &Pubkey| Arc::ptr_eq(&store.slots[store.index[pubkey]].1, &snapshot.slots[snapshot.index[pubkey]].1);
+        assert!(!shared(&charged));
+        assert!(shared(&untouched));
+        assert_eq!(store.get(&charged).map(|account| account.lamports), Some(90));
+        assert_eq!(snapshot.get(&charged).map(|account| account.lamports), Some(100));
+    }
+}
@@ -0,0 +1,211 @@
+/// Address Lookup Table Program
+/// Creates and extends the on-chain address tables that v0 transactions index into
+
+use crate::prelude::*;
+use crate::{Result, TerminatorError};
+use crate::system_program::SYSTEM_PROGRAM_ID;
+use crate::types::{Account, AccountMeta, ExecutionContext, InstructionAccounts, Pubkey};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Address lookup table program ID (AddressLookupTab1e1111111111111111111111111)
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: [u8; 32] = [
+    2, 119, 166, 175, 151, 51, 155, 122, 200, 141, 24, 146, 201, 4, 70, 245,
+    0, 2, 48, 146, 102, 246, 46, 83, 193, 24, 36, 73, 130, 0, 0, 0,
+];
+
+/// Most addresses a table can hold, since v0 messages index them with a u8
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// Lookup table instructions
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum LookupTableInstruction {
+    /// Initialize an empty table owned by this program
+    /// Accounts:
+    /// [0] Uninitialized table account (writable)
+    /// [1] Table authority
+    CreateLookupTable,
+
+    /// Append addresses to an active table
+    /// Accounts:
+    /// [0] Table account (writable)
+    /// [1] Table authority (signer)
+    ExtendLookupTable { new_addresses: Vec<[u8; 32]> },
+}
+
+/// Lookup table account layout, stored Borsh-encoded in the account data
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct AddressLookupTable {
+    /// Key allowed to extend the table
+    pub authority: Option<[u8; 32]>,
+    /// Slot the table was deactivated at, `u64::MAX` while active
+    pub deactivation_slot: u64,
+    pub addresses: Vec<[u8; 32]>,
+}
+
+impl AddressLookupTable {
+    /// Empty, active table
+    pub fn new(authority: [u8; 32]) -> Self {
+        Self {
+            authority: Some(authority),
+            deactivation_slot: u64::MAX,
+            addresses: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.deactivation_slot == u64::MAX
+    }
+
+    /// Decode a table from account data
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        Self::try_from_slice(data)
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid lookup table data: {}", e)))
+    }
+
+    /// Encode the table as account data
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        borsh::to_vec(self)
+            .map_err(|e| TerminatorError::SerializationError(format!("Failed to serialize lookup table: {}", e)))
+    }
+}
+
+/// Address lookup table program processor
+pub struct AddressLookupTableProgram;
+
+impl AddressLookupTableProgram {
+    /// Process a lookup table instruction
+    ///
+    /// `account_metas` describe the instruction's accounts, parallel to `account_infos`.
+    pub fn process_instruction(
+        instruction_data: &[u8],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        let instruction = LookupTableInstruction::try_from_slice(instruction_data)
+            .map_err(|_| TerminatorError::SerializationError("Invalid lookup table instruction".to_string()))?;
+
+        if account_infos.len() < 2 || account_metas.len() < 2 {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "Lookup table instructions require 2 accounts".to_string()
+            ));
+        }
+
+        context.log(format!("Processing lookup table instruction: {:?}", instruction));
+
+        if !account_metas[1].is_signer {
+            return Err(TerminatorError::ProgramError("Lookup table authority must sign".to_string()));
+        }
+
+        match instruction {
+            LookupTableInstruction::CreateLookupTable => {
+                Self::create_lookup_table(account_metas[1].pubkey, &mut account_infos[0], context)
+            }
+            LookupTableInstruction::ExtendLookupTable { new_addresses } => {
+                Self::extend_lookup_table(account_metas[1].pubkey, &mut account_infos[0], new_addresses, context)
+            }
+        }
+    }
+
+    fn create_lookup_table(
+        authority: Pubkey,
+        table_account: &mut Account,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if !table_account.data.is_empty() || table_account.owner != SYSTEM_PROGRAM_ID {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "account already in use".to_string()
+            ));
+        }
+
+        table_account.data = AddressLookupTable::new(authority.0).serialize()?;
+        table_account.owner = ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+
+        context.consume_compute_units(750);
+        Ok(())
+    }
+
+    fn extend_lookup_table(
+        authority: Pubkey,
+        table_account: &mut Account,
+        new_addresses: Vec<[u8; 32]>,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if table_account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+            return Err(TerminatorError::ProgramError(
+                "Lookup table account is not owned by the lookup table program".to_string()
+            ));
+        }
+
+        let mut table = AddressLookupTable::deserialize(&table_account.data)?;
+        if !table.is_active() {
+            return Err(TerminatorError::ProgramError("Lookup table is deactivated".to_string()));
+        }
+        if table.authority != Some(authority.0) {
+            return Err(TerminatorError::ProgramError("Incorrect lookup table authority".to_string()));
+        }
+        if new_addresses.is_empty() {
+            return Err(TerminatorError::ProgramError("Must extend with at least one address".to_string()));
+        }
+        if table.addresses.len() + new_addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+            return Err(TerminatorError::ProgramError(format!(
+                "Lookup table cannot hold more than {} addresses",
+                LOOKUP_TABLE_MAX_ADDRESSES
+            )));
+        }
+
+        table.addresses.extend(new_addresses);
+        table_account.data = table.serialize()?;
+
+        context.consume_compute_units(750);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extend(table: &mut Account, authority: Pubkey, new_addresses: Vec<[u8; 32]>) -> Result<()> {
+        extend_signed_by(table, authority, true, new_addresses)
+    }
+
+    fn extend_signed_by(
+        table: &mut Account,
+        authority: Pubkey,
+        authority_signs: bool,
+        new_addresses: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let data = borsh::to_vec(&LookupTableInstruction::ExtendLookupTable { new_addresses }).unwrap();
+        let mut authority_account = Account::new(0, Vec::new(), SYSTEM_PROGRAM_ID);
+        let metas = [
+            AccountMeta { pubkey: Pubkey::new([9u8; 32]), is_signer: false, is_writable: true },
+            AccountMeta { pubkey: authority, is_signer: authority_signs, is_writable: false },
+        ];
+        AddressLookupTableProgram::process_instruction(
+            &data,
+            &metas,
+            &mut InstructionAccounts::new(vec![table, &mut authority_account]),
+            &mut ExecutionContext::new(200_000),
+        )
+    }
+
+    #[test]
+    fn test_extend_requires_authority_and_capacity() {
+        let authority = Pubkey::new([1u8; 32]);
+        let mut table = Account::new(
+            1_000,
+            AddressLookupTable::new(authority.0).serialize().unwrap(),
+            ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        );
+
+        assert!(extend(&mut table, Pubkey::new([2u8; 32]), vec![[3u8; 32]]).is_err());
+        let unsigned = extend_signed_by(&mut table, authority, false, vec![[3u8; 32]]);
+        assert!(matches!(unsigned, Err(TerminatorError::ProgramError(message)) if message.contains("must sign")));
+        assert!(extend(&mut table, authority, vec![[3u8; 32]; LOOKUP_TABLE_MAX_ADDRESSES + 1]).is_err());
+
+        extend(&mut table, authority, vec![[3u8; 32], [4u8; 32]]).unwrap();
+        let stored = AddressLookupTable::deserialize(&table.data).unwrap();
+        assert_eq!(stored.addresses, vec![[3u8; 32], [4u8; 32]]);
+    }
+}
@@ -0,0 +1,412 @@
+/// Pure-Rust sBPF Interpreter
+/// Core ALU, jump, memory and exit instructions plus logging syscalls, with no native dependencies
+
+use crate::{Result, TerminatorError};
+
+/// Virtual address of the program's bytecode (readonly)
+pub const MM_PROGRAM_START: u64 = 0x1_0000_0000;
+/// Virtual address of the stack; r10 starts at its end
+pub const MM_STACK_START: u64 = 0x2_0000_0000;
+/// Virtual address of the program input (the instruction data)
+pub const MM_INPUT_START: u64 = 0x4_0000_0000;
+/// Bytes of stack available to a program
+pub const STACK_SIZE: usize = 4096;
+
+/// Syscall numbers are the murmur3 hash of the symbol name
+pub const SYSCALL_SOL_LOG: u32 = 0x207559bd;
+pub const SYSCALL_SOL_LOG_64: u32 = 0x5c2a3178;
+pub const SYSCALL_ABORT: u32 = 0xb6fc1a11;
+
+/// Compute units charged for each syscall, on top of the call instruction
+const SYSCALL_COST: u64 = 100;
+
+const INSTRUCTION_SIZE: usize = 8;
+
+// Instruction classes (low three bits of the opcode)
+const CLASS_LD: u8 = 0x00;
+const CLASS_LDX: u8 = 0x01;
+const CLASS_ST: u8 = 0x02;
+const CLASS_STX: u8 = 0x03;
+const CLASS_ALU: u8 = 0x04;
+const CLASS_JMP: u8 = 0x05;
+const CLASS_ALU64: u8 = 0x07;
+
+/// Set when the second operand is a register rather than the immediate
+const SOURCE_REG: u8 = 0x08;
+/// Addressing mode of loads and stores (top three bits); only `MODE_MEM` is supported
+const MODE_MASK: u8 = 0xe0;
+const MODE_MEM: u8 = 0x60;
+/// `lddw`: load a 64-bit immediate spread over two instruction slots
+const LD_DW_IMM: u8 = 0x18;
+
+struct Instruction {
+    opcode: u8,
+    dst: usize,
+    src: usize,
+    offset: i16,
+    imm: i32,
+}
+
+fn decode(text: &[u8], pc: usize) -> Result<Instruction> {
+    let bytes = pc.checked_mul(INSTRUCTION_SIZE)
+        .and_then(|at| text.get(at..at.checked_add(INSTRUCTION_SIZE)?))
+        .ok_or_else(|| vm_error(format!("jumped out of the program to instruction {}", pc)))?;
+    let dst = (bytes[1] & 0x0f) as usize;
+    let src = (bytes[1] >> 4) as usize;
+    if dst > 10 || src > 10 {
+        return Err(vm_error(format!("invalid register at instruction {}", pc)));
+    }
+    Ok(Instruction {
+        opcode: bytes[0],
+        dst,
+        src,
+        offset: i16::from_le_bytes([bytes[2], bytes[3]]),
+        imm: i32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+    })
+}
+
+fn vm_error(message: String) -> TerminatorError {
+    TerminatorError::BpfVmError(message)
+}
+
+fn access_violation(address: u64, len: usize) -> TerminatorError {
+    vm_error(format!("access violation at 0x{:x} ({} bytes)", address, len))
+}
+
+/// Registers and memory of one program run
+struct Vm<'a> {
+    registers: [u64; 11],
+    text: &'a [u8],
+    stack: Vec<u8>,
+    input: Vec<u8>,
+}
+
+impl Vm<'_> {
+    /// The `len` writable bytes at virtual address `address`, on the stack or in the input
+    fn writable(&mut self, address: u64, len: usize) -> Result<&mut [u8]> {
+        let (region, start): (&mut [u8], u64) = if address >= MM_INPUT_START {
+            (&mut self.input, MM_INPUT_START)
+        } else if address >= MM_STACK_START {
+            (&mut self.stack, MM_STACK_START)
+        } else {
+            return Err(access_violation(address, len));
+        };
+        // An offset past usize (on 32-bit targets) can't be inside the region
+        let offset = usize::try_from(address - start).map_err(|_| access_violation(address, len))?;
+        region.get_mut(offset..offset.saturating_add(len)).ok_or_else(|| access_violation(address, len))
+    }
+
+    fn load(&mut self, address: u64, len: usize) -> Result<u64> {
+        let source: &[u8] = if (MM_PROGRAM_START..MM_STACK_START).contains(&address) {
+            let offset = usize::try_from(address - MM_PROGRAM_START).map_err(|_| access_violation(address, len))?;
+            self.text.get(offset..offset.saturating_add(len)).ok_or_else(|| access_violation(address, len))?
+        } else {
+            self.writable(address, len)?
+        };
+        let mut bytes = [0u8; 8];
+        bytes[..len].copy_from_slice(source);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn store(&mut self, address: u64, len: usize, value: u64) -> Result<()> {
+        self.writable(address, len)?.copy_from_slice(&value.to_le_bytes()[..len]);
+        Ok(())
+    }
+
+    /// Bytes for a syscall argument, which may point anywhere readable
+    fn read_bytes(&mut self, address: u64, len: u64) -> Result<Vec<u8>> {
+        (0..len).map(|i| self.load(address.wrapping_add(i), 1).map(|byte| byte as u8)).collect()
+    }
+
+    fn syscall(&mut self, number: u32, logs: &mut Vec<String>) -> Result<()> {
+        let [_, r1, r2, r3, r4, r5, ..] = self.registers;
+        match number {
+            SYSCALL_SOL_LOG => {
+                let message = self.read_bytes(r1, r2)?;
+                logs.push(format!("Program log: {}", String::from_utf8_lossy(&message)));
+            }
+            SYSCALL_SOL_LOG_64 => {
+                logs.push(format!("Program log: 0x{:x}, 0x{:x}, 0x{:x}, 0x{:x}, 0x{:x}", r1, r2, r3, r4, r5));
+            }
+            SYSCALL_ABORT => return Err(TerminatorError::ProgramError("Program aborted".to_string())),
+            _ => return Err(vm_error(format!("unsupported syscall 0x{:08x}", number))),
+        }
+        self.registers[0] = 0;
+        Ok(())
+    }
+}
+
+/// Apply an ALU operation; `None` for an unknown operation or division by zero
+fn alu(op: u8, dst: u64, src: u64, is_64: bool) -> Option<u64> {
+    let result = if is_64 {
+        match op {
+            0x00 => dst.wrapping_add(src),
+            0x10 => dst.wrapping_sub(src),
+            0x20 => dst.wrapping_mul(src),
+            0x30 => dst.checked_div(src)?,
+            0x40 => dst | src,
+            0x50 => dst & src,
+            0x60 => dst.wrapping_shl(src as u32),
+            0x70 => dst.wrapping_shr(src as u32),
+            0x80 => (dst as i64).wrapping_neg() as u64,
+            0x90 => dst.checked_rem(src)?,
+            0xa0 => dst ^ src,
+            0xb0 => src,
+            0xc0 => (dst as i64).wrapping_shr(src as u32) as u64,
+            _ => return None,
+        }
+    } else {
+        let (dst, src) = (dst as u32, src as u32);
+        (match op {
+            0x00 => dst.wrapping_add(src),
+            0x10 => dst.wrapping_sub(src),
+            0x20 => dst.wrapping_mul(src),
+            0x30 => dst.checked_div(src)?,
+            0x40 => dst | src,
+            0x50 => dst & src,
+            0x60 => dst.wrapping_shl(src),
+            0x70 => dst.wrapping_shr(src),
+            0x80 => (dst as i32).wrapping_neg() as u32,
+            0x90 => dst.checked_rem(src)?,
+            0xa0 => dst ^ src,
+            0xb0 => src,
+            0xc0 => (dst as i32).wrapping_shr(src) as u32,
+            _ => return None,
+        }) as u64
+    };
+    Some(result)
+}
+
+/// Whether a conditional jump is taken; `None` for an unknown condition
+fn condition(op: u8, dst: u64, src: u64) -> Option<bool> {
+    Some(match op {
+        0x00 => true,
+        0x10 => dst == src,
+        0x20 => dst > src,
+        0x30 => dst >= src,
+        0x40 => dst & src != 0,
+        0x50 => dst != src,
+        0x60 => (dst as i64) > (src as i64),
+        0x70 => (dst as i64) >= (src as i64),
+        0xa0 => dst < src,
+        0xb0 => dst <= src,
+        0xc0 => (dst as i64) < (src as i64),
+        0xd0 => (dst as i64) <= (src as i64),
+        _ => return None,
+    })
+}
+
+/// Byte width of a load or store
+fn access_size(opcode: u8) -> usize {
+    match opcode & 0x18 {
+        0x00 => 4,
+        0x08 => 2,
+        0x10 => 1,
+        _ => 8,
+    }
+}
+
+/// Run `text` (raw sBPF instructions) with `input` mapped at `MM_INPUT_START`
+/// and passed in r1, returning r0
+///
+/// Each instruction costs one unit drawn from `remaining_compute_units`, and
+/// each syscall a further `SYSCALL_COST`; running out halts with a
+/// `ComputeBudgetExceeded` error. Log messages are appended to `logs` as
+/// they are emitted.
+pub fn execute(
+    text: &[u8],
+    input: &[u8],
+    remaining_compute_units: &mut u64,
+    logs: &mut Vec<String>,
+) -> Result<u64> {
+    let mut vm = Vm {
+        registers: [0; 11],
+        text,
+        stack: vec![0; STACK_SIZE],
+        input: input.to_vec(),
+    };
+    vm.registers[1] = MM_INPUT_START;
+    vm.registers[10] = MM_STACK_START + STACK_SIZE as u64;
+
+    let mut pc = 0usize;
+    loop {
+        if *remaining_compute_units == 0 {
+            return Err(TerminatorError::ComputeBudgetExceeded);
+        }
+        *remaining_compute_units -= 1;
+
+        let insn = decode(text, pc)?;
+        let at = pc;
+        let unsupported = || vm_error(format!("unsupported opcode 0x{:02x} at instruction {}", insn.opcode, at));
+        let operand = if insn.opcode & SOURCE_REG != 0 { vm.registers[insn.src] } else { insn.imm as i64 as u64 };
+        let address = |base: u64| base.wrapping_add(insn.offset as i64 as u64);
+        let op = insn.opcode & 0xf0;
+        pc += 1;
+
+        match insn.opcode & 0x07 {
+            CLASS_ALU64 | CLASS_ALU => {
+                if insn.dst == 10 {
+                    return Err(vm_error(format!("write to the frame pointer at instruction {}", at)));
+                }
+                let is_64 = insn.opcode & 0x07 == CLASS_ALU64;
+                if matches!(op, 0x30 | 0x90) && (if is_64 { operand } else { operand as u32 as u64 }) == 0 {
+                    return Err(TerminatorError::ProgramError("Division by zero".to_string()));
+                }
+                vm.registers[insn.dst] = alu(op, vm.registers[insn.dst], operand, is_64).ok_or_else(unsupported)?;
+            }
+            CLASS_JMP => match op {
+                0x80 => {
+                    // Syscalls are charged on top of the call instruction
+                    if *remaining_compute_units < SYSCALL_COST {
+                        *remaining_compute_units = 0;
+                        return Err(TerminatorError::ComputeBudgetExceeded);
+                    }
+                    *remaining_compute_units -= SYSCALL_COST;
+                    vm.syscall(insn.imm as u32, logs)?;
+                }
+                0x90 => return Ok(vm.registers[0]),
+                _ => {
+                    if condition(op, vm.registers[insn.dst], operand).ok_or_else(unsupported)? {
+                        pc = pc.checked_add_signed(insn.offset as isize).ok_or_else(|| {
+                            vm_error(format!("jump before the start of the program at instruction {}", at))
+                        })?;
+                    }
+                }
+            },
+            CLASS_LD if insn.opcode == LD_DW_IMM => {
+                let high = decode(text, pc)?.imm as u32 as u64;
+                vm.registers[insn.dst] = (insn.imm as u32 as u64) | (high << 32);
+                pc += 1;
+            }
+            CLASS_LDX if insn.opcode & MODE_MASK == MODE_MEM => {
+                vm.registers[insn.dst] = vm.load(address(vm.registers[insn.src]), access_size(insn.opcode))?;
+            }
+            CLASS_ST if insn.opcode & MODE_MASK == MODE_MEM => {
+                vm.store(address(vm.registers[insn.dst]), access_size(insn.opcode), insn.imm as i64 as u64)?;
+            }
+            CLASS_STX if insn.opcode & MODE_MASK == MODE_MEM => {
+                let value = vm.registers[insn.src];
+                vm.store(address(vm.registers[insn.dst]), access_size(insn.opcode), value)?;
+            }
+            _ => return Err(unsupported()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble one instruction
+    fn insn(opcode: u8, dst: u8, src: u8, offset: i16, imm: i32) -> [u8; 8] {
+        let mut bytes = [opcode, dst | (src << 4), 0, 0, 0, 0, 0, 0];
+        bytes[2..4].copy_from_slice(&offset.to_le_bytes());
+        bytes[4..8].copy_from_slice(&imm.to_le_bytes());
+        bytes
+    }
+
+    fn program(instructions: &[[u8; 8]]) -> Vec<u8> {
+        instructions.concat()
+    }
+
+    fn run(text: &[u8], input: &[u8]) -> (Result<u64>, u64, Vec<String>) {
+        let mut remaining = 10_000;
+        let mut logs = Vec::new();
+        let result = execute(text, input, &mut remaining, &mut logs);
+        (result, 10_000 - remaining, logs)
+    }
+
+    #[test]
+    fn test_alu_and_loop() {
+        // r0 = sum of 1..=10, counting r1 down to zero
+        let text = program(&[
+            insn(0xb7, 0, 0, 0, 0),   // mov64 r0, 0
+            insn(0xb7, 1, 0, 0, 10),  // mov64 r1, 10
+            insn(0x0f, 0, 1, 0, 0),   // add64 r0, r1
+            insn(0x17, 1, 0, 0, 1),   // sub64 r1, 1
+            insn(0x55, 1, 0, -3, 0),  // jne r1, 0, -3
+            insn(0x27, 0, 0, 0, 2),   // mul64 r0, 2
+            insn(0x95, 0, 0, 0, 0),   // exit
+        ]);
+        let (result, units, _) = run(&text, &[]);
+        assert_eq!(result.unwrap(), 110);
+        assert_eq!(units, 2 + 3 * 10 + 2);
+
+        // 32-bit ops wrap and zero the upper half
+        let text = program(&[
+            insn(0x18, 0, 0, 0, -1),  // lddw r0, 0x1_ffffffff
+            insn(0x00, 0, 0, 0, 1),
+            insn(0x04, 0, 0, 0, 1),   // add32 r0, 1
+            insn(0x95, 0, 0, 0, 0),
+        ]);
+        assert_eq!(run(&text, &[]).0.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_jump_out_of_program_is_an_error() {
+        for offset in [-2, i16::MIN, i16::MAX] {
+            let text = program(&[
+                insn(0x05, 0, 0, offset, 0), // ja offset
+                insn(0x95, 0, 0, 0, 0),
+            ]);
+            let (result, _, _) = run(&text, &[]);
+            assert!(matches!(result, Err(TerminatorError::BpfVmError(_))), "offset {}: {:?}", offset, result);
+        }
+    }
+
+    #[test]
+    fn test_memory_and_input() {
+        // Store the input's first u64 on the stack, read it back and add its second byte
+        let text = program(&[
+            insn(0x79, 2, 1, 0, 0),   // ldxdw r2, [r1]
+            insn(0x7b, 10, 2, -8, 0), // stxdw [r10-8], r2
+            insn(0x79, 0, 10, -8, 0), // ldxdw r0, [r10-8]
+            insn(0x71, 3, 1, 1, 0),   // ldxb r3, [r1+1]
+            insn(0x0f, 0, 3, 0, 0),   // add64 r0, r3
+            insn(0x95, 0, 0, 0, 0),
+        ]);
+        let input = 0x0102u64.to_le_bytes();
+        assert_eq!(run(&text, &input).0.unwrap(), 0x0103);
+
+        // Reading past the input is an access violation
+        let (result, _, _) = run(&text, &[1, 2]);
+        assert!(matches!(result, Err(TerminatorError::BpfVmError(msg)) if msg.starts_with("access violation")));
+
+        // So is an address too far into a region to be an offset on any target
+        let text = program(&[
+            insn(0x18, 2, 0, 0, -8),  // lddw r2, u64::MAX - 7
+            insn(0x00, 0, 0, 0, -1),
+            insn(0x79, 0, 2, 0, 0),   // ldxdw r0, [r2]
+            insn(0x95, 0, 0, 0, 0),
+        ]);
+        let (result, _, _) = run(&text, &[]);
+        assert!(matches!(result, Err(TerminatorError::BpfVmError(msg)) if msg.starts_with("access violation")));
+    }
+
+    #[test]
+    fn test_sol_log_then_abort() {
+        // The message lives after the code, addressed through the program region
+        let message = b"hello";
+        let message_at = MM_PROGRAM_START + 6 * 8;
+        let mut text = program(&[
+            insn(0x18, 1, 0, 0, message_at as u32 as i32), // lddw r1, message
+            insn(0x00, 0, 0, 0, (message_at >> 32) as i32),
+            insn(0xb7, 2, 0, 0, message.len() as i32),      // mov64 r2, len
+            insn(0x85, 0, 0, 0, SYSCALL_SOL_LOG as i32),    // call sol_log_
+            insn(0x85, 0, 0, 0, SYSCALL_ABORT as i32),      // call abort
+            insn(0x95, 0, 0, 0, 0),
+        ]);
+        text.extend_from_slice(message);
+
+        let (result, units, logs) = run(&text, &[]);
+        assert!(matches!(result, Err(TerminatorError::ProgramError(_))));
+        assert_eq!(logs, vec!["Program log: hello".to_string()]);
+        assert_eq!(units, 4 + 2 * SYSCALL_COST);
+
+        // Running out of units stops the program where it is
+        let mut remaining = 3;
+        let result = execute(&text, &[], &mut remaining, &mut Vec::new());
+        assert!(matches!(result, Err(TerminatorError::ComputeBudgetExceeded)));
+        assert_eq!(remaining, 0);
+    }
+}
@@ -0,0 +1,155 @@
+/// Compute Budget Program
+/// Parses the ComputeBudget instructions that set a transaction's CU limit and priority fee
+
+use crate::prelude::*;
+use crate::{Result, TerminatorError};
+use crate::solana_format::SolanaMessage;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Compute budget program ID (ComputeBudget111111111111111111111111111111)
+pub const COMPUTE_BUDGET_PROGRAM_ID: [u8; 32] = [
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231,
+    188, 140, 229, 187, 197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+];
+
+/// Compute units granted to each instruction when no limit is requested
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Upper bound on a transaction's compute unit limit
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Compute budget instructions
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum ComputeBudgetInstruction {
+    /// Deprecated combined units/fee request
+    RequestUnitsDeprecated { units: u32, additional_fee: u32 },
+    /// Request a larger heap frame, in bytes
+    RequestHeapFrame(u32),
+    /// Set the transaction's compute unit limit
+    SetComputeUnitLimit(u32),
+    /// Set the priority fee, in micro-lamports per compute unit
+    SetComputeUnitPrice(u64),
+    /// Cap the total size of loaded account data
+    SetLoadedAccountsDataSizeLimit(u32),
+}
+
+/// Compute limits and pricing requested by a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudgetLimits {
+    pub compute_unit_limit: u32,
+    /// Priority fee in micro-lamports per compute unit
+    pub compute_unit_price: u64,
+}
+
+impl ComputeBudgetLimits {
+    /// Collect the compute budget instructions in `message`
+    ///
+    /// Without a `SetComputeUnitLimit`, every other instruction is granted
+    /// the default limit. Duplicate or malformed instructions are an error.
+    pub fn from_message(message: &SolanaMessage) -> Result<Self> {
+        let mut compute_unit_limit = None;
+        let mut compute_unit_price = None;
+        let mut other_instructions = 0u32;
+
+        for instruction in &message.instructions {
+            let program_id = message.account_keys
+                .get(instruction.program_id_index as usize)
+                .map(|key| key.0);
+            if program_id != Some(COMPUTE_BUDGET_PROGRAM_ID) {
+                other_instructions += 1;
+                continue;
+            }
+
+            let parsed = ComputeBudgetInstruction::try_from_slice(&instruction.data)
+                .map_err(|e| TerminatorError::ProgramError(format!("Invalid compute budget instruction: {}", e)))?;
+            match parsed {
+                ComputeBudgetInstruction::SetComputeUnitLimit(units) => {
+                    set_once(&mut compute_unit_limit, units)?;
+                }
+                ComputeBudgetInstruction::SetComputeUnitPrice(price) => {
+                    set_once(&mut compute_unit_price, price)?;
+                }
+                _ => {}
+            }
+        }
+
+        let default_limit = other_instructions.saturating_mul(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT);
+        Ok(ComputeBudgetLimits {
+            compute_unit_limit: compute_unit_limit.unwrap_or(default_limit).min(MAX_COMPUTE_UNIT_LIMIT),
+            compute_unit_price: compute_unit_price.unwrap_or(0),
+        })
+    }
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(TerminatorError::ProgramError("Duplicate compute budget instruction".to_string()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_format::{CompiledInstruction, MessageHeader, SolanaHash, SolanaPubkey, SolanaTransactionParser};
+    use crate::types::FeeCalculator;
+
+    #[test]
+    fn test_compute_budget_limits() {
+        let instruction = |data: Vec<u8>| CompiledInstruction { program_id_index: 1, accounts: vec![], data };
+        let mut message = SolanaMessage {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![SolanaPubkey::new([1u8; 32]), SolanaPubkey::new(COMPUTE_BUDGET_PROGRAM_ID)],
+            recent_blockhash: SolanaHash([0u8; 32]),
+            instructions: vec![],
+        };
+        assert_eq!(ComputeBudgetLimits::from_message(&message).unwrap().compute_unit_limit, 0);
+
+        message.instructions = vec![
+            instruction(borsh::to_vec(&ComputeBudgetInstruction::SetComputeUnitLimit(50_000)).unwrap()),
+            instruction(borsh::to_vec(&ComputeBudgetInstruction::SetComputeUnitPrice(1_000)).unwrap()),
+        ];
+        let limits = ComputeBudgetLimits::from_message(&message).unwrap();
+        assert_eq!(limits.compute_unit_limit, 50_000);
+        assert_eq!(limits.compute_unit_price, 1_000);
+
+        message.instructions.push(instruction(borsh::to_vec(&ComputeBudgetInstruction::SetComputeUnitPrice(5)).unwrap()));
+        assert!(ComputeBudgetLimits::from_message(&message).is_err());
+    }
+
+    #[test]
+    fn test_fee_calculation() {
+        let fees = FeeCalculator::new(5_000);
+        let mut message = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([2u8; 32]), 1_000, SolanaHash([0u8; 32]),
+        ).message;
+
+        // Signature fee only: no compute budget instructions means no priority fee
+        let limits = ComputeBudgetLimits::from_message(&message).unwrap();
+        assert_eq!(limits.compute_unit_limit, DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT);
+        assert_eq!(fees.calculate_fee(&message, &limits), 5_000);
+
+        // Priority fee only
+        let priority = ComputeBudgetLimits { compute_unit_limit: 200_000, compute_unit_price: 10_000 };
+        assert_eq!(FeeCalculator::new(0).calculate_fee(&message, &priority), 2_000);
+
+        // Signatures plus a requested limit and price
+        message.header.num_required_signatures = 2;
+        message.account_keys.push(SolanaPubkey::new(COMPUTE_BUDGET_PROGRAM_ID));
+        let budget_index = (message.account_keys.len() - 1) as u8;
+        for ix in [ComputeBudgetInstruction::SetComputeUnitLimit(300_000), ComputeBudgetInstruction::SetComputeUnitPrice(2_000)] {
+            message.instructions.push(CompiledInstruction {
+                program_id_index: budget_index,
+                accounts: vec![],
+                data: borsh::to_vec(&ix).unwrap(),
+            });
+        }
+        let limits = ComputeBudgetLimits::from_message(&message).unwrap();
+        assert_eq!(fees.calculate_fee(&message, &limits), 2 * 5_000 + 600);
+    }
+}
@@ -1,9 +1,217 @@
-use crate::{Result};
+use crate::{Result, TerminatorError};
+use crate::integrated_runtime::{replay, IntegratedRuntime};
+use crate::solana_format::SolanaTransaction;
 use crate::types::*;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A recorded transaction with the pre-state it ran against and the effects
+/// a conforming runtime must reproduce
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Wire-format transaction, base64-encoded
+    pub transaction: String,
+    pub pre_accounts: Vec<(Pubkey, Account)>,
+    pub effects: FixtureEffects,
+}
+
+/// Observable outcome of executing a fixture's transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureEffects {
+    pub success: bool,
+    pub compute_units_consumed: u64,
+    pub error: Option<TransactionError>,
+    /// Post-state of every account the transaction's instructions referenced;
+    /// `None` when the account no longer exists
+    pub post_accounts: Vec<(Pubkey, Option<Account>)>,
+}
+
+impl Fixture {
+    /// Execute the transaction against the pre-state, reporting what happened
+    pub fn replay(&self) -> Result<FixtureEffects> {
+        let tx_bytes = BASE64.decode(&self.transaction)
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid fixture transaction: {}", e)))?;
+        let (runtime, result) = replay(&tx_bytes, &self.pre_accounts)?;
+
+        Ok(FixtureEffects {
+            success: result.success,
+            compute_units_consumed: result.compute_units_consumed,
+            error: result.error,
+            post_accounts: result.account_deltas.iter()
+                .map(|delta| (delta.pubkey, runtime.get_account(&delta.pubkey).cloned()))
+                .collect(),
+        })
+    }
+
+    /// Replay and fail unless the effects match the recorded ones
+    pub fn verify(&self) -> Result<()> {
+        let actual = self.replay()?;
+        let expected = &self.effects;
+
+        let mismatch = |field: &str| {
+            Err(TerminatorError::TransactionExecutionFailed(format!("Fixture {} does not match", field)))
+        };
+        if actual.success != expected.success || actual.error != expected.error {
+            return mismatch("result");
+        }
+        if actual.compute_units_consumed != expected.compute_units_consumed {
+            return mismatch("compute units");
+        }
+        if actual.post_accounts != expected.post_accounts {
+            // Show how the first differing account diverged from the recording
+            let diff = expected.post_accounts.iter().zip(&actual.post_accounts).find_map(|((pubkey, want), (_, got))| {
+                match (want, got) {
+                    (Some(want), Some(got)) if want != got => {
+                        Some(format!(" for {}:\n{}", pubkey.to_base58(), AccountDiff::compute(want, got)))
+                    }
+                    _ => None,
+                }
+            });
+            return Err(TerminatorError::TransactionExecutionFailed(
+                format!("Fixture post-state does not match{}", diff.unwrap_or_default())
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| TerminatorError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| TerminatorError::SerializationError(e.to_string()))
+    }
+}
+
+/// Expected outcome of running a transaction, checked all at once so every
+/// unmet expectation is reported together
+pub struct Expect<'a> {
+    transaction: SolanaTransaction,
+    runtime: Option<&'a mut IntegratedRuntime>,
+    success: Option<bool>,
+    balances: Vec<(Pubkey, u64)>,
+    logs: Vec<String>,
+}
+
+impl Expect<'static> {
+    /// Start expectations for `transaction`, run on a fresh runtime unless
+    /// `on_runtime` supplies one
+    pub fn transaction(transaction: &SolanaTransaction) -> Self {
+        Expect {
+            transaction: transaction.clone(),
+            runtime: None,
+            success: None,
+            balances: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Expect<'a> {
+    /// Run the transaction on `runtime`, whose state it changes
+    pub fn on_runtime<'b>(self, runtime: &'b mut IntegratedRuntime) -> Expect<'b> {
+        Expect {
+            transaction: self.transaction,
+            runtime: Some(runtime),
+            success: self.success,
+            balances: self.balances,
+            logs: self.logs,
+        }
+    }
+
+    pub fn to_succeed(mut self) -> Self {
+        self.success = Some(true);
+        self
+    }
+
+    pub fn to_fail(mut self) -> Self {
+        self.success = Some(false);
+        self
+    }
+
+    /// Expect `pubkey` to hold `lamports` afterwards
+    pub fn with_balance(mut self, pubkey: Pubkey, lamports: u64) -> Self {
+        self.balances.push((pubkey, lamports));
+        self
+    }
+
+    /// Expect some log message to contain `text`
+    pub fn with_log_containing(mut self, text: &str) -> Self {
+        self.logs.push(text.to_string());
+        self
+    }
+
+    /// Execute the transaction, failing with every unmet expectation
+    pub fn verify(self) -> Result<()> {
+        let mut fresh;
+        let runtime = match self.runtime {
+            Some(runtime) => runtime,
+            None => {
+                fresh = IntegratedRuntime::new()?;
+                &mut fresh
+            }
+        };
+        let result = runtime.execute_solana_transaction_to_result(&self.transaction);
+
+        let mut failures = Vec::new();
+        if let Some(success) = self.success.filter(|&success| success != result.success) {
+            failures.push(match &result.error {
+                Some(error) if success => format!("expected success, failed with {:?}", error),
+                _ => "expected failure, succeeded".to_string(),
+            });
+        }
+        for (pubkey, expected) in &self.balances {
+            let actual = runtime.get_balance(pubkey);
+            if actual != *expected {
+                failures.push(format!("expected {} to hold {} lamports, found {}", pubkey.to_base58(), expected, actual));
+            }
+        }
+        for text in &self.logs {
+            if !result.logs.iter().any(|log| log.contains(text.as_str())) {
+                failures.push(format!("expected a log containing {:?}", text));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(TerminatorError::ConformanceTestFailed(failures.join("; ")))
+        }
+    }
+}
+
+/// Result of one named conformance test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestOutcome {
+    pub name: String,
+    pub passed: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// How a run's results changed relative to a golden results file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceDiff {
+    /// Failing now but passing in the golden file, or not in it at all
+    pub newly_failing: Vec<String>,
+    pub newly_passing: Vec<String>,
+    pub still_failing: Vec<String>,
+}
+
+impl ConformanceDiff {
+    /// Some test fails now that didn't before; failures already recorded
+    /// in the golden file don't count
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty()
+    }
+}
 
 pub struct ConformanceHarness {
     pub passed: usize,
     pub failed: usize,
+    /// Every test run so far, in order
+    pub results: Vec<TestOutcome>,
 }
 
 impl ConformanceHarness {
@@ -11,6 +219,7 @@ impl ConformanceHarness {
         Self {
             passed: 0,
             failed: 0,
+            results: Vec::new(),
         }
     }
 
@@ -18,19 +227,153 @@ impl ConformanceHarness {
     where
         F: FnOnce() -> Result<()>,
     {
-        match test_fn() {
+        let error = match test_fn() {
             Ok(()) => {
                 println!("✅ {}", name);
                 self.passed += 1;
+                None
             }
             Err(e) => {
                 println!("❌ {}: {}", name, e);
                 self.failed += 1;
+                Some(e.to_string())
             }
+        };
+        self.results.push(TestOutcome { name: name.to_string(), passed: error.is_none(), error });
+    }
+
+    /// Every result so far as a JSON array, the format `diff_against` reads
+    pub fn results_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.results).map_err(|e| TerminatorError::SerializationError(e.to_string()))
+    }
+
+    /// Save the results as a golden file for later runs to diff against
+    pub fn save_results(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.results_json()?)
+            .map_err(|e| TerminatorError::SerializationError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Compare this run's results with the golden results saved at `golden_path`
+    ///
+    /// A test the golden file doesn't mention counts as passing before, so
+    /// a new test that fails is a regression.
+    pub fn diff_against(&self, golden_path: &Path) -> Result<ConformanceDiff> {
+        let golden = std::fs::read_to_string(golden_path)
+            .map_err(|e| TerminatorError::SerializationError(format!("Failed to read {}: {}", golden_path.display(), e)))?;
+        let golden: Vec<TestOutcome> = serde_json::from_str(&golden)
+            .map_err(|e| TerminatorError::SerializationError(e.to_string()))?;
+
+        let mut diff = ConformanceDiff::default();
+        for outcome in &self.results {
+            let passed_before = golden.iter()
+                .find(|previous| previous.name == outcome.name)
+                .is_none_or(|previous| previous.passed);
+            let list = match (passed_before, outcome.passed) {
+                (true, false) => &mut diff.newly_failing,
+                (false, true) => &mut diff.newly_passing,
+                (false, false) => &mut diff.still_failing,
+                (true, true) => continue,
+            };
+            list.push(outcome.name.clone());
         }
+        Ok(diff)
+    }
+
+    /// Run a wire-format transaction against `pre_state` and record its
+    /// effects as a fixture
+    pub fn capture_fixture(tx: &[u8], pre_state: &[(Pubkey, Account)]) -> Result<Fixture> {
+        let mut fixture = Fixture {
+            transaction: BASE64.encode(tx),
+            pre_accounts: pre_state.to_vec(),
+            effects: FixtureEffects {
+                success: false,
+                compute_units_consumed: 0,
+                error: None,
+                post_accounts: Vec::new(),
+            },
+        };
+        fixture.effects = fixture.replay()?;
+        Ok(fixture)
+    }
+
+    /// Verify a fixture, counting it as a pass or failure
+    pub fn run_fixture(&mut self, name: &str, fixture: &Fixture) {
+        self.run_test(name, || fixture.verify());
     }
 
     pub fn report(&self) {
         println!("Conformance test results: {} passed, {} failed", self.passed, self.failed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_format::{SolanaHash, SolanaPubkey, SolanaTransactionParser};
+
+    fn transfer(lamports: u64) -> SolanaTransaction {
+        SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]),
+            SolanaPubkey::new([2u8; 32]),
+            lamports,
+            SolanaHash([0u8; 32]),
+        )
+    }
+
+    #[test]
+    fn test_expect_passing_chain() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let mut harness = ConformanceHarness::new();
+        harness.run_test("transfer", || {
+            Expect::transaction(&transfer(1_000))
+                .on_runtime(&mut runtime)
+                .to_succeed()
+                .with_balance(Pubkey::new([2u8; 32]), 1_000)
+                .with_log_containing("Transferring 1000 lamports")
+                .verify()
+        });
+        assert_eq!((harness.passed, harness.failed), (1, 0));
+        assert_eq!(harness.results, vec![TestOutcome { name: "transfer".to_string(), passed: true, error: None }]);
+    }
+
+    #[test]
+    fn test_diff_against_golden_detects_regression() {
+        let pass = || Ok(());
+        let fail = || Err(TerminatorError::ConformanceTestFailed("boom".to_string()));
+        let path = std::env::temp_dir().join(format!("terminator_dancer_golden_{}.json", std::process::id()));
+
+        let mut golden = ConformanceHarness::new();
+        golden.run_test("transfer", pass);
+        golden.run_test("nonce", fail);
+        golden.run_test("lookup table", fail);
+        golden.save_results(&path).unwrap();
+
+        let mut current = ConformanceHarness::new();
+        current.run_test("transfer", fail);
+        current.run_test("nonce", pass);
+        current.run_test("lookup table", fail);
+        let diff = current.diff_against(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(diff.has_regressions());
+        assert_eq!(diff.newly_failing, vec!["transfer"]);
+        assert_eq!(diff.newly_passing, vec!["nonce"]);
+        assert_eq!(diff.still_failing, vec!["lookup table"]);
+        assert!(!ConformanceDiff { newly_failing: vec![], ..diff }.has_regressions());
+    }
+
+    #[test]
+    fn test_expect_reports_every_failure() {
+        let err = Expect::transaction(&transfer(1_000))
+            .to_fail()
+            .with_balance(Pubkey::new([2u8; 32]), 5)
+            .with_log_containing("no such log")
+            .verify()
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("expected failure, succeeded"), "{}", err);
+        assert!(err.contains("to hold 5 lamports, found 1000"), "{}", err);
+        assert!(err.contains("\"no such log\""), "{}", err);
+    }
+}
@@ -1,13 +1,65 @@
 use crate::{Result, TerminatorError};
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use crate::solana_format::{MessageHeader, SolanaPubkey, SolanaSignature, SolanaTransaction, SolanaTransactionParser, VersionedMessage, VersionedTransaction};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use sha2::{Sha256, Digest};
+use sha3::Keccak256;
 use blake3::Hasher as Blake3Hasher;
+use k256::ecdsa::{RecoveryId, Signature as Secp256k1Signature, VerifyingKey as Secp256k1VerifyingKey};
+use zeroize::ZeroizeOnDrop;
+
+/// Ed25519 keypair whose secret is zeroed when dropped and never shown by `Debug`
+pub struct Keypair(SigningKey);
+
+impl Keypair {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Keypair(SigningKey::from_bytes(seed))
+    }
+
+    pub fn pubkey(&self) -> [u8; 32] {
+        self.0.verifying_key().to_bytes()
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        self.0.sign(message).to_bytes()
+    }
+
+    /// The underlying key, for APIs such as `TransactionBuilder` that take one
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.0
+    }
+}
+
+/// `SigningKey` zeroes its secret in its own `Drop`
+impl ZeroizeOnDrop for Keypair {}
+
+impl std::fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keypair")
+            .field("pubkey", &bs58::encode(self.pubkey()).into_string())
+            .finish_non_exhaustive()
+    }
+}
 
 /// Real cryptographic operations using industry-standard libraries
 pub struct SolanaCrypto;
 
 impl SolanaCrypto {
+    /// Fresh keypair from the operating system's random number generator
+    pub fn generate_keypair() -> Keypair {
+        Keypair(SigningKey::generate(&mut rand::rngs::OsRng))
+    }
+
+    /// Whether `pubkey` decompresses to a point on the Ed25519 curve
+    ///
+    /// Keypair addresses are on the curve; program derived addresses never are.
+    pub fn is_on_curve(pubkey: &[u8; 32]) -> bool {
+        VerifyingKey::from_bytes(pubkey).is_ok()
+    }
+
     /// Verify an Ed25519 signature using the same crypto as Solana
+    ///
+    /// A public key that isn't a curve point is a `MalformedPublicKey` error
+    /// rather than a failed verification.
     pub fn verify_ed25519_signature(
         signature_bytes: &[u8; 64],
         message: &[u8],
@@ -15,7 +67,7 @@ impl SolanaCrypto {
     ) -> Result<bool> {
         // Parse the public key
         let public_key = VerifyingKey::from_bytes(public_key_bytes)
-            .map_err(|_| TerminatorError::InvalidSignature)?;
+            .map_err(|_| TerminatorError::MalformedPublicKey(bs58::encode(public_key_bytes).into_string()))?;
         
         // Parse the signature
         let signature = Signature::from_bytes(signature_bytes);
@@ -41,6 +93,24 @@ impl SolanaCrypto {
         hasher.finalize().into()
     }
 
+    /// Compute Keccak-256 hash, as Ethereum and the secp256k1 precompile use
+    pub fn keccak256_hash(data: &[u8]) -> [u8; 32] {
+        Keccak256::digest(data).into()
+    }
+
+    /// Recover the secp256k1 public key (uncompressed, without the `0x04`
+    /// prefix) that produced `signature` over `message_hash`
+    pub fn secp256k1_recover(message_hash: &[u8; 32], signature: &[u8; 64], recovery_id: u8) -> Result<[u8; 64]> {
+        let signature = Secp256k1Signature::from_slice(signature).map_err(|_| TerminatorError::InvalidSignature)?;
+        let recovery_id = RecoveryId::from_byte(recovery_id).ok_or(TerminatorError::InvalidSignature)?;
+        let key = Secp256k1VerifyingKey::recover_from_prehash(message_hash, &signature, recovery_id)
+            .map_err(|_| TerminatorError::InvalidSignature)?;
+
+        let mut pubkey = [0u8; 64];
+        pubkey.copy_from_slice(&key.to_encoded_point(false).as_bytes()[1..]);
+        Ok(pubkey)
+    }
+
     /// Create a transaction message hash for signature verification
     pub fn create_transaction_message_hash(
         transaction_data: &[u8],
@@ -78,6 +148,49 @@ impl SolanaCrypto {
     }
 }
 
+/// Check every required signature of `tx` against its signer's account
+/// key, without a runtime or any account state
+///
+/// An all-zero placeholder signature, a signature count that doesn't match
+/// the header, or a signature that doesn't verify is `InvalidSignature`.
+pub fn verify_transaction_signatures(tx: &SolanaTransaction) -> Result<()> {
+    let message = SolanaTransactionParser::message_data(&tx.message)?;
+    verify_signatures(&tx.signatures, &tx.message.header, &tx.message.account_keys, &message)
+}
+
+/// `verify_transaction_signatures` for a legacy or v0 transaction, checking
+/// signatures over the message as it was signed
+pub fn verify_versioned_transaction_signatures(tx: &VersionedTransaction) -> Result<()> {
+    let message = SolanaTransactionParser::versioned_message_data(&tx.message)?;
+    let (header, account_keys) = match &tx.message {
+        VersionedMessage::Legacy(legacy) => (&legacy.header, &legacy.account_keys),
+        VersionedMessage::V0(v0) => (&v0.header, &v0.account_keys),
+    };
+    verify_signatures(&tx.signatures, header, account_keys, &message)
+}
+
+fn verify_signatures(
+    signatures: &[SolanaSignature],
+    header: &MessageHeader,
+    account_keys: &[SolanaPubkey],
+    message: &[u8],
+) -> Result<()> {
+    let num_required = header.num_required_signatures as usize;
+    if signatures.len() != num_required || account_keys.len() < num_required {
+        return Err(TerminatorError::InvalidSignature);
+    }
+
+    for (signature, signer) in signatures.iter().zip(account_keys) {
+        if signature.0 == [0u8; 64] {
+            return Err(TerminatorError::InvalidSignature);
+        }
+        if !SolanaCrypto::verify_ed25519_signature(&signature.0, message, &signer.0)? {
+            return Err(TerminatorError::InvalidSignature);
+        }
+    }
+    Ok(())
+}
+
 /// Performance-oriented crypto operations
 pub struct FastCrypto;
 
@@ -142,7 +255,7 @@ impl AddressDerivation {
             let hash: [u8; 32] = hasher.finalize().into();
             
             // Check if this is a valid PDA (not on the Ed25519 curve)
-            if let Err(_) = VerifyingKey::from_bytes(&hash) {
+            if !SolanaCrypto::is_on_curve(&hash) {
                 return Ok((hash, bump));
             }
         }
@@ -203,6 +316,26 @@ mod tests {
         assert!(!result, "Invalid signature should not verify");
     }
 
+    #[test]
+    fn test_off_curve_public_key_rejected() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let on_curve = signing_key.verifying_key().to_bytes();
+        // y = 2 has no matching x on the curve
+        let mut off_curve = [0u8; 32];
+        off_curve[0] = 2;
+
+        assert!(SolanaCrypto::is_on_curve(&on_curve));
+        assert!(!SolanaCrypto::is_on_curve(&off_curve));
+
+        let message = b"test message";
+        let signature = signing_key.sign(message).to_bytes();
+        assert!(SolanaCrypto::verify_ed25519_signature(&signature, message, &on_curve).unwrap());
+        assert!(matches!(
+            SolanaCrypto::verify_ed25519_signature(&signature, message, &off_curve),
+            Err(TerminatorError::MalformedPublicKey(_))
+        ));
+    }
+
     #[test]
     fn test_sha256_consistency() {
         let message = b"consistent hashing test";
@@ -254,4 +387,73 @@ mod tests {
         let result = FastCrypto::batch_verify_signatures(&sig_refs, &msg_refs, &key_refs).unwrap();
         assert!(result, "Batch verification should succeed for valid signatures");
     }
+
+    #[test]
+    fn test_verify_transaction_signatures() {
+        use crate::solana_format::{SolanaHash, SolanaPubkey, SolanaSignature};
+
+        let keypair = Keypair::from_seed(&[9u8; 32]);
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new(keypair.pubkey()), SolanaPubkey::new([2u8; 32]), 1_000, SolanaHash([3u8; 32]),
+        );
+        assert!(matches!(verify_transaction_signatures(&tx), Err(TerminatorError::InvalidSignature)));
+
+        let message = SolanaTransactionParser::message_data(&tx.message).unwrap();
+        tx.signatures[0] = SolanaSignature(keypair.sign(&message));
+        assert!(verify_transaction_signatures(&tx).is_ok());
+
+        tx.message.recent_blockhash = SolanaHash([4u8; 32]);
+        assert!(matches!(verify_transaction_signatures(&tx), Err(TerminatorError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_verify_v0_transaction_signatures() {
+        use crate::solana_format::{MessageAddressTableLookup, SolanaHash, SolanaSignature, V0Message};
+
+        let keypair = Keypair::from_seed(&[9u8; 32]);
+        let legacy = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new(keypair.pubkey()), SolanaPubkey::new([2u8; 32]), 1_000, SolanaHash([3u8; 32]),
+        ).message;
+        let message = VersionedMessage::V0(V0Message {
+            header: legacy.header,
+            account_keys: legacy.account_keys,
+            recent_blockhash: legacy.recent_blockhash,
+            instructions: legacy.instructions,
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: SolanaPubkey::new([50u8; 32]),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            }],
+        });
+        let signed = SolanaTransactionParser::versioned_message_data(&message).unwrap();
+        let mut tx = VersionedTransaction { signatures: vec![SolanaSignature(keypair.sign(&signed))], message };
+        assert!(verify_versioned_transaction_signatures(&tx).is_ok());
+
+        if let VersionedMessage::V0(v0) = &mut tx.message {
+            v0.address_table_lookups[0].writable_indexes[0] = 1;
+        }
+        assert!(matches!(verify_versioned_transaction_signatures(&tx), Err(TerminatorError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_keypair_secret_zeroized_and_hidden() {
+        let seed = [42u8; 32];
+        let keypair = Keypair::from_seed(&seed);
+        let signature = keypair.sign(b"hello");
+        assert!(SolanaCrypto::verify_ed25519_signature(&signature, b"hello", &keypair.pubkey()).unwrap());
+
+        let debug = format!("{:?}", keypair);
+        assert!(debug.contains(&bs58::encode(keypair.pubkey()).into_string()));
+        assert!(!debug.contains("42, 42"), "{}", debug);
+
+        // Best effort: drop in place so the storage can still be read afterwards
+        let mut slot = std::mem::MaybeUninit::new(keypair);
+        let holds_seed = |slot: &std::mem::MaybeUninit<Keypair>| {
+            let bytes = unsafe { std::slice::from_raw_parts(slot.as_ptr() as *const u8, std::mem::size_of::<Keypair>()) };
+            bytes.windows(32).any(|window| window == seed)
+        };
+        assert!(holds_seed(&slot));
+        unsafe { slot.assume_init_drop() };
+        assert!(!holds_seed(&slot));
+    }
 } 
\ No newline at end of file
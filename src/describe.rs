@@ -0,0 +1,99 @@
+/// Instruction descriptions
+/// Plain-language summaries of instructions, as shown to users before they sign
+
+use crate::prelude::*;
+use crate::address_lookup_table::ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+use crate::compute_budget::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::memo::{decode_memo, is_memo_program};
+use crate::solana_format::SolanaPubkey;
+use crate::spl_token::TokenInstruction;
+use crate::system_program::{SystemInstruction, SYSTEM_PROGRAM_ID};
+
+/// Decimal places of SOL (one SOL is 10^9 lamports)
+pub const SOL_DECIMALS: u8 = 9;
+
+/// `amount` base units as a decimal with `decimals` places, without trailing zeros
+///
+/// Decimals too large to scale by (they come from untrusted instruction data)
+/// show the raw base-unit amount.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals as u32) else {
+        return amount.to_string();
+    };
+    let (whole, fraction) = (amount as u128 / scale, amount as u128 % scale);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Display name of `program_id`, for the programs this crate recognizes
+pub fn program_name(program_id: &[u8; 32]) -> Option<&'static str> {
+    match *program_id {
+        SYSTEM_PROGRAM_ID => Some("System Program"),
+        COMPUTE_BUDGET_PROGRAM_ID => Some("Compute Budget Program"),
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID => Some("Address Lookup Table Program"),
+        id if id == SolanaPubkey::token_program().0 => Some("Token Program"),
+        id if is_memo_program(&id) => Some("Memo Program"),
+        _ => None,
+    }
+}
+
+/// Summary of an instruction to `program_id`, for the programs this crate
+/// can decode
+pub fn describe_instruction(program_id: &[u8; 32], data: &[u8]) -> String {
+    let program = SolanaPubkey::new(*program_id);
+    if *program_id == SYSTEM_PROGRAM_ID {
+        SystemInstruction::decode(data)
+            .map(|instruction| instruction.describe())
+            .unwrap_or_else(|_| "Invalid system instruction".to_string())
+    } else if program == SolanaPubkey::token_program() {
+        TokenInstruction::decode(data)
+            .map(|instruction| instruction.describe())
+            .unwrap_or_else(|_| "Unrecognized token instruction".to_string())
+    } else if is_memo_program(program_id) {
+        format!("Memo: {:?}", decode_memo(data))
+    } else {
+        format!("Unknown program {}", program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_system_instructions() {
+        let transfer = borsh::to_vec(&SystemInstruction::Transfer { lamports: 10_000_000 }).unwrap();
+        assert_eq!(describe_instruction(&SYSTEM_PROGRAM_ID, &transfer), "Transfer 0.01 SOL");
+
+        let token_program = SolanaPubkey::token_program().0;
+        let create = SystemInstruction::CreateAccount { lamports: 2_039_280, space: 128, owner: token_program };
+        assert_eq!(
+            create.describe(),
+            "Create account with 128 bytes owned by TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA, funded with 0.00203928 SOL"
+        );
+        assert_eq!(describe_instruction(&SYSTEM_PROGRAM_ID, &[0xFF]), "Invalid system instruction");
+    }
+
+    #[test]
+    fn test_describe_other_programs() {
+        let mut checked = vec![12u8];
+        checked.extend_from_slice(&1_500_000u64.to_le_bytes());
+        checked.push(6);
+        assert_eq!(describe_instruction(&SolanaPubkey::token_program().0, &checked), "Transfer 1.5 tokens");
+        assert_eq!(describe_instruction(&crate::memo::MEMO_PROGRAM_ID, b"gm"), "Memo: \"gm\"");
+        assert_eq!(
+            describe_instruction(&[9u8; 32], &[]),
+            format!("Unknown program {}", SolanaPubkey::new([9u8; 32]))
+        );
+        assert_eq!(format_amount(1_000_000_000, SOL_DECIMALS), "1");
+        assert_eq!(format_amount(5, 0), "5");
+        assert_eq!(format_amount(1_500_000, 255), "1500000");
+
+        let mut huge_decimals = checked[..9].to_vec();
+        huge_decimals.push(255);
+        assert_eq!(describe_instruction(&SolanaPubkey::token_program().0, &huge_decimals), "Transfer 1500000 tokens");
+    }
+}
@@ -16,6 +16,8 @@ impl TransactionExecutor {
             compute_units_consumed: 100,
             logs: vec!["Transaction executed successfully".to_string()],
             error: None,
+            account_deltas: Vec::new(),
+            memos: Vec::new(),
         })
     }
 }
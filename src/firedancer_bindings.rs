@@ -5,10 +5,10 @@
 use std::ffi::{c_char, c_int, c_uchar, c_ulong, c_void};
 #[cfg(not(target_arch = "wasm32"))]
 use std::ptr;
-use crate::{Result, TerminatorError};
+use crate::{CryptoBackend, Result, TerminatorError};
 
 // DEMO STUB IMPLEMENTATIONS - Replace with real Firedancer when libraries are built
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(firedancer_linked)))]
 mod firedancer_stubs {
     use super::*;
     
@@ -141,16 +141,19 @@ mod firedancer_stubs {
         _vm: *mut c_void,
         _program: *mut c_void,
         _input: *const c_uchar,
-        _input_sz: c_ulong,
-        output: *mut c_uchar,
+        input_sz: c_ulong,
+        _output: *mut c_uchar,
         output_sz: *mut c_ulong,
-        _compute_units: *mut c_ulong,
+        compute_units: *mut c_ulong,
     ) -> c_int {
-        // Stub: Return success with empty output
+        // Stub: Return success with empty output and an estimated CU cost
         unsafe {
             if !output_sz.is_null() {
                 *output_sz = 0;
             }
+            if !compute_units.is_null() {
+                *compute_units = estimate_compute_units(input_sz as u64);
+            }
         }
         0
     }
@@ -160,25 +163,63 @@ mod firedancer_stubs {
         // Stub: No-op
     }
     
+    /// Account storage owned by the stub account manager
+    #[derive(Default)]
+    struct StubAccountDb {
+        accounts: std::collections::HashMap<[u8; 32], StubAccount>,
+    }
+    
+    struct StubAccount {
+        lamports: u64,
+        data: Vec<u8>,
+        owner: [u8; 32],
+        executable: c_int,
+        rent_epoch: u64,
+    }
+    
     #[no_mangle]
     pub extern "C" fn fd_acc_mgr_new() -> *mut c_void {
-        // Stub: Return fake account manager handle
-        0x9abc as *mut c_void
+        // Stub: Heap-allocated in-memory account store
+        Box::into_raw(Box::new(StubAccountDb::default())) as *mut c_void
     }
     
     #[no_mangle]
-    pub extern "C" fn fd_acc_mgr_delete(_mgr: *mut c_void) {
-        // Stub: No-op
+    pub extern "C" fn fd_acc_mgr_delete(mgr: *mut c_void) {
+        if !mgr.is_null() {
+            unsafe { drop(Box::from_raw(mgr as *mut StubAccountDb)) };
+        }
     }
     
     #[no_mangle]
     pub extern "C" fn fd_acc_mgr_view(
-        _mgr: *mut c_void,
-        _address: *const c_uchar,
-        _record: *mut c_void,
+        mgr: *mut c_void,
+        address: *const c_uchar,
+        record: *mut c_void,
     ) -> c_int {
-        // Stub: Account not found
-        1
+        unsafe {
+            if mgr.is_null() || address.is_null() || record.is_null() {
+                return 1; // Error
+            }
+            
+            let db = &*(mgr as *const StubAccountDb);
+            let mut key = [0u8; 32];
+            key.copy_from_slice(std::slice::from_raw_parts(address, 32));
+            
+            let Some(stored) = db.accounts.get(&key) else {
+                return 1; // Account not found
+            };
+            
+            // The data pointer borrows manager-owned storage and stays valid
+            // until the next modify of this account
+            let record = &mut *(record as *mut AccountRecord);
+            record.lamports = stored.lamports;
+            record.data_sz = stored.data.len() as u64;
+            record.data = stored.data.as_ptr() as *mut c_uchar;
+            record.owner = stored.owner;
+            record.executable = stored.executable;
+            record.rent_epoch = stored.rent_epoch;
+            0
+        }
     }
     
     #[no_mangle]
@@ -190,12 +231,131 @@ mod firedancer_stubs {
         // Stub: Always success
         0
     }
+    
+    #[no_mangle]
+    pub extern "C" fn fd_acc_mgr_modify_copy(
+        mgr: *mut c_void,
+        address: *const c_uchar,
+        record: *const c_void,
+    ) -> c_int {
+        unsafe {
+            if mgr.is_null() || address.is_null() || record.is_null() {
+                return 1; // Error
+            }
+            
+            let db = &mut *(mgr as *mut StubAccountDb);
+            let record = &*(record as *const AccountRecord);
+            if record.data.is_null() && record.data_sz > 0 {
+                return 1; // Error
+            }
+            
+            let mut key = [0u8; 32];
+            key.copy_from_slice(std::slice::from_raw_parts(address, 32));
+            
+            // Deep-copy the data so the caller's buffer can be freed afterwards
+            let data = if record.data_sz > 0 {
+                std::slice::from_raw_parts(record.data, record.data_sz as usize).to_vec()
+            } else {
+                Vec::new()
+            };
+            
+            db.accounts.insert(key, StubAccount {
+                lamports: record.lamports,
+                data,
+                owner: record.owner,
+                executable: record.executable,
+                rent_epoch: record.rent_epoch,
+            });
+            0
+        }
+    }
 }
 
 // Import stub functions for use
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(firedancer_linked)))]
 use firedancer_stubs::*;
 
+// Real Firedancer functions, resolved by the linker
+#[cfg(all(not(target_arch = "wasm32"), firedancer_linked))]
+extern "C" {
+    fn fd_ed25519_verify(
+        sig: *const c_uchar,
+        msg: *const c_uchar,
+        msg_sz: c_ulong,
+        public_key: *const c_uchar,
+        sha: *mut c_void,
+    ) -> c_int;
+    fn fd_sha256_hash(msg: *const c_uchar, msg_sz: c_ulong, hash: *mut c_uchar) -> c_int;
+    fn fd_blake3_hash(msg: *const c_uchar, msg_sz: c_ulong, hash: *mut c_uchar) -> c_int;
+    fn fd_sbpf_vm_new() -> *mut c_void;
+    fn fd_sbpf_vm_delete(vm: *mut c_void);
+    fn fd_sbpf_program_new(bytecode: *const c_uchar, bytecode_sz: c_ulong, entry_pc: *mut c_ulong) -> *mut c_void;
+    fn fd_sbpf_vm_exec(
+        vm: *mut c_void,
+        program: *mut c_void,
+        input: *const c_uchar,
+        input_sz: c_ulong,
+        output: *mut c_uchar,
+        output_sz: *mut c_ulong,
+        compute_units: *mut c_ulong,
+    ) -> c_int;
+    fn fd_sbpf_program_delete(program: *mut c_void);
+    fn fd_acc_mgr_new() -> *mut c_void;
+    fn fd_acc_mgr_delete(mgr: *mut c_void);
+    fn fd_acc_mgr_view(mgr: *mut c_void, address: *const c_uchar, record: *mut c_void) -> c_int;
+    fn fd_acc_mgr_modify_copy(mgr: *mut c_void, address: *const c_uchar, record: *const c_void) -> c_int;
+}
+
+/// Which implementation the `fd_*` functions resolve to
+///
+/// Linked libraries must also pass a known-answer probe, checked once;
+/// if they don't, `FiredancerCrypto` falls back to pure Rust.
+pub fn active_backend() -> CryptoBackend {
+    #[cfg(target_arch = "wasm32")]
+    {
+        CryptoBackend::PureRust
+    }
+    #[cfg(all(not(target_arch = "wasm32"), not(firedancer_linked)))]
+    {
+        CryptoBackend::Stub
+    }
+    #[cfg(all(not(target_arch = "wasm32"), firedancer_linked))]
+    {
+        static BACKEND: std::sync::OnceLock<CryptoBackend> = std::sync::OnceLock::new();
+        *BACKEND.get_or_init(|| {
+            if probe_linked_crypto() {
+                CryptoBackend::Firedancer
+            } else {
+                tracing::warn!("Linked Firedancer crypto failed its self-test, using pure Rust");
+                CryptoBackend::PureRust
+            }
+        })
+    }
+}
+
+/// Whether the linked SHA256 agrees with the pure Rust one on a fixed input
+#[cfg(all(not(target_arch = "wasm32"), firedancer_linked))]
+fn probe_linked_crypto() -> bool {
+    let input = b"terminator-dancer probe";
+    let mut hash = [0u8; 32];
+    let result = unsafe { fd_sha256_hash(input.as_ptr(), input.len() as c_ulong, hash.as_mut_ptr()) };
+    result == 0 && hash == crate::crypto::SolanaCrypto::sha256_hash(input)
+}
+
+/// Rough CU estimate used wherever real VM metering is unavailable
+fn estimate_compute_units(input_len: u64) -> u64 {
+    100 + input_len * 10
+}
+
+/// Outcome of a single BPF program run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VmExecutionResult {
+    /// Number of bytes written to the output buffer
+    pub output_len: u64,
+    /// Compute units reported by the VM
+    pub compute_units: u64,
+}
+
 // Firedancer account record structure
 #[cfg(not(target_arch = "wasm32"))]
 #[repr(C)]
@@ -219,8 +379,16 @@ impl FiredancerCrypto {
         message: &[u8],
         public_key: &[u8; 32],
     ) -> Result<bool> {
+        if !crate::crypto::SolanaCrypto::is_on_curve(public_key) {
+            return Err(TerminatorError::MalformedPublicKey(bs58::encode(public_key).into_string()));
+        }
+        
         #[cfg(not(target_arch = "wasm32"))]
         {
+            if active_backend() == CryptoBackend::PureRust {
+                return crate::crypto::SolanaCrypto::verify_ed25519_signature(signature, message, public_key);
+            }
+            
             let result = unsafe {
                 fd_ed25519_verify(
                     signature.as_ptr(),
@@ -244,6 +412,10 @@ impl FiredancerCrypto {
     pub fn sha256(data: &[u8]) -> Result<[u8; 32]> {
         #[cfg(not(target_arch = "wasm32"))]
         {
+            if active_backend() == CryptoBackend::PureRust {
+                return Ok(crate::crypto::SolanaCrypto::sha256_hash(data));
+            }
+            
             let mut hash = [0u8; 32];
             
             let result = unsafe {
@@ -274,6 +446,10 @@ impl FiredancerCrypto {
     pub fn blake3(data: &[u8]) -> Result<[u8; 32]> {
         #[cfg(not(target_arch = "wasm32"))]
         {
+            if active_backend() == CryptoBackend::PureRust {
+                return Ok(crate::crypto::SolanaCrypto::blake3_hash(data));
+            }
+            
             let mut hash = [0u8; 32];
             
             let result = unsafe {
@@ -326,7 +502,7 @@ impl FiredancerVM {
         bytecode: &[u8],
         input: &[u8],
         output: &mut [u8],
-    ) -> Result<u64> {
+    ) -> Result<VmExecutionResult> {
         // Load program
         let mut entry_pc = 0u64;
         let prog_handle = unsafe {
@@ -365,7 +541,10 @@ impl FiredancerVM {
             return Err(TerminatorError::ProgramError("BPF program execution failed".to_string()));
         }
         
-        Ok(output_sz as u64)
+        Ok(VmExecutionResult {
+            output_len: output_sz as u64,
+            compute_units,
+        })
     }
 }
 
@@ -441,12 +620,17 @@ impl FiredancerAccountManager {
     }
 
     /// Update account
+    ///
+    /// The record handed to Firedancer only borrows `account.data` for the
+    /// duration of the call, so this goes through `fd_acc_mgr_modify_copy`,
+    /// which deep-copies the data into manager-owned storage. The caller's
+    /// buffer can be dropped as soon as this returns.
     pub fn update_account(
         &mut self,
         address: &[u8; 32],
         account: &crate::types::Account,
     ) -> Result<()> {
-        // Convert to C record
+        // Convert to C record (borrowed view, valid only for this call)
         let record = AccountRecord {
             lamports: account.lamports,
             data_sz: account.data.len() as u64,
@@ -457,7 +641,7 @@ impl FiredancerAccountManager {
         };
         
         let result = unsafe {
-            fd_acc_mgr_modify(
+            fd_acc_mgr_modify_copy(
                 self.handle,
                 address.as_ptr(),
                 &record as *const _ as *const c_void,
@@ -493,14 +677,16 @@ impl FiredancerVM {
 
     pub fn execute_program(
         &self,
-        bytecode: &[u8],
+        _bytecode: &[u8],
         input: &[u8],
         output: &mut [u8],
-    ) -> Result<u64> {
-        // WASM fallback - simple computation
-        let result = input.len() as u64 + bytecode.len() as u64;
+    ) -> Result<VmExecutionResult> {
+        // WASM fallback - no real VM, so report an estimate
         output.fill(0x42); // Fill with demo data
-        Ok(result)
+        Ok(VmExecutionResult {
+            output_len: output.len() as u64,
+            compute_units: estimate_compute_units(input.len() as u64),
+        })
     }
 }
 
@@ -559,9 +745,36 @@ mod tests {
         let _vm_result = FiredancerVM::new();
     }
     
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_vm_reports_compute_units() {
+        let mut vm = FiredancerVM::new().unwrap();
+        let mut output = [0u8; 16];
+        let result = vm.execute_program(&[0x95, 0, 0, 0, 0, 0, 0, 0], &[1, 2, 3], &mut output).unwrap();
+
+        assert!(result.compute_units > 0);
+        assert_eq!(result.output_len, 0);
+    }
+
     #[test]
     fn test_account_manager_creation() {
         // Test account manager creation (will use real Firedancer if linked)
         let _acc_mgr_result = FiredancerAccountManager::new();
     }
+    
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_update_account_copies_data() {
+        let mut manager = FiredancerAccountManager::new().unwrap();
+        let address = [9u8; 32];
+        
+        let account = crate::types::Account::new(500, vec![1, 2, 3, 4], [7u8; 32]);
+        manager.update_account(&address, &account).unwrap();
+        drop(account);
+        
+        let stored = manager.get_account(&address).unwrap().expect("account should exist");
+        assert_eq!(stored.lamports, 500);
+        assert_eq!(stored.data, vec![1, 2, 3, 4]);
+        assert_eq!(stored.owner, [7u8; 32]);
+    }
 } 
\ No newline at end of file
@@ -1,4 +1,7 @@
 use crate::{Result, TerminatorError};
+use crate::crypto::SolanaCrypto;
+use crate::firedancer_bindings::FiredancerCrypto as FiredancerFfiCrypto;
+use crate::solana_format::{SolanaTransaction, SolanaTransactionParser};
 // use std::ptr; // Unused for now
 
 // Example Firedancer C library bindings
@@ -151,6 +154,137 @@ impl FiredancerValidator {
     pub fn compute_transaction_hash(transaction_data: &[u8]) -> Result<[u8; 32]> {
         FiredancerCrypto::sha256_hash(transaction_data)
     }
+
+    /// Differentially validate a transaction against the Firedancer FFI path
+    ///
+    /// Signature verification and SHA256/Blake3 hashing of the message are run
+    /// through both `SolanaCrypto` and the Firedancer bindings, and every
+    /// disagreement is recorded byte-for-byte. Without the `firedancer` feature
+    /// only the pure-Rust path runs and the report says so.
+    pub fn validate_against_firedancer(tx: &SolanaTransaction) -> ValidationReport {
+        if cfg!(feature = "firedancer") {
+            Self::compare_crypto_paths(tx)
+        } else {
+            let mut report = Self::native_report(tx);
+            report.notes.push("native only: firedancer feature disabled".to_string());
+            report
+        }
+    }
+
+    /// Run the pure-Rust checks without consulting Firedancer
+    fn native_report(tx: &SolanaTransaction) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let message = match SolanaTransactionParser::message_data(&tx.message) {
+            Ok(message) => message,
+            Err(e) => {
+                report.notes.push(format!("failed to serialize message: {}", e));
+                return report;
+            }
+        };
+
+        for (i, signature) in tx.signatures.iter().enumerate() {
+            let valid = match tx.message.account_keys.get(i) {
+                Some(signer) => SolanaCrypto::verify_ed25519_signature(&signature.0, &message, &signer.0)
+                    .unwrap_or(false),
+                None => false,
+            };
+            report.native_signature_results.push(valid);
+        }
+
+        report
+    }
+
+    /// Run every check through both crypto paths and collect disagreements
+    fn compare_crypto_paths(tx: &SolanaTransaction) -> ValidationReport {
+        let mut report = Self::native_report(tx);
+        report.firedancer_compared = true;
+
+        let message = match SolanaTransactionParser::message_data(&tx.message) {
+            Ok(message) => message,
+            Err(_) => return report,
+        };
+
+        for (i, signature) in tx.signatures.iter().enumerate() {
+            let native = report.native_signature_results[i];
+            let firedancer = match tx.message.account_keys.get(i) {
+                Some(signer) => FiredancerFfiCrypto::verify_signature(&signature.0, &message, &signer.0)
+                    .unwrap_or(false),
+                None => false,
+            };
+
+            if native != firedancer {
+                report.mismatches.push(CryptoMismatch {
+                    check: format!("ed25519 signature {}", i),
+                    native: vec![native as u8],
+                    firedancer: vec![firedancer as u8],
+                });
+            }
+        }
+
+        let native_sha256 = SolanaCrypto::sha256_hash(&message);
+        match FiredancerFfiCrypto::sha256(&message) {
+            Ok(firedancer_sha256) => report.compare_bytes("sha256", &native_sha256, &firedancer_sha256),
+            Err(e) => report.notes.push(format!("firedancer sha256 failed: {}", e)),
+        }
+
+        let native_blake3 = SolanaCrypto::blake3_hash(&message);
+        match FiredancerFfiCrypto::blake3(&message) {
+            Ok(firedancer_blake3) => report.compare_bytes("blake3", &native_blake3, &firedancer_blake3),
+            Err(e) => report.notes.push(format!("firedancer blake3 failed: {}", e)),
+        }
+
+        report
+    }
+}
+
+/// Result of comparing the pure-Rust and Firedancer crypto paths
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Whether the Firedancer FFI path took part in the comparison
+    pub firedancer_compared: bool,
+    /// Signature verification results from the pure-Rust path, in signer order
+    pub native_signature_results: Vec<bool>,
+    /// Checks where the two paths produced different output
+    pub mismatches: Vec<CryptoMismatch>,
+    /// Informational notes (e.g. "native only")
+    pub notes: Vec<String>,
+}
+
+impl ValidationReport {
+    /// True when no disagreement between the two paths was found
+    pub fn agrees(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    fn compare_bytes(&mut self, check: &str, native: &[u8], firedancer: &[u8]) {
+        if native != firedancer {
+            self.mismatches.push(CryptoMismatch {
+                check: check.to_string(),
+                native: native.to_vec(),
+                firedancer: firedancer.to_vec(),
+            });
+        }
+    }
+}
+
+/// A single disagreement between the pure-Rust and Firedancer outputs
+#[derive(Debug, Clone)]
+pub struct CryptoMismatch {
+    pub check: String,
+    pub native: Vec<u8>,
+    pub firedancer: Vec<u8>,
+}
+
+impl CryptoMismatch {
+    /// Offset of the first differing byte
+    pub fn first_difference(&self) -> Option<usize> {
+        self.native.iter()
+            .zip(self.firedancer.iter())
+            .position(|(a, b)| a != b)
+            .or_else(|| (self.native.len() != self.firedancer.len())
+                .then(|| self.native.len().min(self.firedancer.len())))
+    }
 }
 
 /// Example integration with Firedancer's conformance testing
@@ -204,6 +338,29 @@ mod tests {
         assert_eq!(hash.unwrap().len(), 32);
     }
 
+    #[test]
+    fn test_validate_against_firedancer_agrees() {
+        use crate::solana_format::{SolanaHash, SolanaPubkey, SolanaSignature};
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let from = SolanaPubkey::new(signing_key.verifying_key().to_bytes());
+        let to = SolanaPubkey::new([2u8; 32]);
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(from, to, 1_000, SolanaHash([3u8; 32]));
+
+        let message = SolanaTransactionParser::message_data(&tx.message).unwrap();
+        tx.signatures[0] = SolanaSignature(signing_key.sign(&message).to_bytes());
+
+        let report = FiredancerValidator::compare_crypto_paths(&tx);
+        assert!(report.firedancer_compared);
+        assert_eq!(report.native_signature_results, vec![true]);
+        assert!(report.agrees(), "mismatches: {:?}", report.mismatches);
+
+        let report = FiredancerValidator::validate_against_firedancer(&tx);
+        assert!(report.agrees());
+        assert_eq!(report.firedancer_compared, cfg!(feature = "firedancer"));
+    }
+
     #[test]
     fn test_conformance_vectors() {
         assert!(FiredancerConformanceTest::run_ed25519_test_vector().is_ok());
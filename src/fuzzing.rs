@@ -1,50 +1,180 @@
 use crate::types::*;
 use crate::{Result};
+use crate::system_program::{MAX_PERMITTED_DATA_LENGTH, SYSTEM_PROGRAM_ID};
+use rand::Rng;
+
+/// Instructions a fuzzed transaction may carry before it's treated as a timeout
+pub const DEFAULT_MAX_FUZZ_INSTRUCTIONS: usize = 64;
+
+/// Most instructions `generate_random_transaction` puts in one transaction
+pub const MAX_GENERATED_INSTRUCTIONS: usize = 8;
+
+/// An input the fuzzer flagged instead of (or after) executing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FuzzFinding {
+    /// Input exceeded a step or allocation guard and was not executed
+    Timeout { reason: String },
+    /// Execution returned an error
+    Failure { error: String },
+}
 
 pub struct RuntimeFuzzer {
     pub iterations: usize,
+    /// Most instructions executed per transaction
+    pub max_instructions: usize,
+    /// Largest account allocation, in bytes, a transaction may request
+    pub max_allocation: u64,
 }
 
 impl RuntimeFuzzer {
     pub fn new(iterations: usize) -> Self {
-        Self { iterations }
+        Self {
+            iterations,
+            max_instructions: DEFAULT_MAX_FUZZ_INSTRUCTIONS,
+            max_allocation: MAX_PERMITTED_DATA_LENGTH,
+        }
+    }
+
+    pub fn with_max_instructions(mut self, max_instructions: usize) -> Self {
+        self.max_instructions = max_instructions;
+        self
+    }
+
+    pub fn with_max_allocation(mut self, max_allocation: u64) -> Self {
+        self.max_allocation = max_allocation;
+        self
     }
 
+    /// Timeout finding for a transaction that would exceed this fuzzer's guards
+    pub fn check_limits(&self, transaction: &Transaction) -> Option<FuzzFinding> {
+        if transaction.instructions.len() > self.max_instructions {
+            return Some(FuzzFinding::Timeout {
+                reason: format!(
+                    "{} instructions exceeds the limit of {}",
+                    transaction.instructions.len(),
+                    self.max_instructions
+                ),
+            });
+        }
+
+        transaction.instructions.iter().find_map(|instruction| match instruction.data {
+            InstructionData::CreateAccount { space, .. } if space > self.max_allocation => Some(FuzzFinding::Timeout {
+                reason: format!("{} byte allocation exceeds the limit of {}", space, self.max_allocation),
+            }),
+            _ => None,
+        })
+    }
+
+    /// Run `test_fn` on `transaction` unless a guard trips first
+    pub fn run_transaction<F>(&self, transaction: &Transaction, test_fn: F) -> Option<FuzzFinding>
+    where
+        F: Fn(&Transaction) -> Result<()>,
+    {
+        if let Some(finding) = self.check_limits(transaction) {
+            return Some(finding);
+        }
+        test_fn(transaction).err().map(|e| FuzzFinding::Failure { error: e.to_string() })
+    }
+
+    /// Transaction of 1 to `MAX_GENERATED_INSTRUCTIONS` instructions mixing
+    /// system instructions with calls to random programs
     pub fn generate_random_transaction(&self) -> Transaction {
-        let program_id = Pubkey::new_unique();
-        let account = Pubkey::new_unique();
-        
-        let instruction = Instruction {
-            program_id,
-            accounts: vec![AccountMeta {
-                pubkey: account,
-                is_signer: true,
-                is_writable: true,
-            }],
-            data: InstructionData::Generic {
-                data: vec![1, 2, 3, 4],
-            },
-        };
+        let mut rng = rand::thread_rng();
+        let instruction_count = rng.gen_range(1..=MAX_GENERATED_INSTRUCTIONS);
+
+        // The payer plus two keys per instruction
+        let keys: Vec<Pubkey> = (0..=2 * instruction_count).map(|_| Pubkey::new_unique()).collect();
+        let instructions = (0..instruction_count)
+            .map(|_| random_instruction(&mut rng, &keys))
+            .collect();
 
         Transaction {
-            instructions: vec![instruction],
+            instructions,
             signatures: vec![[0u8; 64]],
-            payer: account.0,
+            payer: keys[0].0,
             recent_blockhash: [1u8; 32],
         }
     }
 
-    pub fn run_fuzz_test<F>(&self, name: &str, test_fn: F)
+    /// Fuzz `test_fn`, returning every finding
+    pub fn run_fuzz_test<F>(&self, name: &str, test_fn: F) -> Vec<FuzzFinding>
     where
         F: Fn(&Transaction) -> Result<()>,
     {
         println!("Running fuzz test: {}", name);
+        let mut findings = Vec::new();
         for _i in 0..self.iterations {
             let transaction = self.generate_random_transaction();
-            if let Err(e) = test_fn(&transaction) {
-                println!("Fuzz test failed: {}", e);
+            if let Some(finding) = self.run_transaction(&transaction, &test_fn) {
+                println!("Fuzz test finding: {:?}", finding);
+                findings.push(finding);
             }
         }
         println!("Completed {} iterations of {}", self.iterations, name);
+        findings
+    }
+}
+
+/// A key from `keys`, or about one time in eight a key outside the
+/// transaction, so error paths for unknown accounts get exercised
+fn random_key(rng: &mut impl Rng, keys: &[Pubkey]) -> Pubkey {
+    if rng.gen_ratio(1, 8) {
+        Pubkey::new_unique()
+    } else {
+        keys[rng.gen_range(0..keys.len())]
+    }
+}
+
+fn random_instruction(rng: &mut impl Rng, keys: &[Pubkey]) -> Instruction {
+    let system_program = Pubkey::new(SYSTEM_PROGRAM_ID);
+    let signer = |pubkey| AccountMeta { pubkey, is_signer: true, is_writable: true };
+    let writable = |pubkey| AccountMeta { pubkey, is_signer: false, is_writable: true };
+
+    match rng.gen_range(0..4) {
+        0 => {
+            let (from, to) = (random_key(rng, keys), random_key(rng, keys));
+            Instruction {
+                program_id: system_program,
+                accounts: vec![signer(from), writable(to)],
+                data: InstructionData::Transfer { from: from.0, to: to.0, lamports: rng.gen_range(0..=1_000_000) },
+            }
+        }
+        1 => {
+            let (from, to) = (random_key(rng, keys), random_key(rng, keys));
+            Instruction {
+                program_id: system_program,
+                accounts: vec![signer(from), signer(to)],
+                data: InstructionData::CreateAccount {
+                    from: from.0,
+                    to: to.0,
+                    lamports: rng.gen_range(0..=1_000_000),
+                    space: rng.gen_range(0..=10_240),
+                    owner: random_key(rng, keys).0,
+                },
+            }
+        }
+        2 => {
+            let account = random_key(rng, keys);
+            Instruction {
+                program_id: system_program,
+                accounts: vec![signer(account)],
+                data: InstructionData::Assign { account: account.0, owner: random_key(rng, keys).0 },
+            }
+        }
+        _ => {
+            let accounts = (0..rng.gen_range(0..=3))
+                .map(|_| AccountMeta {
+                    pubkey: random_key(rng, keys),
+                    is_signer: rng.gen(),
+                    is_writable: rng.gen(),
+                })
+                .collect();
+            let data = (0..rng.gen_range(0..16)).map(|_| rng.gen()).collect();
+            Instruction {
+                program_id: Pubkey::new_unique(),
+                accounts,
+                data: InstructionData::Generic { data },
+            }
+        }
     }
 }
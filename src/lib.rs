@@ -1,33 +1,99 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Without the `std` feature only the transaction parser, the system
+//! program and the types they share are built, on `core` and `alloc`.
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod account_store;
+pub mod address_lookup_table;
+pub mod compute_budget;
+pub mod describe;
+#[cfg(feature = "std")]
+pub mod bpf_interpreter;
+#[cfg(feature = "std")]
 pub mod conformance;
+#[cfg(feature = "std")]
 pub mod firedancer_integration;
+#[cfg(feature = "std")]
 pub mod firedancer_bindings;
+#[cfg(feature = "std")]
 pub mod integrated_runtime;
 pub mod system_program;
+#[cfg(feature = "std")]
 pub mod runtime;
 pub mod solana_format;
+pub mod spl_token;
 pub mod types;
+pub mod wire;
+#[cfg(feature = "std")]
 pub mod crypto;
+#[cfg(feature = "std")]
 pub mod fuzzing;
+pub mod memo;
+#[cfg(feature = "std")]
+pub mod precompiles;
+#[cfg(feature = "std")]
+pub mod program_registry;
+#[cfg(feature = "std")]
 pub mod real_bpf_vm; // Real Solana BPF VM integration
+#[cfg(feature = "std")]
+pub mod risk_analyzer;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod transaction_builder;
 
 // WASM-specific modules
 #[cfg(feature = "wasm")]
 pub mod wasm_runtime;
 
+/// `alloc` items the std prelude would otherwise provide
+pub(crate) mod prelude {
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
+use prelude::*;
+
 // Export public API
 pub use types::*;
+#[cfg(feature = "std")]
 pub use crypto::*;
+#[cfg(feature = "std")]
 pub use runtime::*;
-pub use integrated_runtime::IntegratedRuntime;
-pub use conformance::ConformanceHarness;
-pub use firedancer_integration::{FiredancerCrypto, FiredancerValidator, FiredancerConformanceTest};
-pub use solana_format::{SolanaTransaction, SolanaTransactionParser, SolanaPubkey, SolanaHash};
-pub use system_program::{SystemProgram, SystemInstruction, SYSTEM_PROGRAM_ID};
-pub use real_bpf_vm::RealBpfVm;
+#[cfg(feature = "std")]
+pub use account_store::{AccountStore, CowAccountStore};
+#[cfg(feature = "std")]
+pub use integrated_runtime::{check_cpi_accounts, replay_transaction, IntegratedRuntime, NativeProgram, RuntimeStats, MAX_PROCESSING_AGE, RECENT_TRANSFERS};
+#[cfg(feature = "std")]
+pub use conformance::{ConformanceDiff, ConformanceHarness, Expect, Fixture, FixtureEffects, TestOutcome};
+#[cfg(feature = "std")]
+pub use firedancer_integration::{FiredancerCrypto, FiredancerValidator, FiredancerConformanceTest, ValidationReport};
+pub use solana_format::{ParseError, SolanaTransaction, SolanaTransactionParser, SolanaPubkey, SolanaHash, MAX_ACCOUNTS, MAX_INSTRUCTIONS, PACKET_DATA_SIZE};
+pub use system_program::{SystemProgram, SystemInstruction, NonceState, NonceVersions, SYSTEM_PROGRAM_ID, MAX_PERMITTED_DATA_LENGTH, NONCE_STATE_SIZE};
+#[cfg(feature = "std")]
+pub use program_registry::{ProgramInfo, ProgramRegistry, TrustLevel};
+#[cfg(feature = "std")]
+pub use real_bpf_vm::{BpfBackend, RealBpfVm};
+#[cfg(feature = "std")]
+pub use transaction_builder::{TransactionBuilder, SolanaInstruction, SolanaAccountMeta};
+pub use address_lookup_table::{AddressLookupTable, AddressLookupTableProgram, LookupTableInstruction, ADDRESS_LOOKUP_TABLE_PROGRAM_ID};
+pub use compute_budget::{ComputeBudgetInstruction, ComputeBudgetLimits, COMPUTE_BUDGET_PROGRAM_ID};
+pub use spl_token::{TokenInstruction, TokenTransfer};
+pub use memo::{MemoProgram, MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID};
+pub use describe::describe_instruction;
+#[cfg(feature = "std")]
+pub use precompiles::{Ed25519Program, Secp256k1Program, ED25519_PROGRAM_ID, SECP256K1_PROGRAM_ID};
+#[cfg(feature = "std")]
+pub use risk_analyzer::{TransactionRiskAnalyzer, RiskReport, RiskFactor, Blacklist, AddressList, ListMode, FlagReason};
 
 // WASM exports
 #[cfg(feature = "wasm")]
-pub use wasm_runtime::WasmRuntime;
+pub use wasm_runtime::{wasm_blake3, wasm_capabilities, wasm_sha256, wasm_verify_ed25519, WasmRuntime};
 
 #[cfg(feature = "firedancer")]
 pub use firedancer_bindings::{FiredancerCrypto as FiredancerCryptoNative, FiredancerVM, FiredancerAccountManager};
@@ -43,9 +109,21 @@ pub enum TerminatorError {
     #[error("Insufficient funds")]
     InsufficientFunds,
     
+    #[error("Compute budget exceeded")]
+    ComputeBudgetExceeded,
+    
+    #[error("Invalid account index")]
+    InvalidAccountIndex,
+    
+    #[error("Attempt to write to readonly account")]
+    ReadonlyAccountModified,
+    
     #[error("Invalid signature")]
     InvalidSignature,
     
+    #[error("Malformed public key: {0}")]
+    MalformedPublicKey(String),
+    
     #[error("Program error: {0}")]
     ProgramError(String),
     
@@ -65,11 +143,38 @@ pub enum TerminatorError {
     WasmError(String),
 }
 
-pub type Result<T> = std::result::Result<T, TerminatorError>;
+pub type Result<T> = core::result::Result<T, TerminatorError>;
+
+/// Implementation actually serving signature verification and hashing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum CryptoBackend {
+    /// Firedancer's C libraries, linked and self-tested
+    Firedancer,
+    /// In-crate Rust stand-ins for the Firedancer interface
+    Stub,
+    /// Pure Rust crypto, with no Firedancer interface in use
+    PureRust,
+}
+
+impl CryptoBackend {
+    pub fn detect() -> Self {
+        #[cfg(feature = "std")]
+        {
+            firedancer_bindings::active_backend()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            CryptoBackend::PureRust
+        }
+    }
+}
 
 /// Runtime configuration and feature detection
+#[derive(serde::Serialize)]
 pub struct RuntimeCapabilities {
+    /// Real Firedancer libraries are linked, not just the `firedancer` feature
     pub firedancer_available: bool,
+    pub crypto_backend: CryptoBackend,
     pub crypto_acceleration: bool,
     pub bpf_vm: bool,
     pub account_management: bool,
@@ -78,15 +183,26 @@ pub struct RuntimeCapabilities {
 
 impl RuntimeCapabilities {
     pub fn detect() -> Self {
+        let crypto_backend = CryptoBackend::detect();
+        let firedancer_available = cfg!(feature = "firedancer") && crypto_backend == CryptoBackend::Firedancer;
         RuntimeCapabilities {
-            firedancer_available: cfg!(feature = "firedancer"),
+            firedancer_available,
+            crypto_backend,
             crypto_acceleration: true, // Always available with pure Rust crypto
-            bpf_vm: cfg!(feature = "firedancer"),
+            bpf_vm: firedancer_available,
             account_management: true,
             wasm_mode: cfg!(feature = "wasm"),
         }
     }
     
+    /// The capabilities as a JSON object, one field per flag plus the crypto
+    /// backend's name, for UIs that render them
+    #[cfg(feature = "std")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("capabilities serialize to JSON")
+    }
+    
+    #[cfg(feature = "std")]
     pub fn print_summary(&self) {
         #[cfg(feature = "wasm")]
         {
@@ -101,6 +217,7 @@ impl RuntimeCapabilities {
         {
             println!("🤖 Terminator-Dancer Runtime Capabilities:");
             println!("   🔥 Firedancer Integration:  {}", if self.firedancer_available { "✅ AVAILABLE" } else { "⚠️  Fallback Mode" });
+            println!("   🔑 Crypto Backend:          {:?}", self.crypto_backend);
             println!("   🔐 Crypto Acceleration:     {}", if self.crypto_acceleration { "✅ ENABLED" } else { "❌ DISABLED" });
             println!("   🧠 BPF Virtual Machine:     {}", if self.bpf_vm { "✅ AVAILABLE" } else { "⚠️  Mock Mode" });
             println!("   💾 Account Management:      {}", if self.account_management { "✅ ENABLED" } else { "❌ DISABLED" });
@@ -108,7 +225,7 @@ impl RuntimeCapabilities {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use crate::runtime::*;
@@ -151,6 +268,260 @@ mod tests {
         assert!(result.unwrap().success);
     }
 
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_create_account_rejects_oversized_data() {
+        let mut runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+        let payer = [1u8; 32];
+        let create = |space| Transaction {
+            instructions: vec![Instruction {
+                program_id: Pubkey::system_program(),
+                accounts: vec![],
+                data: InstructionData::CreateAccount { from: payer, to: [2u8; 32], lamports: 1, space, owner: [0u8; 32] },
+            }],
+            signatures: vec![[0u8; 64]],
+            payer,
+            recent_blockhash: [1u8; 32],
+        };
+
+        let too_large = crate::system_program::MAX_PERMITTED_DATA_LENGTH + 1;
+        assert!(runtime.execute_transaction(&create(too_large)).is_err());
+        assert!(runtime.execute_transaction(&create(64)).is_ok());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_transaction_history() {
+        let mut runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+
+        for i in 0..3u8 {
+            let from = Pubkey::new([10 + i; 32]);
+            let to = Pubkey::new([20 + i; 32]);
+            let transaction = Transaction {
+                instructions: vec![Instruction {
+                    program_id: Pubkey::system_program(),
+                    accounts: vec![],
+                    data: InstructionData::Transfer { from: from.0, to: to.0, lamports: 1_000 },
+                }],
+                signatures: vec![[i; 64]],
+                payer: from.0,
+                recent_blockhash: [1u8; 32],
+            };
+            runtime.execute_transaction(&transaction).unwrap();
+        }
+
+        assert_eq!(runtime.history().len(), 3);
+        for (i, entry) in runtime.history().iter().enumerate() {
+            assert!(entry.success);
+            assert_eq!(entry.sequence, i as u64);
+            assert_eq!(entry.fee, 5000);
+            assert_eq!(entry.compute_units_consumed, 1000);
+        }
+        assert_eq!(runtime.get_transaction(&[1u8; 64]).unwrap().sequence, 1);
+
+        runtime.set_history_capacity(2);
+        assert_eq!(runtime.history().len(), 2);
+        assert!(runtime.get_transaction(&[0u8; 64]).is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_committed_transactions_advance_slot() {
+        let mut runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+
+        for i in 0..3u8 {
+            let transaction = Transaction {
+                instructions: vec![Instruction {
+                    program_id: Pubkey::new([8u8; 32]),
+                    accounts: vec![],
+                    data: InstructionData::Generic { data: vec![] },
+                }],
+                signatures: vec![[i; 64]],
+                payer: [7u8; 32],
+                recent_blockhash: [1u8; 32],
+            };
+            runtime.execute_transaction(&transaction).unwrap();
+        }
+
+        let slots: Vec<u64> = runtime.history().iter().map(|entry| entry.slot).collect();
+        assert_eq!(slots, vec![0, 1, 2]);
+        assert_eq!(runtime.get_sysvar_clock().slot, 3);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_huge_slots_per_transaction_advances_in_one_step() {
+        let path = write_temp_config("slots", r#"
+[runtime]
+compute_budget = 200000
+max_transaction_size = 1232
+enable_fuzzing = false
+conformance_testing = false
+require_valid_signatures = false
+slots_per_transaction = 9223372036854775807
+"#);
+        let mut runtime = TerminatorRuntime::new(&path).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        for i in 0..3u8 {
+            let transaction = Transaction {
+                instructions: vec![],
+                signatures: vec![[i; 64]],
+                payer: [7u8; 32],
+                recent_blockhash: [1u8; 32],
+            };
+            runtime.execute_transaction(&transaction).unwrap();
+        }
+
+        // The clock stops at the last slot rather than wrapping
+        assert_eq!(runtime.get_sysvar_clock().slot, u64::MAX);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_transaction_queue_concurrent_submit() {
+        let runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+        let (queue, worker) = runtime.spawn_queue(2);
+
+        let submitters: Vec<_> = (0..8u8).map(|i| {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let transaction = Transaction {
+                    instructions: vec![Instruction {
+                        program_id: Pubkey::new([8u8; 32]),
+                        accounts: vec![],
+                        data: InstructionData::Generic { data: vec![i] },
+                    }],
+                    signatures: vec![[i; 64]],
+                    payer: [7u8; 32],
+                    recent_blockhash: [1u8; 32],
+                };
+                queue.submit(transaction).await.unwrap().await.unwrap()
+            })
+        }).collect();
+        for submitter in submitters {
+            assert!(submitter.await.unwrap().unwrap().success);
+        }
+
+        drop(queue);
+        let runtime = worker.await.unwrap();
+        assert_eq!(runtime.history().len(), 8);
+        let sequences: Vec<u64> = runtime.history().iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, (0..8).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_transaction_queue_zero_capacity() {
+        let runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+        let (queue, worker) = runtime.spawn_queue(0);
+
+        let transaction = Transaction {
+            instructions: vec![],
+            signatures: vec![[1u8; 64]],
+            payer: [7u8; 32],
+            recent_blockhash: [1u8; 32],
+        };
+        assert!(queue.submit(transaction).await.unwrap().await.unwrap().unwrap().success);
+
+        drop(queue);
+        assert_eq!(worker.await.unwrap().history().len(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_clock_sysvar() {
+        let mut runtime = TerminatorRuntime::new("nonexistent_config.toml").await.unwrap();
+        assert_eq!(runtime.get_sysvar_clock().slot, 0);
+
+        runtime.advance_slot();
+        runtime.advance_slot();
+        assert_eq!(runtime.get_sysvar_clock().slot, 2);
+
+        let account = Pubkey::new([7u8; 32]);
+        let transaction = Transaction {
+            instructions: vec![Instruction {
+                program_id: Pubkey::new([8u8; 32]),
+                accounts: vec![],
+                data: InstructionData::Generic { data: vec![] },
+            }],
+            signatures: vec![[0u8; 64]],
+            payer: account.0,
+            recent_blockhash: [1u8; 32],
+        };
+        let result = runtime.execute_transaction(&transaction).unwrap();
+        assert!(result.logs.iter().any(|log| log.ends_with("at slot 2")));
+        assert_eq!(runtime.history().back().unwrap().slot, 2);
+    }
+
+    fn write_temp_config(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("terminator_dancer_{}_{}.toml", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_config_defaults_when_missing() {
+        let config = RuntimeConfig::load("nonexistent_config.toml").unwrap();
+        assert_eq!(config.runtime.compute_budget, 1_400_000);
+        assert!(!config.runtime.require_valid_signatures);
+        assert_eq!(config.runtime.max_call_depth, 4);
+        assert!(config.runtime.enable_jit);
+        assert_eq!(config.runtime.slots_per_transaction, 1);
+        assert_eq!(config.bank.fee_lamports_per_signature, 5000);
+    }
+
+    #[test]
+    fn test_config_from_valid_file() {
+        let path = write_temp_config("valid", r#"
+[runtime]
+compute_budget = 200000
+max_transaction_size = 1232
+enable_fuzzing = false
+conformance_testing = false
+require_valid_signatures = true
+max_call_depth = 2
+enable_jit = false
+
+[bank]
+initial_lamports = 0
+rent_collection_enabled = false
+fee_rate_governor_enabled = false
+fee_lamports_per_signature = 7500
+"#);
+        let config = RuntimeConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(config.runtime.compute_budget, 200_000);
+        assert!(config.runtime.require_valid_signatures);
+        assert_eq!(config.runtime.max_call_depth, 2);
+        assert!(!config.runtime.enable_jit);
+        assert_eq!(config.bank.fee_lamports_per_signature, 7500);
+        // Sections left out of the file keep their defaults
+        assert_eq!(config.logging.level, "info");
+
+        let runtime = IntegratedRuntime::with_config(&config).unwrap();
+        assert_eq!(runtime.compute_budget(), 200_000);
+    }
+
+    #[test]
+    fn test_config_malformed_file() {
+        let path = write_temp_config("malformed", "[runtime\ncompute_budget = \"lots\"");
+        let result = RuntimeConfig::load(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(TerminatorError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_config_unknown_log_level() {
+        let result = RuntimeConfig::from_toml_str("[logging]\nlevel = \"loud\"\nenable_transaction_logs = true\nenable_execution_traces = false");
+        match result {
+            Err(TerminatorError::SerializationError(msg)) => assert_eq!(msg, "Unknown log level: loud"),
+            other => panic!("expected the log level to be rejected, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_conformance_harness() {
         let mut harness = ConformanceHarness::new();
@@ -162,14 +533,75 @@ mod tests {
         assert_eq!(harness.failed, 1);
     }
 
+    #[test]
+    fn test_captured_fixture_round_trips() {
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        let tx = SolanaTransactionParser::create_transfer_transaction(from, to, 250_000, SolanaHash([0u8; 32]));
+        let tx_bytes = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        let pre_state = [(from.into(), Account::new(1_000_000, Vec::new(), SYSTEM_PROGRAM_ID))];
+
+        let fixture = ConformanceHarness::capture_fixture(&tx_bytes, &pre_state).unwrap();
+        assert!(fixture.effects.success);
+        let (_, recipient) = fixture.effects.post_accounts.iter().find(|(key, _)| *key == to.into()).unwrap();
+        assert_eq!(recipient.as_ref().unwrap().lamports, 250_000);
+
+        let loaded = Fixture::from_json(&fixture.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, fixture);
+
+        let mut tampered = loaded.clone();
+        tampered.effects.compute_units_consumed += 1;
+        let mut diverged = loaded.clone();
+        if let Some((_, Some(account))) = diverged.effects.post_accounts.iter_mut().find(|(key, _)| *key == to.into()) {
+            account.lamports += 1;
+        }
+        let error = diverged.verify().unwrap_err().to_string();
+        assert!(error.contains("lamports: -1"), "{}", error);
+
+        let mut harness = ConformanceHarness::new();
+        harness.run_fixture("captured_transfer", &loaded);
+        harness.run_fixture("tampered_transfer", &tampered);
+        assert_eq!((harness.passed, harness.failed), (1, 1));
+    }
+
     #[test]
     fn test_fuzzer() {
-        use crate::fuzzing::RuntimeFuzzer;
+        use crate::fuzzing::{RuntimeFuzzer, MAX_GENERATED_INSTRUCTIONS};
         let fuzzer = RuntimeFuzzer::new(5);
         assert_eq!(fuzzer.iterations, 5);
         
         let transaction = fuzzer.generate_random_transaction();
-        assert_eq!(transaction.instructions.len(), 1);
+        assert!((1..=MAX_GENERATED_INSTRUCTIONS).contains(&transaction.instructions.len()));
+    }
+    
+    #[test]
+    fn test_fuzzer_generates_multi_instruction_transactions() {
+        use crate::fuzzing::RuntimeFuzzer;
+        let fuzzer = RuntimeFuzzer::new(1);
+        
+        // All 100 being single-instruction has probability 8^-100
+        assert!((0..100).any(|_| fuzzer.generate_random_transaction().instructions.len() > 1));
+    }
+    
+    #[test]
+    fn test_fuzzer_guards_huge_allocation() {
+        use crate::fuzzing::{FuzzFinding, RuntimeFuzzer};
+        let fuzzer = RuntimeFuzzer::new(1);
+        let mut transaction = fuzzer.generate_random_transaction();
+        transaction.instructions[0].data = InstructionData::CreateAccount {
+            from: transaction.payer,
+            to: [2u8; 32],
+            lamports: 1,
+            space: 1 << 30,
+            owner: [0u8; 32],
+        };
+        
+        let finding = fuzzer.run_transaction(&transaction, |_| panic!("guarded input was executed"));
+        assert!(matches!(finding, Some(FuzzFinding::Timeout { .. })));
+        
+        let fuzzer = fuzzer.with_max_instructions(0);
+        let finding = fuzzer.run_transaction(&fuzzer.generate_random_transaction(), |_| Ok(()));
+        assert!(matches!(finding, Some(FuzzFinding::Timeout { .. })));
     }
     
     #[test]
@@ -178,4 +610,25 @@ mod tests {
         assert!(caps.crypto_acceleration);
         assert!(caps.account_management);
     }
+    
+    #[test]
+    fn test_capabilities_json() {
+        let caps = RuntimeCapabilities::detect();
+        let json: serde_json::Value = serde_json::from_str(&caps.to_json()).unwrap();
+        
+        for flag in ["crypto_acceleration", "bpf_vm", "account_management", "firedancer_available", "wasm_mode"] {
+            assert!(json[flag].is_boolean(), "{} missing from {}", flag, json);
+        }
+        assert_eq!(json["account_management"], true);
+        assert_eq!(json["crypto_backend"], format!("{:?}", caps.crypto_backend));
+    }
+    
+    #[cfg(not(firedancer_linked))]
+    #[test]
+    fn test_capabilities_report_stub_backend_without_libraries() {
+        let caps = RuntimeCapabilities::detect();
+        assert_eq!(caps.crypto_backend, CryptoBackend::Stub);
+        assert!(!caps.firedancer_available);
+        assert!(!caps.bpf_vm);
+    }
 }
@@ -0,0 +1,87 @@
+/// SPL Memo decoding
+/// Memos carry arbitrary text that wallets show to users; the program only checks signers
+
+use crate::prelude::*;
+use crate::{Result, TerminatorError};
+use crate::solana_format::SolanaTransaction;
+use crate::types::{AccountMeta, ExecutionContext};
+
+/// SPL Memo program ID (MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr)
+pub const MEMO_PROGRAM_ID: [u8; 32] = [
+    5, 74, 83, 90, 153, 41, 33, 6, 77, 36, 232, 113, 96, 218, 56, 124,
+    124, 53, 181, 221, 188, 146, 187, 129, 228, 31, 168, 64, 65, 5, 68, 141,
+];
+
+/// Legacy SPL Memo v1 program ID (Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo)
+pub const MEMO_V1_PROGRAM_ID: [u8; 32] = [
+    5, 74, 83, 80, 248, 93, 200, 130, 214, 20, 165, 86, 114, 120, 138, 41,
+    109, 223, 30, 171, 171, 208, 166, 6, 120, 136, 73, 50, 244, 238, 246, 160,
+];
+
+/// Whether `program_id` is either version of the Memo program
+pub fn is_memo_program(program_id: &[u8; 32]) -> bool {
+    *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID
+}
+
+/// Memo text, with invalid UTF-8 replaced rather than rejected
+pub fn decode_memo(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).into_owned()
+}
+
+/// Memo program processor
+pub struct MemoProgram;
+
+impl MemoProgram {
+    /// Log the memo; every account passed to the instruction must have signed
+    pub fn process_instruction(
+        instruction_data: &[u8],
+        account_metas: &[AccountMeta],
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_metas.iter().any(|meta| !meta.is_signer) {
+            return Err(TerminatorError::ProgramError("Memo accounts must sign".to_string()));
+        }
+
+        let memo = decode_memo(instruction_data);
+        context.log(format!("Program log: Memo (len {}): {:?}", memo.len(), memo));
+        Ok(())
+    }
+}
+
+/// Every memo in a transaction, in instruction order
+pub fn transaction_memos(tx: &SolanaTransaction) -> Vec<String> {
+    tx.message
+        .instructions
+        .iter()
+        .filter(|ix| {
+            tx.message
+                .account_keys
+                .get(ix.program_id_index as usize)
+                .is_some_and(|program| is_memo_program(&program.0))
+        })
+        .map(|ix| decode_memo(&ix.data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_format::{CompiledInstruction, SolanaHash, SolanaPubkey};
+
+    #[test]
+    fn test_transaction_memos() {
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        let mut tx = crate::SolanaTransactionParser::create_transfer_transaction(from, to, 1_000, SolanaHash([0u8; 32]));
+        tx.message.account_keys.push(SolanaPubkey::new(MEMO_PROGRAM_ID));
+        let memo_index = (tx.message.account_keys.len() - 1) as u8;
+        tx.message.header.num_readonly_unsigned_accounts += 1;
+        for data in [b"invoice #42".to_vec(), vec![b'o', b'k', 0xFF]] {
+            tx.message.instructions.push(CompiledInstruction { program_id_index: memo_index, accounts: vec![0], data });
+        }
+
+        assert_eq!(transaction_memos(&tx), vec!["invoice #42".to_string(), "ok\u{FFFD}".to_string()]);
+        assert_eq!(SolanaPubkey::new(MEMO_PROGRAM_ID).to_string(), "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+        assert_eq!(SolanaPubkey::new(MEMO_V1_PROGRAM_ID).to_string(), "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+    }
+}
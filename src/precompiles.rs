@@ -0,0 +1,274 @@
+/// Signature Verification Precompiles
+/// Native ed25519 and secp256k1 verifiers whose offsets may point into sibling instructions
+
+use crate::{Result, TerminatorError};
+use crate::crypto::SolanaCrypto;
+use crate::types::ExecutionContext;
+use crate::wire::{read_u16_le, write_u16_le};
+
+/// Ed25519 precompile program ID (Ed25519SigVerify111111111111111111111111111)
+pub const ED25519_PROGRAM_ID: [u8; 32] = [
+    3, 125, 70, 214, 124, 147, 251, 190, 18, 249, 66, 143, 131, 141, 64, 255,
+    5, 112, 116, 73, 39, 244, 138, 100, 252, 202, 112, 68, 128, 0, 0, 0,
+];
+
+/// Secp256k1 precompile program ID (KeccakSecp256k11111111111111111111111111111)
+pub const SECP256K1_PROGRAM_ID: [u8; 32] = [
+    4, 198, 252, 32, 240, 80, 204, 240, 85, 132, 215, 33, 28, 159, 140, 245,
+    158, 193, 71, 133, 187, 22, 106, 30, 40, 48, 232, 18, 32, 0, 0, 0,
+];
+
+/// Ed25519 instruction index meaning "this instruction"
+pub const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Count and padding bytes ahead of the ed25519 offsets
+const ED25519_OFFSETS_START: usize = 2;
+/// Seven little-endian u16s per ed25519 signature
+const ED25519_OFFSETS_SIZE: usize = 14;
+/// Count byte ahead of the secp256k1 offsets
+const SECP256K1_OFFSETS_START: usize = 1;
+/// Three u16s and three u8 instruction indices per secp256k1 signature
+const SECP256K1_OFFSETS_SIZE: usize = 11;
+const ETH_ADDRESS_SIZE: usize = 20;
+
+fn invalid_data(reason: &str) -> TerminatorError {
+    TerminatorError::ProgramError(format!("Invalid precompile instruction data: {}", reason))
+}
+
+/// `size` bytes at `offset` in instruction `index`'s data, or in
+/// `current` for `CURRENT_INSTRUCTION`
+fn data_slice<'a>(
+    context: &'a ExecutionContext,
+    current: &'a [u8],
+    index: u16,
+    offset: u16,
+    size: usize,
+) -> Result<&'a [u8]> {
+    let data = if index == CURRENT_INSTRUCTION {
+        current
+    } else {
+        &context.get_instruction(index as usize)
+            .ok_or_else(|| invalid_data("instruction index out of range"))?
+            .data
+    };
+    let start = offset as usize;
+    data.get(start..start + size).ok_or_else(|| invalid_data("offset out of bounds"))
+}
+
+/// Ed25519 signature verification precompile
+pub struct Ed25519Program;
+
+impl Ed25519Program {
+    /// Verify every signature the offsets describe, failing on the first bad one
+    pub fn process_instruction(instruction_data: &[u8], context: &ExecutionContext) -> Result<()> {
+        if instruction_data.len() < ED25519_OFFSETS_START {
+            return Err(invalid_data("missing header"));
+        }
+        let num_signatures = instruction_data[0] as usize;
+        if num_signatures == 0 && instruction_data.len() > ED25519_OFFSETS_START {
+            return Err(invalid_data("no signatures"));
+        }
+        if instruction_data.len() < ED25519_OFFSETS_START + num_signatures * ED25519_OFFSETS_SIZE {
+            return Err(invalid_data("truncated offsets"));
+        }
+
+        for i in 0..num_signatures {
+            let at = ED25519_OFFSETS_START + i * ED25519_OFFSETS_SIZE;
+            let field = |n: usize| read_u16_le(instruction_data, at + 2 * n);
+
+            let signature = data_slice(context, instruction_data, field(1)?, field(0)?, 64)?;
+            let pubkey = data_slice(context, instruction_data, field(3)?, field(2)?, 32)?;
+            let message = data_slice(context, instruction_data, field(6)?, field(4)?, field(5)? as usize)?;
+
+            let verified = SolanaCrypto::verify_ed25519_signature(
+                signature.try_into().unwrap(),
+                message,
+                pubkey.try_into().unwrap(),
+            )?;
+            if !verified {
+                return Err(TerminatorError::InvalidSignature);
+            }
+        }
+        Ok(())
+    }
+
+    /// Instruction data verifying one signature, with every field inline
+    pub fn instruction_data(pubkey: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+        let pubkey_offset = ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE;
+        let signature_offset = pubkey_offset + pubkey.len();
+        let message_offset = signature_offset + signature.len();
+
+        let mut data = vec![1, 0];
+        for field in [
+            signature_offset as u16,
+            CURRENT_INSTRUCTION,
+            pubkey_offset as u16,
+            CURRENT_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            write_u16_le(&mut data, field);
+        }
+        data.extend_from_slice(pubkey);
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        data
+    }
+}
+
+/// Secp256k1 (Ethereum-style) signature recovery precompile
+pub struct Secp256k1Program;
+
+impl Secp256k1Program {
+    /// Recover each signer and check it against the expected Ethereum address
+    pub fn process_instruction(instruction_data: &[u8], context: &ExecutionContext) -> Result<()> {
+        let Some(&count) = instruction_data.first() else {
+            return Err(invalid_data("missing header"));
+        };
+        let num_signatures = count as usize;
+        if num_signatures == 0 && instruction_data.len() > SECP256K1_OFFSETS_START {
+            return Err(invalid_data("no signatures"));
+        }
+        if instruction_data.len() < SECP256K1_OFFSETS_START + num_signatures * SECP256K1_OFFSETS_SIZE {
+            return Err(invalid_data("truncated offsets"));
+        }
+
+        for i in 0..num_signatures {
+            let at = SECP256K1_OFFSETS_START + i * SECP256K1_OFFSETS_SIZE;
+            let offsets = &instruction_data[at..at + SECP256K1_OFFSETS_SIZE];
+
+            // Offsets are u16 at 0, 3, 6 and 8; instruction indices are u8 at 2, 5 and 10
+            let signature = data_slice(context, instruction_data, offsets[2] as u16, read_u16_le(offsets, 0)?, 65)?;
+            let eth_address = data_slice(context, instruction_data, offsets[5] as u16, read_u16_le(offsets, 3)?, ETH_ADDRESS_SIZE)?;
+            let message = data_slice(
+                context,
+                instruction_data,
+                offsets[10] as u16,
+                read_u16_le(offsets, 6)?,
+                read_u16_le(offsets, 8)? as usize,
+            )?;
+
+            let message_hash = SolanaCrypto::keccak256_hash(message);
+            let pubkey = SolanaCrypto::secp256k1_recover(&message_hash, signature[..64].try_into().unwrap(), signature[64])?;
+            if eth_address != Self::eth_address(&pubkey) {
+                return Err(TerminatorError::InvalidSignature);
+            }
+        }
+        Ok(())
+    }
+
+    /// Ethereum address of an uncompressed secp256k1 public key
+    pub fn eth_address(pubkey: &[u8; 64]) -> [u8; ETH_ADDRESS_SIZE] {
+        let mut address = [0u8; ETH_ADDRESS_SIZE];
+        address.copy_from_slice(&SolanaCrypto::keccak256_hash(pubkey)[32 - ETH_ADDRESS_SIZE..]);
+        address
+    }
+
+    /// Instruction data verifying one signature inline, for the instruction at
+    /// `instruction_index` (secp256k1 offsets cannot refer to "this instruction")
+    pub fn instruction_data(
+        eth_address: &[u8; ETH_ADDRESS_SIZE],
+        signature: &[u8; 64],
+        recovery_id: u8,
+        message: &[u8],
+        instruction_index: u8,
+    ) -> Vec<u8> {
+        let eth_address_offset = SECP256K1_OFFSETS_START + SECP256K1_OFFSETS_SIZE;
+        let signature_offset = eth_address_offset + ETH_ADDRESS_SIZE;
+        let message_offset = signature_offset + signature.len() + 1;
+
+        let mut data = vec![1];
+        write_u16_le(&mut data, signature_offset as u16);
+        data.push(instruction_index);
+        write_u16_le(&mut data, eth_address_offset as u16);
+        data.push(instruction_index);
+        write_u16_le(&mut data, message_offset as u16);
+        write_u16_le(&mut data, message.len() as u16);
+        data.push(instruction_index);
+        data.extend_from_slice(eth_address);
+        data.extend_from_slice(signature);
+        data.push(recovery_id);
+        data.extend_from_slice(message);
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_format::CompiledInstruction;
+    use ed25519_dalek::Signer;
+
+    fn context_with(datas: Vec<Vec<u8>>) -> ExecutionContext {
+        let instructions = datas.into_iter()
+            .map(|data| CompiledInstruction { program_id_index: 0, accounts: Vec::new(), data })
+            .collect();
+        ExecutionContext::new(1_000).with_instructions(instructions)
+    }
+
+    #[test]
+    fn test_ed25519_precompile_verifies_signature() {
+        let keypair = crate::testing::payer();
+        let message = b"withdraw 5 SOL";
+        let signature = keypair.sign(message).to_bytes();
+        let data = Ed25519Program::instruction_data(&keypair.verifying_key().to_bytes(), &signature, message);
+
+        let context = context_with(vec![data.clone()]);
+        assert!(Ed25519Program::process_instruction(&data, &context).is_ok());
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            Ed25519Program::process_instruction(&tampered, &context),
+            Err(TerminatorError::InvalidSignature)
+        ));
+        assert!(Ed25519Program::process_instruction(&data[..20], &context).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_precompile_reads_sibling_instruction() {
+        let keypair = crate::testing::alice();
+        let message = b"order #7";
+        let signature = keypair.sign(message).to_bytes();
+
+        // The message lives in instruction 1; the precompile only carries the key and signature
+        let mut data = Ed25519Program::instruction_data(&keypair.verifying_key().to_bytes(), &signature, b"");
+        data[10..12].copy_from_slice(&0u16.to_le_bytes());
+        data[12..14].copy_from_slice(&(message.len() as u16).to_le_bytes());
+        data[14..16].copy_from_slice(&1u16.to_le_bytes());
+
+        let context = context_with(vec![data.clone(), message.to_vec()]);
+        assert!(Ed25519Program::process_instruction(&data, &context).is_ok());
+
+        let context = context_with(vec![data.clone(), b"order #8".to_vec()]);
+        assert!(Ed25519Program::process_instruction(&data, &context).is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_precompile_recovers_signer() {
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let message = b"bridge transfer";
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(&SolanaCrypto::keccak256_hash(message))
+            .unwrap();
+        let mut pubkey = [0u8; 64];
+        pubkey.copy_from_slice(&signing_key.verifying_key().to_encoded_point(false).as_bytes()[1..]);
+        let eth_address = Secp256k1Program::eth_address(&pubkey);
+
+        let data = Secp256k1Program::instruction_data(
+            &eth_address,
+            &signature.to_bytes().into(),
+            recovery_id.to_byte(),
+            message,
+            0,
+        );
+        let context = context_with(vec![data.clone()]);
+        assert!(Secp256k1Program::process_instruction(&data, &context).is_ok());
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        let context = context_with(vec![tampered.clone()]);
+        assert!(Secp256k1Program::process_instruction(&tampered, &context).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+/// Program Registry
+/// Names and trust levels of well-known programs, so they are not mistaken for unknown code
+
+use crate::address_lookup_table::ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+use crate::compute_budget::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::memo::{MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID};
+use crate::precompiles::{ED25519_PROGRAM_ID, SECP256K1_PROGRAM_ID};
+use crate::solana_format::SolanaPubkey;
+use crate::system_program::SYSTEM_PROGRAM_ID;
+use std::collections::HashMap;
+
+/// Associated Token Account program ID (ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL)
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: [u8; 32] = [
+    140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131,
+    11, 90, 19, 153, 218, 255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+];
+
+/// Token-2022 program ID (TokenzQdBNbLqP5VEhdkAS6EPFLC1PL5hyN4ygyoSYM)
+pub const TOKEN_2022_PROGRAM_ID: [u8; 32] = [
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218,
+    182, 26, 252, 77, 131, 185, 13, 220, 217, 239, 69, 28, 158, 24, 60, 46,
+];
+
+/// Stake program ID (Stake11111111111111111111111111111111111111)
+pub const STAKE_PROGRAM_ID: [u8; 32] = [
+    6, 161, 216, 23, 145, 55, 84, 42, 152, 52, 55, 189, 254, 42, 122, 178,
+    85, 127, 83, 92, 138, 120, 114, 43, 104, 164, 157, 192, 0, 0, 0, 0,
+];
+
+/// Vote program ID (Vote111111111111111111111111111111111111111)
+pub const VOTE_PROGRAM_ID: [u8; 32] = [
+    7, 97, 72, 29, 53, 116, 116, 187, 124, 77, 118, 36, 235, 211, 189, 179,
+    216, 53, 94, 115, 209, 16, 67, 252, 13, 163, 83, 128, 0, 0, 0, 0,
+];
+
+/// Upgradeable BPF loader program ID (BPFLoaderUpgradeab1e11111111111111111111111)
+pub const BPF_LOADER_UPGRADEABLE_PROGRAM_ID: [u8; 32] = [
+    2, 168, 246, 145, 78, 136, 161, 176, 226, 16, 21, 62, 247, 99, 174, 43,
+    0, 194, 185, 61, 22, 193, 36, 210, 192, 83, 122, 16, 4, 128, 0, 0,
+];
+
+/// How far a registered program is trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustLevel {
+    /// Native program or precompile built into the runtime
+    Builtin,
+    /// Canonical SPL program deployed by Solana Labs
+    Canonical,
+    /// Registered as trusted by the user
+    Trusted,
+}
+
+/// A registered program's display name and trust level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInfo {
+    pub name: String,
+    pub trust: TrustLevel,
+}
+
+/// Program ids mapped to what is known about them
+#[derive(Debug, Clone)]
+pub struct ProgramRegistry {
+    programs: HashMap<[u8; 32], ProgramInfo>,
+}
+
+impl Default for ProgramRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgramRegistry {
+    /// Registry preloaded with the canonical Solana programs
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        for (program_id, name) in [
+            (SYSTEM_PROGRAM_ID, "System Program"),
+            (COMPUTE_BUDGET_PROGRAM_ID, "Compute Budget Program"),
+            (ADDRESS_LOOKUP_TABLE_PROGRAM_ID, "Address Lookup Table Program"),
+            (STAKE_PROGRAM_ID, "Stake Program"),
+            (VOTE_PROGRAM_ID, "Vote Program"),
+            (BPF_LOADER_UPGRADEABLE_PROGRAM_ID, "BPF Upgradeable Loader"),
+            (ED25519_PROGRAM_ID, "Ed25519 Program"),
+            (SECP256K1_PROGRAM_ID, "Secp256k1 Program"),
+        ] {
+            registry.insert(program_id, name, TrustLevel::Builtin);
+        }
+        for (program_id, name) in [
+            (SolanaPubkey::token_program().0, "Token Program"),
+            (TOKEN_2022_PROGRAM_ID, "Token-2022 Program"),
+            (ASSOCIATED_TOKEN_PROGRAM_ID, "Associated Token Account Program"),
+            (MEMO_PROGRAM_ID, "Memo Program"),
+            (MEMO_V1_PROGRAM_ID, "Memo Program v1"),
+        ] {
+            registry.insert(program_id, name, TrustLevel::Canonical);
+        }
+        registry
+    }
+
+    /// Registry with no programs at all
+    pub fn empty() -> Self {
+        ProgramRegistry { programs: HashMap::new() }
+    }
+
+    /// Register `program_id` as a user-trusted program, replacing any
+    /// existing entry
+    pub fn register(&mut self, program_id: [u8; 32], name: impl Into<String>) {
+        self.insert(program_id, name, TrustLevel::Trusted);
+    }
+
+    fn insert(&mut self, program_id: [u8; 32], name: impl Into<String>, trust: TrustLevel) {
+        self.programs.insert(program_id, ProgramInfo { name: name.into(), trust });
+    }
+
+    pub fn lookup(&self, program_id: &[u8; 32]) -> Option<ProgramInfo> {
+        self.programs.get(program_id).cloned()
+    }
+
+    pub fn is_known(&self, program_id: &[u8; 32]) -> bool {
+        self.programs.contains_key(program_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_known_and_unknown_programs() {
+        let mut registry = ProgramRegistry::new();
+        let token = registry.lookup(&SolanaPubkey::token_program().0).unwrap();
+        assert_eq!(token.name, "Token Program");
+        assert_eq!(token.trust, TrustLevel::Canonical);
+        assert_eq!(SolanaPubkey::new(STAKE_PROGRAM_ID).to_string(), "Stake11111111111111111111111111111111111111");
+        assert_eq!(SolanaPubkey::new(ASSOCIATED_TOKEN_PROGRAM_ID).to_string(), "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+        let random = [88u8; 32];
+        assert_eq!(registry.lookup(&random), None);
+        registry.register(random, "My Program");
+        assert_eq!(registry.lookup(&random).map(|info| info.trust), Some(TrustLevel::Trusted));
+    }
+}
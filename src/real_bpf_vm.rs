@@ -2,38 +2,202 @@
 /// Framework ready for Solana rbpf integration - complex API requires more setup
 
 use crate::{Result, TerminatorError};
+use crate::bpf_interpreter;
 use crate::types::{Account, Pubkey};
+use crate::wire::{read_u16_le, read_u64_le};
 use std::collections::HashMap;
+use std::sync::Arc;
+
+/// ELF machine type for BPF programs
+pub const EM_BPF: u16 = 0xF7;
+
+/// Size of an ELF64 file header
+const ELF64_HEADER_SIZE: usize = 64;
+
+/// Size of an ELF64 program header table entry
+const ELF64_PROGRAM_HEADER_SIZE: u16 = 56;
+
+/// Size of an ELF64 section header table entry
+const ELF64_SECTION_HEADER_SIZE: u16 = 64;
+
+/// Validate the ELF64 header of a BPF program
+///
+/// Checks the magic, class, byte order and machine type, and that the
+/// program and section header tables lie within the file.
+pub fn validate_elf_header(bytecode: &[u8]) -> Result<()> {
+    if bytecode.len() < 4 || &bytecode[0..4] != b"\x7fELF" {
+        return Err(TerminatorError::ProgramError("Invalid ELF format".to_string()));
+    }
+    if bytecode.len() < ELF64_HEADER_SIZE {
+        return Err(TerminatorError::ProgramError(format!(
+            "ELF header truncated: {} of {} bytes", bytecode.len(), ELF64_HEADER_SIZE
+        )));
+    }
+    if bytecode[4] != 2 {
+        return Err(TerminatorError::ProgramError("ELF is not 64-bit".to_string()));
+    }
+    if bytecode[5] != 1 {
+        return Err(TerminatorError::ProgramError("ELF is not little-endian".to_string()));
+    }
+
+    let machine = read_u16_le(bytecode, 18)?;
+    if machine != EM_BPF {
+        return Err(TerminatorError::ProgramError(format!(
+            "ELF machine 0x{:x} is not BPF (0x{:x})", machine, EM_BPF
+        )));
+    }
+
+    check_table_bounds(
+        "Program", read_u64_le(bytecode, 32)?, read_u16_le(bytecode, 54)?, ELF64_PROGRAM_HEADER_SIZE,
+        read_u16_le(bytecode, 56)?, bytecode.len(),
+    )?;
+    check_table_bounds(
+        "Section", read_u64_le(bytecode, 40)?, read_u16_le(bytecode, 58)?, ELF64_SECTION_HEADER_SIZE,
+        read_u16_le(bytecode, 60)?, bytecode.len(),
+    )
+}
+
+/// Check a header table of `count` entries of `entry_size` bytes at `offset`
+/// fits in the file, each entry at least the `min_entry_size` its fields need
+fn check_table_bounds(kind: &str, offset: u64, entry_size: u16, min_entry_size: u16, count: u16, file_len: usize) -> Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+    if entry_size < min_entry_size {
+        return Err(TerminatorError::ProgramError(format!(
+            "{} header entries are {} bytes, smaller than {}", kind, entry_size, min_entry_size
+        )));
+    }
+    let end = (entry_size as u64)
+        .checked_mul(count as u64)
+        .and_then(|size| size.checked_add(offset));
+    match end {
+        Some(end) if end <= file_len as u64 => Ok(()),
+        _ => Err(TerminatorError::ProgramError(format!(
+            "{} headers out of bounds: {} entries of {} bytes at offset {} in a {}-byte file",
+            kind, count, entry_size, offset, file_len
+        ))),
+    }
+}
+
+/// Executable flag of an ELF section header
+const SHF_EXECINSTR: u64 = 0x4;
+
+/// The instructions of a BPF program: its first executable section, or
+/// everything after the ELF header when it has no section headers
+pub fn program_text(bytecode: &[u8]) -> Result<&[u8]> {
+    validate_elf_header(bytecode)?;
+    let section_offset = read_u64_le(bytecode, 40)? as usize;
+    let (entry_size, count) = (read_u16_le(bytecode, 58)? as usize, read_u16_le(bytecode, 60)? as usize);
+    if count == 0 {
+        return Ok(&bytecode[ELF64_HEADER_SIZE..]);
+    }
+    let no_text = || TerminatorError::ProgramError("ELF has no executable section in bounds".to_string());
+    for i in 0..count {
+        let header = i.checked_mul(entry_size).and_then(|at| at.checked_add(section_offset)).ok_or_else(no_text)?;
+        if read_u64_le(bytecode, header + 8)? & SHF_EXECINSTR != 0 {
+            let (start, size) = (read_u64_le(bytecode, header + 24)? as usize, read_u64_le(bytecode, header + 32)? as usize);
+            return start.checked_add(size)
+                .and_then(|end| bytecode.get(start..end))
+                .ok_or_else(no_text);
+        }
+    }
+    Err(no_text())
+}
+
+/// How `RealBpfVm` runs programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfBackend {
+    /// Metered estimate standing in for the native rbpf VM
+    Native,
+    /// The pure-Rust `bpf_interpreter`, which runs the program's instructions
+    /// on any target, wasm32 included
+    Interpreter,
+}
+
+/// Programs kept verified in the cache before the least recently used is evicted
+pub const DEFAULT_PROGRAM_CACHE_CAPACITY: usize = 256;
+
+/// A deployed program's bytecode, kept for as long as the program id is loaded
+#[derive(Clone)]
+struct DeployedProgram {
+    /// Blake3 hash of `bytecode`, keying its cache entry
+    hash: [u8; 32],
+    bytecode: Arc<[u8]>,
+}
+
+/// A verified program, shared by every program id deployed with its bytecode
+#[derive(Clone)]
+struct CachedProgram {
+    /// Verified bytecode (the compiled executable once rbpf lands)
+    bytecode: Arc<[u8]>,
+    /// Value of the VM's use counter when this entry was last loaded or run
+    last_used: u64,
+}
 
 /// Real BPF VM Interface (ready for solana_rbpf integration)
+#[derive(Clone)]
 pub struct RealBpfVm {
-    /// Loaded programs cache (bytecode storage)
-    programs: HashMap<Pubkey, Vec<u8>>,
+    /// Program id -> its bytecode, which outlives the bytecode's cache entry
+    programs: HashMap<Pubkey, DeployedProgram>,
+    /// Verified programs keyed by bytecode hash, evicted least recently used
+    /// first and re-verified on their next use
+    cache: HashMap<[u8; 32], CachedProgram>,
+    cache_capacity: usize,
+    use_counter: u64,
     /// VM configuration flags
     enable_jit: bool,
     max_call_depth: u32,
+    backend: BpfBackend,
 }
 
 impl RealBpfVm {
     /// Create new BPF VM interface
     pub fn new() -> Result<Self> {
+        Self::with_settings(true, 64)
+    }
+
+    /// Create BPF VM interface with explicit JIT and call depth settings
+    pub fn with_settings(enable_jit: bool, max_call_depth: u32) -> Result<Self> {
         Ok(RealBpfVm {
             programs: HashMap::new(),
-            enable_jit: true,
-            max_call_depth: 64,
+            cache: HashMap::new(),
+            cache_capacity: DEFAULT_PROGRAM_CACHE_CAPACITY,
+            use_counter: 0,
+            enable_jit,
+            max_call_depth,
+            backend: BpfBackend::Native,
         })
     }
 
+    /// Run programs with `backend`
+    pub fn with_backend(mut self, backend: BpfBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Bound the number of verified programs kept in the cache (at least one)
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity.max(1);
+        self
+    }
+
     /// Load a BPF program from bytecode
+    ///
+    /// Bytecode already in the cache is reused without re-verification.
     pub fn load_program(&mut self, program_id: &Pubkey, bytecode: &[u8]) -> Result<()> {
-        // Validate ELF format (basic check)
-        if bytecode.len() < 4 || &bytecode[0..4] != b"\x7fELF" {
-            return Err(TerminatorError::ProgramError("Invalid ELF format".to_string()));
-        }
+        let hash = *blake3::hash(bytecode).as_bytes();
+        let cached = self.cache.get(&hash).map(|cached| Arc::clone(&cached.bytecode));
+        let from_cache = cached.is_some();
+        let shared = cached.unwrap_or_else(|| Arc::from(bytecode));
 
-        // Store bytecode for execution (ready for real solana_rbpf integration)
-        self.programs.insert(*program_id, bytecode.to_vec());
-        
+        self.verify_cached(hash, &shared)?;
+        self.programs.insert(*program_id, DeployedProgram { hash, bytecode: shared });
+
+        if from_cache {
+            println!("📦 BPF program loaded from cache: {:?} ({} bytes)", program_id, bytecode.len());
+            return Ok(());
+        }
         println!("📦 BPF program loaded: {:?} ({} bytes)", program_id, bytecode.len());
         if self.enable_jit {
             println!("⚡ JIT compilation enabled for performance");
@@ -42,22 +206,74 @@ impl RealBpfVm {
         Ok(())
     }
 
+    /// Mark `hash`'s cache entry as just used, verifying `bytecode` into the
+    /// cache first if it isn't there
+    fn verify_cached(&mut self, hash: [u8; 32], bytecode: &Arc<[u8]>) -> Result<()> {
+        self.use_counter += 1;
+        if let Some(cached) = self.cache.get_mut(&hash) {
+            cached.last_used = self.use_counter;
+            return Ok(());
+        }
+
+        validate_elf_header(bytecode)?;
+
+        // Store verified bytecode for execution (ready for real solana_rbpf integration)
+        if self.cache.len() >= self.cache_capacity {
+            self.evict_least_recently_used();
+        }
+        self.cache.insert(hash, CachedProgram { bytecode: Arc::clone(bytecode), last_used: self.use_counter });
+        Ok(())
+    }
+
+    /// Drop the least recently used cache entry; programs using it stay
+    /// loaded and are verified again when next run
+    fn evict_least_recently_used(&mut self) {
+        let Some(hash) = self.cache.iter().min_by_key(|(_, cached)| cached.last_used).map(|(hash, _)| *hash) else {
+            return;
+        };
+        self.cache.remove(&hash);
+    }
+
     /// Execute a BPF program (interface ready for solana_rbpf integration)
+    ///
+    /// Units are drawn from `remaining_compute_units` as the program runs;
+    /// running out halts execution with a `ComputeBudgetExceeded` error.
+    /// Program log messages are appended to `logs` as they are emitted, so
+    /// they survive a failure. Returns the units consumed.
     pub fn execute_program(
-        &self,
+        &mut self,
         program_id: &Pubkey,
         instruction_data: &[u8],
         accounts: &mut [Account],
+        remaining_compute_units: &mut u64,
+        logs: &mut Vec<String>,
     ) -> Result<u64> {
         // Get loaded program bytecode
-        let bytecode = self.programs.get(program_id)
+        let program = self.programs.get(program_id)
             .ok_or_else(|| TerminatorError::ProgramError("Program not loaded".to_string()))?;
+        let (hash, bytecode) = (program.hash, Arc::clone(&program.bytecode));
+        self.verify_cached(hash, &bytecode)?;
 
         println!("🚀 Executing BPF program: {:?}", program_id);
         println!("📋 Program size: {} bytes", bytecode.len());
         println!("📝 Instruction data: {} bytes", instruction_data.len());
         println!("👥 Accounts involved: {}", accounts.len());
 
+        if self.backend == BpfBackend::Interpreter {
+            // Only the instruction data is mapped as input; accounts are not serialized
+            let units_before = *remaining_compute_units;
+            let return_value = bpf_interpreter::execute(
+                program_text(&bytecode)?,
+                instruction_data,
+                remaining_compute_units,
+                logs,
+            )?;
+            if return_value != 0 {
+                return Err(TerminatorError::ProgramError(format!("Program returned error code {}", return_value)));
+            }
+            return Ok(units_before - *remaining_compute_units);
+        }
+
         // HONEST: This is the interface ready for real solana_rbpf integration
         // The real implementation would:
         // 1. Parse ELF bytecode with solana_rbpf::elf::Executable
@@ -65,8 +281,15 @@ impl RealBpfVm {
         // 3. Execute bytecode with compute unit metering
         // 4. Handle account mutations properly
         
-        // For now: Simulate basic program execution
-        let compute_units_used = instruction_data.len() as u64 * 10; // Realistic estimate
+        // For now: Simulate basic program execution, metering an estimated
+        // 10 units per byte of instruction data
+        logs.push(format!("Program log: Processing {} bytes of instruction data", instruction_data.len()));
+        let compute_units_used = instruction_data.len() as u64 * 10;
+        if compute_units_used > *remaining_compute_units {
+            *remaining_compute_units = 0;
+            return Err(TerminatorError::ComputeBudgetExceeded);
+        }
+        *remaining_compute_units -= compute_units_used;
         
         // Basic account mutation simulation (for system-like operations)
         if instruction_data.len() > 0 && accounts.len() >= 2 {
@@ -80,15 +303,20 @@ impl RealBpfVm {
 
 
 
-    /// Get loaded program count
+    /// Get the number of distinct programs in the cache
     pub fn loaded_program_count(&self) -> usize {
-        self.programs.len()
+        self.cache.len()
     }
 
     /// Check if program is loaded
     pub fn is_program_loaded(&self, program_id: &Pubkey) -> bool {
         self.programs.contains_key(program_id)
     }
+
+    /// Get the bytecode of a loaded program
+    pub fn program_bytecode(&self, program_id: &Pubkey) -> Option<&[u8]> {
+        self.programs.get(program_id).map(|program| &*program.bytecode)
+    }
 }
 
 /// Example: Load and execute a simple BPF program
@@ -109,9 +337,21 @@ impl RealBpfVm {
         // This is a simplified bytecode that would normally be compiled from Rust
         // In practice, you'd compile Solana programs with `cargo build-bpf`
         vec![
-            // ELF header and minimal BPF instructions
+            // ELF header
             0x7f, 0x45, 0x4c, 0x46, // ELF magic
+            0x02, 0x01, 0x01, 0x00, // 64-bit, little-endian, SYSV ABI
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0xf7, 0x00, // ET_REL, EM_BPF
+            0x01, 0x00, 0x00, 0x00, // e_version
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // e_entry
+            0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // e_phoff
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // e_shoff
+            0x00, 0x00, 0x00, 0x00, // e_flags
+            0x40, 0x00, 0x38, 0x00, // e_ehsize, e_phentsize
+            0x00, 0x00, 0x40, 0x00, // e_phnum, e_shentsize
+            0x00, 0x00, 0x00, 0x00, // e_shnum, e_shstrndx
             // ... rest would be actual compiled BPF bytecode
+            0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // exit
         ]
     }
 }
@@ -137,4 +377,152 @@ mod tests {
         // Expected to fail with invalid bytecode
         assert!(result.is_err());
     }
+
+    fn minimal_elf() -> Vec<u8> {
+        RealBpfVm::new().unwrap().create_hello_world_bytecode()
+    }
+
+    fn elf_error(bytecode: &[u8]) -> String {
+        match validate_elf_header(bytecode) {
+            Err(TerminatorError::ProgramError(msg)) => msg,
+            other => panic!("expected a ProgramError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_elf_validation() {
+        let elf = minimal_elf();
+        assert!(validate_elf_header(&elf).is_ok());
+        assert!(RealBpfVm::new().unwrap().load_program(&Pubkey::new([1; 32]), &elf).is_ok());
+
+        assert_eq!(elf_error(&elf[..40]), "ELF header truncated: 40 of 64 bytes");
+
+        let mut wrong_machine = elf.clone();
+        wrong_machine[18] = 0x3e; // x86-64
+        assert_eq!(elf_error(&wrong_machine), "ELF machine 0x3e is not BPF (0xf7)");
+
+        let mut elf32 = elf.clone();
+        elf32[4] = 1;
+        assert_eq!(elf_error(&elf32), "ELF is not 64-bit");
+
+        let mut big_endian = elf.clone();
+        big_endian[5] = 2;
+        assert_eq!(elf_error(&big_endian), "ELF is not little-endian");
+
+        // One program header claimed where only the exit instruction follows
+        let mut phdr_overflow = elf.clone();
+        phdr_overflow[56] = 1;
+        assert!(elf_error(&phdr_overflow).starts_with("Program headers out of bounds"));
+
+        let mut shdr_overflow = elf.clone();
+        shdr_overflow[40] = 0xff;
+        shdr_overflow[60] = 2;
+        assert!(elf_error(&shdr_overflow).starts_with("Section headers out of bounds"));
+
+        // A one-byte section header entry fits the file but not its fields
+        let mut tiny_shdr = elf[..65].to_vec();
+        tiny_shdr[40] = 64;
+        tiny_shdr[58] = 1;
+        tiny_shdr[60] = 1;
+        assert_eq!(elf_error(&tiny_shdr), "Section header entries are 1 bytes, smaller than 64");
+        assert!(program_text(&tiny_shdr).is_err());
+    }
+
+    #[test]
+    fn test_identical_bytecode_shares_cache_entry() {
+        let mut vm = RealBpfVm::new().unwrap();
+        let (first, second) = (Pubkey::new([1; 32]), Pubkey::new([2; 32]));
+
+        vm.load_program(&first, &minimal_elf()).unwrap();
+        vm.load_program(&second, &minimal_elf()).unwrap();
+
+        assert_eq!(vm.loaded_program_count(), 1);
+        assert!(vm.is_program_loaded(&first) && vm.is_program_loaded(&second));
+        assert_eq!(vm.program_bytecode(&first), vm.program_bytecode(&second));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut vm = RealBpfVm::new().unwrap().with_cache_capacity(2);
+        let programs: Vec<(Pubkey, Vec<u8>)> = (1..=3u8)
+            .map(|i| {
+                let mut elf = minimal_elf();
+                elf.push(i); // distinct bytecode per program
+                (Pubkey::new([i; 32]), elf)
+            })
+            .collect();
+
+        vm.load_program(&programs[0].0, &programs[0].1).unwrap();
+        vm.load_program(&programs[1].0, &programs[1].1).unwrap();
+        // Running the first program makes the second the least recently used
+        vm.execute_program(&programs[0].0, &[], &mut [], &mut 0, &mut Vec::new()).unwrap();
+        vm.load_program(&programs[2].0, &programs[2].1).unwrap();
+
+        assert_eq!(vm.loaded_program_count(), 2);
+        assert!(vm.cache.contains_key(blake3::hash(&programs[0].1).as_bytes()));
+        assert!(!vm.cache.contains_key(blake3::hash(&programs[1].1).as_bytes()));
+        // Eviction only drops the verified entry; the program stays deployed
+        assert!(programs.iter().all(|(program_id, _)| vm.is_program_loaded(program_id)));
+    }
+
+    #[test]
+    fn test_evicted_program_still_executes() {
+        let capacity = 2;
+        let mut vm = RealBpfVm::new().unwrap().with_cache_capacity(capacity);
+        let programs: Vec<(Pubkey, Vec<u8>)> = (1..=capacity as u8 + 1)
+            .map(|i| {
+                let mut elf = minimal_elf();
+                elf.push(i);
+                (Pubkey::new([i; 32]), elf)
+            })
+            .collect();
+        for (program_id, bytecode) in &programs {
+            vm.load_program(program_id, bytecode).unwrap();
+        }
+
+        let first = &programs[0];
+        assert!(!vm.cache.contains_key(blake3::hash(&first.1).as_bytes()));
+        vm.execute_program(&first.0, &[], &mut [], &mut 0, &mut Vec::new()).unwrap();
+        assert_eq!(vm.program_bytecode(&first.0), Some(first.1.as_slice()));
+        assert!(vm.cache.contains_key(blake3::hash(&first.1).as_bytes()));
+        assert_eq!(vm.loaded_program_count(), capacity);
+    }
+
+    #[test]
+    fn test_interpreter_backend_runs_program_text() {
+        let mut vm = RealBpfVm::new().unwrap().with_backend(BpfBackend::Interpreter);
+        let header = vm.create_hello_world_bytecode()[..ELF64_HEADER_SIZE].to_vec();
+        let program_id = Pubkey::new([0x43; 32]);
+
+        // ldxb r0, [r1]; exit: returns the first input byte
+        let mut bytecode = header.clone();
+        bytecode.extend_from_slice(&[0x71, 0x10, 0, 0, 0, 0, 0, 0]);
+        bytecode.extend_from_slice(&[0x95, 0, 0, 0, 0, 0, 0, 0]);
+        vm.load_program(&program_id, &bytecode).unwrap();
+        assert_eq!(program_text(&bytecode).unwrap().len(), 16);
+
+        let mut remaining = 1_000;
+        assert_eq!(vm.execute_program(&program_id, &[0], &mut [], &mut remaining, &mut Vec::new()).unwrap(), 2);
+        assert_eq!(remaining, 998);
+
+        let result = vm.execute_program(&program_id, &[7], &mut [], &mut remaining, &mut Vec::new());
+        assert!(matches!(result, Err(TerminatorError::ProgramError(msg)) if msg == "Program returned error code 7"));
+    }
+
+    #[test]
+    fn test_compute_budget_exceeded() {
+        let mut vm = RealBpfVm::new().unwrap();
+        let program_id = vm.load_hello_world_program().unwrap();
+
+        let mut remaining = 1_000;
+        assert_eq!(vm.execute_program(&program_id, &[0u8; 8], &mut [], &mut remaining, &mut Vec::new()).unwrap(), 80);
+        assert_eq!(remaining, 920);
+
+        let mut remaining = 50;
+        let mut logs = Vec::new();
+        let result = vm.execute_program(&program_id, &[0u8; 8], &mut [], &mut remaining, &mut logs);
+        assert!(matches!(result, Err(TerminatorError::ComputeBudgetExceeded)));
+        assert_eq!(remaining, 0);
+        assert_eq!(logs, vec!["Program log: Processing 8 bytes of instruction data".to_string()]);
+    }
 } 
\ No newline at end of file
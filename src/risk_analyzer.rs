@@ -0,0 +1,650 @@
+/// Transaction Risk Analysis
+/// Scores a transaction before signing, flagging drains, unknown programs and other red flags
+
+use crate::{Result, TerminatorError};
+use crate::integrated_runtime::IntegratedRuntime;
+use crate::memo::transaction_memos;
+use crate::program_registry::ProgramRegistry;
+use crate::solana_format::{SolanaPubkey, SolanaTransaction};
+use crate::spl_token::TokenTransfer;
+use crate::system_program::SystemInstruction;
+use crate::types::Pubkey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Highest score a `RiskReport` can carry
+pub const MAX_RISK_SCORE: u8 = 10;
+
+/// Why an address list flagged a pubkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagReason {
+    /// Address is on a deny list
+    Denylisted,
+    /// Address is missing from an allow list
+    NotAllowlisted,
+}
+
+impl std::fmt::Display for FlagReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlagReason::Denylisted => write!(f, "address is on the deny list"),
+            FlagReason::NotAllowlisted => write!(f, "address is not on the allow list"),
+        }
+    }
+}
+
+/// Source of address reputation for the risk analyzer
+pub trait Blacklist {
+    /// Reason `pubkey` should be treated as suspicious, if any
+    fn is_flagged(&self, pubkey: &SolanaPubkey) -> Option<FlagReason>;
+}
+
+/// A plain set of addresses is a deny list
+impl Blacklist for HashSet<SolanaPubkey> {
+    fn is_flagged(&self, pubkey: &SolanaPubkey) -> Option<FlagReason> {
+        self.contains(pubkey).then_some(FlagReason::Denylisted)
+    }
+}
+
+/// How an `AddressList` interprets its entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMode {
+    /// Flag listed addresses
+    Deny,
+    /// Flag every address that isn't listed
+    Allow,
+}
+
+/// Deny or allow list of addresses, optionally loaded from disk
+#[derive(Debug, Clone)]
+pub struct AddressList {
+    mode: ListMode,
+    addresses: HashSet<SolanaPubkey>,
+}
+
+impl AddressList {
+    pub fn new(mode: ListMode, addresses: impl IntoIterator<Item = SolanaPubkey>) -> Self {
+        AddressList {
+            mode,
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+
+    /// Load newline-delimited base58 addresses; blank lines and `#` comments are skipped
+    pub fn load_from_file(path: &str, mode: ListMode) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| TerminatorError::SerializationError(format!("Failed to read {}: {}", path, e)))?;
+        Self::parse(&contents, mode)
+    }
+
+    /// Parse newline-delimited base58 addresses
+    pub fn parse(contents: &str, mode: ListMode) -> Result<Self> {
+        let mut addresses = HashSet::new();
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let pubkey = SolanaPubkey::from_str(line).map_err(|e| {
+                TerminatorError::SerializationError(format!("Invalid address on line {}: {}", line_number + 1, e))
+            })?;
+            addresses.insert(pubkey);
+        }
+        Ok(AddressList { mode, addresses })
+    }
+
+    pub fn mode(&self) -> ListMode {
+        self.mode
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+}
+
+impl Blacklist for AddressList {
+    fn is_flagged(&self, pubkey: &SolanaPubkey) -> Option<FlagReason> {
+        match (self.mode, self.addresses.contains(pubkey)) {
+            (ListMode::Deny, true) => Some(FlagReason::Denylisted),
+            (ListMode::Allow, false) => Some(FlagReason::NotAllowlisted),
+            _ => None,
+        }
+    }
+}
+
+/// A single red flag raised by the analyzer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RiskFactor {
+    /// Transaction moves at least the configured large-amount threshold
+    LargeAmount { lamports: u64 },
+    /// An SPL Token transfer moves at least the configured token threshold
+    LargeTokenTransfer { source: SolanaPubkey, destination: SolanaPubkey, amount: u64 },
+    /// An account key was flagged by the blacklist
+    SuspiciousRecipient { address: SolanaPubkey, reason: FlagReason },
+    /// Transaction moves a large share of the payer's balance
+    HighDrainPercentage { percentage: f64 },
+    /// Instruction targets a program outside the known-safe set
+    UnknownProgram { program: SolanaPubkey },
+    /// Transaction would fail if executed
+    SimulationFailure { reason: String },
+    /// Transaction carries more than one instruction
+    ComplexStructure { instruction_count: usize },
+    /// A transfer's source and destination are the same account
+    SelfTransfer { instruction: usize, account: SolanaPubkey },
+    /// A system transfer moves no lamports
+    ZeroLamportTransfer { instruction: usize },
+    /// An instruction repeats an earlier one exactly
+    DuplicateInstruction { instruction: usize, original: usize },
+}
+
+impl RiskFactor {
+    /// Contribution of this factor to the overall score
+    pub fn weight(&self) -> u8 {
+        match self {
+            RiskFactor::LargeAmount { .. } => 3,
+            RiskFactor::LargeTokenTransfer { .. } => 3,
+            RiskFactor::SuspiciousRecipient { .. } => 3,
+            RiskFactor::HighDrainPercentage { .. } => 3,
+            RiskFactor::UnknownProgram { .. } => 8,
+            RiskFactor::SimulationFailure { .. } => 4,
+            RiskFactor::ComplexStructure { .. } => 1,
+            RiskFactor::SelfTransfer { .. } => 2,
+            RiskFactor::ZeroLamportTransfer { .. } => 1,
+            RiskFactor::DuplicateInstruction { .. } => 2,
+        }
+    }
+}
+
+/// Outcome of analyzing a transaction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskReport {
+    /// 0 (safe) to 10 (very dangerous)
+    pub score: u8,
+    pub factors: Vec<RiskFactor>,
+    /// What the transaction's memos claim it is, for display; not scored
+    #[serde(default)]
+    pub memos: Vec<String>,
+}
+
+impl RiskReport {
+    fn from_factors(factors: Vec<RiskFactor>, memos: Vec<String>) -> Self {
+        let total: u32 = factors.iter().map(|f| f.weight() as u32).sum();
+        RiskReport {
+            score: total.min(MAX_RISK_SCORE as u32) as u8,
+            factors,
+            memos,
+        }
+    }
+
+    /// No risk factors were raised
+    pub fn is_safe(&self) -> bool {
+        self.factors.is_empty()
+    }
+}
+
+/// Pre-signing risk analyzer for wallets and agents
+pub struct TransactionRiskAnalyzer {
+    large_amount_threshold: u64,
+    large_token_amount_threshold: u64,
+    drain_percentage_threshold: f64,
+    programs: ProgramRegistry,
+    blacklist: Option<Box<dyn Blacklist>>,
+}
+
+impl Default for TransactionRiskAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionRiskAnalyzer {
+    /// Create an analyzer with the default thresholds (0.5 SOL, 1M base token units, 50% drain)
+    pub fn new() -> Self {
+        TransactionRiskAnalyzer {
+            large_amount_threshold: 500_000_000,
+            large_token_amount_threshold: 1_000_000,
+            drain_percentage_threshold: 50.0,
+            programs: ProgramRegistry::new(),
+            blacklist: None,
+        }
+    }
+
+    /// Flag transactions moving at least `lamports`
+    pub fn with_large_amount_threshold(mut self, lamports: u64) -> Self {
+        self.large_amount_threshold = lamports;
+        self
+    }
+
+    /// Flag token transfers moving at least `amount` base units
+    pub fn with_large_token_amount_threshold(mut self, amount: u64) -> Self {
+        self.large_token_amount_threshold = amount;
+        self
+    }
+
+    /// Flag transactions draining at least `percentage` of the payer's balance
+    pub fn with_drain_percentage_threshold(mut self, percentage: f64) -> Self {
+        self.drain_percentage_threshold = percentage;
+        self
+    }
+
+    /// Treat `program` as known-safe
+    pub fn with_known_program(mut self, program: SolanaPubkey) -> Self {
+        self.programs.register(program.0, program.to_string());
+        self
+    }
+
+    /// Judge programs against `registry` instead of the canonical programs
+    pub fn with_program_registry(mut self, registry: ProgramRegistry) -> Self {
+        self.programs = registry;
+        self
+    }
+
+    /// Consult `blacklist` for every non-signer, non-program account key
+    pub fn with_blacklist(mut self, blacklist: impl Blacklist + 'static) -> Self {
+        self.blacklist = Some(Box::new(blacklist));
+        self
+    }
+
+    /// Run every check and score the transaction
+    pub fn analyze(&self, tx: &SolanaTransaction, runtime: &IntegratedRuntime) -> RiskReport {
+        let mut factors = Vec::new();
+        factors.extend(self.check_large_amount(tx));
+        factors.extend(self.check_large_token_transfers(tx));
+        factors.extend(self.check_suspicious_recipients(tx));
+        factors.extend(self.check_drain_percentage(tx, runtime));
+        factors.extend(self.check_unknown_programs(tx));
+        factors.extend(self.check_simulation(tx, runtime));
+        factors.extend(self.check_structure(tx));
+        factors.extend(self.check_padding(tx));
+        RiskReport::from_factors(factors, transaction_memos(tx))
+    }
+
+    /// Flag a transaction whose system transfers total at least the threshold
+    pub fn check_large_amount(&self, tx: &SolanaTransaction) -> Option<RiskFactor> {
+        let lamports = self.total_outgoing_lamports(tx);
+        (lamports >= self.large_amount_threshold).then_some(RiskFactor::LargeAmount { lamports })
+    }
+
+    /// Flag every SPL Token transfer moving at least the token threshold
+    pub fn check_large_token_transfers(&self, tx: &SolanaTransaction) -> Vec<RiskFactor> {
+        self.token_transfers(tx)
+            .into_iter()
+            .filter(|transfer| transfer.amount >= self.large_token_amount_threshold)
+            .map(|transfer| RiskFactor::LargeTokenTransfer {
+                source: transfer.source,
+                destination: transfer.destination,
+                amount: transfer.amount,
+            })
+            .collect()
+    }
+
+    /// Flag every account key the blacklist objects to, signers and program
+    /// ids included
+    ///
+    /// An allow list must name the fee payer and the programs it trusts too.
+    pub fn check_suspicious_recipients(&self, tx: &SolanaTransaction) -> Vec<RiskFactor> {
+        let Some(blacklist) = &self.blacklist else {
+            return Vec::new();
+        };
+
+        tx.message
+            .account_keys
+            .iter()
+            .filter_map(|key| {
+                blacklist
+                    .is_flagged(key)
+                    .map(|reason| RiskFactor::SuspiciousRecipient { address: *key, reason })
+            })
+            .collect()
+    }
+
+    /// Flag a transaction moving a large share of the fee payer's balance
+    pub fn check_drain_percentage(&self, tx: &SolanaTransaction, runtime: &IntegratedRuntime) -> Option<RiskFactor> {
+        let payer = tx.message.account_keys.first()?;
+        let balance = runtime.get_balance(&Pubkey::from(*payer));
+        let lamports = self.total_outgoing_lamports(tx);
+        if balance == 0 || lamports == 0 {
+            return None;
+        }
+
+        let percentage = lamports as f64 / balance as f64 * 100.0;
+        (percentage >= self.drain_percentage_threshold).then_some(RiskFactor::HighDrainPercentage { percentage })
+    }
+
+    /// Flag every instruction targeting a program the registry doesn't know
+    pub fn check_unknown_programs(&self, tx: &SolanaTransaction) -> Vec<RiskFactor> {
+        let mut seen = HashSet::new();
+        tx.message
+            .instructions
+            .iter()
+            .filter_map(|ix| tx.message.account_keys.get(ix.program_id_index as usize))
+            .filter(|program| !self.programs.is_known(&program.0) && seen.insert(**program))
+            .map(|program| RiskFactor::UnknownProgram { program: *program })
+            .collect()
+    }
+
+    /// Flag a transaction that would fail, simulating it without touching the ledger
+    pub fn check_simulation(&self, tx: &SolanaTransaction, runtime: &IntegratedRuntime) -> Option<RiskFactor> {
+        match runtime.simulate_solana_transaction(tx) {
+            Ok(simulation) => simulation.error.map(|e| RiskFactor::SimulationFailure { reason: e.to_string() }),
+            Err(e) => Some(RiskFactor::SimulationFailure { reason: e.to_string() }),
+        }
+    }
+
+    /// Flag transactions carrying more than one instruction
+    pub fn check_structure(&self, tx: &SolanaTransaction) -> Option<RiskFactor> {
+        let instruction_count = tx.message.instructions.len();
+        (instruction_count > 1).then_some(RiskFactor::ComplexStructure { instruction_count })
+    }
+
+    /// Flag instructions that do nothing useful, which can pad a transaction
+    /// to hide the one that matters: self-transfers, zero-lamport transfers
+    /// and exact repeats of an earlier instruction
+    pub fn check_padding(&self, tx: &SolanaTransaction) -> Vec<RiskFactor> {
+        let Ok(decompiled) = tx.message.decompile_all() else {
+            return Vec::new();
+        };
+        let mut factors = Vec::new();
+        for (instruction, ix) in decompiled.iter().enumerate() {
+            let accounts: Vec<SolanaPubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+            if ix.program_id == SolanaPubkey::system_program() {
+                if let Ok(SystemInstruction::Transfer { lamports }) = SystemInstruction::decode(&ix.data) {
+                    if lamports == 0 {
+                        factors.push(RiskFactor::ZeroLamportTransfer { instruction });
+                    }
+                    if accounts.len() >= 2 && accounts[0] == accounts[1] {
+                        factors.push(RiskFactor::SelfTransfer { instruction, account: accounts[0] });
+                    }
+                }
+            } else if ix.program_id == SolanaPubkey::token_program() {
+                if let Ok(transfer) = TokenTransfer::decode(&ix.data, &accounts) {
+                    if transfer.source == transfer.destination {
+                        factors.push(RiskFactor::SelfTransfer { instruction, account: transfer.source });
+                    }
+                }
+            }
+        }
+
+        let compiled = &tx.message.instructions;
+        for (instruction, ix) in compiled.iter().enumerate() {
+            if let Some(original) = compiled[..instruction].iter().position(|earlier| earlier == ix) {
+                factors.push(RiskFactor::DuplicateInstruction { instruction, original });
+            }
+        }
+        factors
+    }
+
+    /// Token `Transfer`/`TransferChecked` instructions; other token instructions are skipped
+    fn token_transfers(&self, tx: &SolanaTransaction) -> Vec<TokenTransfer> {
+        let Ok(instructions) = tx.message.decompile_all() else {
+            return Vec::new();
+        };
+        instructions
+            .iter()
+            .filter(|ix| ix.program_id == SolanaPubkey::token_program())
+            .filter_map(|ix| {
+                let accounts: Vec<SolanaPubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+                TokenTransfer::decode(&ix.data, &accounts).ok()
+            })
+            .collect()
+    }
+
+    /// Sum of lamports moved by system transfers and account creations
+    fn total_outgoing_lamports(&self, tx: &SolanaTransaction) -> u64 {
+        tx.message
+            .instructions
+            .iter()
+            .filter(|ix| {
+                tx.message.account_keys.get(ix.program_id_index as usize) == Some(&SolanaPubkey::system_program())
+            })
+            .filter_map(|ix| match SystemInstruction::decode(&ix.data).ok()? {
+                SystemInstruction::Transfer { lamports }
+                | SystemInstruction::CreateAccount { lamports, .. }
+                | SystemInstruction::CreateAccountWithSeed { lamports, .. } => Some(lamports),
+                _ => None,
+            })
+            .fold(0u64, |total, lamports| total.saturating_add(lamports))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memo::MEMO_PROGRAM_ID;
+    use crate::solana_format::{
+        CompiledInstruction, MessageHeader, SolanaHash, SolanaMessage, SolanaSignature, SolanaTransactionParser,
+    };
+    use crate::system_program::SYSTEM_PROGRAM_ID;
+
+    #[test]
+    fn test_safe_transfer_verdict() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        runtime.fund_account(&from.into(), 100_000_000);
+
+        let tx = SolanaTransactionParser::create_transfer_transaction(from, to, 10_000_000, SolanaHash([42u8; 32]));
+        let report = TransactionRiskAnalyzer::new().analyze(&tx, &runtime);
+
+        assert!(report.is_safe());
+        assert_eq!(report.score, 0);
+    }
+
+    #[test]
+    fn test_drain_verdict() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let victim = SolanaPubkey::new([10u8; 32]);
+        let attacker = SolanaPubkey::new([66u8; 32]);
+        runtime.fund_account(&victim.into(), 1_100_000_000);
+
+        let tx = SolanaTransactionParser::create_transfer_transaction(victim, attacker, 1_000_000_000, SolanaHash([99u8; 32]));
+        let analyzer = TransactionRiskAnalyzer::new().with_blacklist(HashSet::from([attacker]));
+        let report = analyzer.analyze(&tx, &runtime);
+
+        assert_eq!(report.score, 9);
+        assert!(report.factors.contains(&RiskFactor::LargeAmount { lamports: 1_000_000_000 }));
+        assert!(report.factors.contains(&RiskFactor::SuspiciousRecipient {
+            address: attacker,
+            reason: FlagReason::Denylisted,
+        }));
+        assert!(matches!(
+            analyzer.check_drain_percentage(&tx, &runtime),
+            Some(RiskFactor::HighDrainPercentage { percentage }) if percentage > 90.0
+        ));
+    }
+
+    #[test]
+    fn test_unknown_program_verdict() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let user = SolanaPubkey::new([5u8; 32]);
+        let unknown_program = SolanaPubkey::new([88u8; 32]);
+        runtime.fund_account(&user.into(), 10_000_000);
+
+        let tx = SolanaTransaction {
+            signatures: vec![SolanaSignature([0u8; 64])],
+            message: SolanaMessage {
+                header: MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                },
+                account_keys: vec![user, unknown_program],
+                recent_blockhash: SolanaHash([77u8; 32]),
+                instructions: vec![CompiledInstruction {
+                    program_id_index: 1,
+                    accounts: vec![0],
+                    data: vec![1, 255, 255, 255, 255, 255, 255, 255, 255],
+                }],
+            },
+        };
+        let report = TransactionRiskAnalyzer::new().analyze(&tx, &runtime);
+
+        assert_eq!(report.score, 8);
+        assert_eq!(report.factors, vec![RiskFactor::UnknownProgram { program: unknown_program }]);
+    }
+
+    #[test]
+    fn test_memo_reported() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        runtime.fund_account(&from.into(), 100_000_000);
+
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(from, to, 10_000_000, SolanaHash([42u8; 32]));
+        tx.message.account_keys.push(SolanaPubkey::new(MEMO_PROGRAM_ID));
+        tx.message.header.num_readonly_unsigned_accounts += 1;
+        tx.message.instructions.push(CompiledInstruction {
+            program_id_index: 3,
+            accounts: vec![0],
+            data: b"Airdrop claim".to_vec(),
+        });
+        let report = TransactionRiskAnalyzer::new().analyze(&tx, &runtime);
+
+        assert_eq!(report.memos, vec!["Airdrop claim".to_string()]);
+        assert_eq!(report.factors, vec![RiskFactor::ComplexStructure { instruction_count: 2 }]);
+
+        let result = runtime.execute_solana_transaction_to_result(&tx);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.memos, report.memos);
+    }
+
+    #[test]
+    fn test_self_transfer_flagged() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let user = SolanaPubkey::new([1u8; 32]);
+        runtime.fund_account(&user.into(), 100_000_000);
+
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(user, user, 10_000, SolanaHash([42u8; 32]));
+        tx.message.account_keys.remove(1);
+        tx.message.instructions[0].program_id_index = 1;
+        tx.message.instructions[0].accounts = vec![0, 0];
+        let report = TransactionRiskAnalyzer::new().analyze(&tx, &runtime);
+
+        assert_eq!(report.factors, vec![RiskFactor::SelfTransfer { instruction: 0, account: user }]);
+        assert_eq!(report.score, 2);
+    }
+
+    #[test]
+    fn test_identical_no_op_padding_flagged() {
+        let mut runtime = IntegratedRuntime::new().unwrap();
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        runtime.fund_account(&from.into(), 100_000_000);
+
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(from, to, 10_000, SolanaHash([42u8; 32]));
+        let no_op = CompiledInstruction {
+            program_id_index: 2,
+            accounts: vec![0, 1],
+            data: borsh::to_vec(&SystemInstruction::Transfer { lamports: 0 }).unwrap(),
+        };
+        tx.message.instructions.extend([no_op.clone(), no_op.clone(), no_op]);
+        let report = TransactionRiskAnalyzer::new().analyze(&tx, &runtime);
+
+        assert_eq!(report.factors, vec![
+            RiskFactor::ComplexStructure { instruction_count: 4 },
+            RiskFactor::ZeroLamportTransfer { instruction: 1 },
+            RiskFactor::ZeroLamportTransfer { instruction: 2 },
+            RiskFactor::ZeroLamportTransfer { instruction: 3 },
+            RiskFactor::DuplicateInstruction { instruction: 2, original: 1 },
+            RiskFactor::DuplicateInstruction { instruction: 3, original: 1 },
+        ]);
+        assert_eq!(report.score, 8);
+    }
+
+    #[test]
+    fn test_large_token_transfer_flagged() {
+        use crate::transaction_builder::{SolanaAccountMeta, SolanaInstruction, TransactionBuilder};
+
+        let owner = SolanaPubkey::new([5u8; 32]);
+        let source = SolanaPubkey::new([6u8; 32]);
+        let mint = SolanaPubkey::new([7u8; 32]);
+        let destination = SolanaPubkey::new([8u8; 32]);
+        let mut data = vec![12u8];
+        data.extend_from_slice(&5_000_000u64.to_le_bytes());
+        data.push(6);
+
+        let tx = TransactionBuilder::new(owner, SolanaHash([0u8; 32]))
+            .add_instruction(SolanaInstruction::new(
+                SolanaPubkey::token_program(),
+                vec![
+                    SolanaAccountMeta::new(source, false),
+                    SolanaAccountMeta::new_readonly(mint, false),
+                    SolanaAccountMeta::new(destination, false),
+                    SolanaAccountMeta::new_readonly(owner, true),
+                ],
+                data,
+            ))
+            .build_unsigned();
+
+        let flagged = TransactionRiskAnalyzer::new().check_large_token_transfers(&tx);
+        assert_eq!(flagged, vec![RiskFactor::LargeTokenTransfer { source, destination, amount: 5_000_000 }]);
+        let analyzer = TransactionRiskAnalyzer::new().with_large_token_amount_threshold(10_000_000);
+        assert!(analyzer.check_large_token_transfers(&tx).is_empty());
+    }
+
+    #[test]
+    fn test_address_list_modes() {
+        let payer = SolanaPubkey::new([1u8; 32]);
+        let flagged = SolanaPubkey::new([66u8; 32]);
+        let clean = SolanaPubkey::new([2u8; 32]);
+        let to_flagged = SolanaTransactionParser::create_transfer_transaction(payer, flagged, 1, SolanaHash([0u8; 32]));
+        let to_clean = SolanaTransactionParser::create_transfer_transaction(payer, clean, 1, SolanaHash([0u8; 32]));
+
+        let deny = TransactionRiskAnalyzer::new().with_blacklist(AddressList::new(ListMode::Deny, [flagged]));
+        assert_eq!(
+            deny.check_suspicious_recipients(&to_flagged),
+            vec![RiskFactor::SuspiciousRecipient { address: flagged, reason: FlagReason::Denylisted }]
+        );
+        assert!(deny.check_suspicious_recipients(&to_clean).is_empty());
+
+        let system_program = SolanaPubkey::new(SYSTEM_PROGRAM_ID);
+        let allow = TransactionRiskAnalyzer::new()
+            .with_blacklist(AddressList::new(ListMode::Allow, [payer, system_program, clean]));
+        assert_eq!(
+            allow.check_suspicious_recipients(&to_flagged),
+            vec![RiskFactor::SuspiciousRecipient { address: flagged, reason: FlagReason::NotAllowlisted }]
+        );
+        assert!(allow.check_suspicious_recipients(&to_clean).is_empty());
+    }
+
+    #[test]
+    fn test_blacklist_covers_signers_and_programs() {
+        let payer = SolanaPubkey::new([66u8; 32]);
+        let system_program = SolanaPubkey::new(SYSTEM_PROGRAM_ID);
+        let tx = SolanaTransactionParser::create_transfer_transaction(
+            payer,
+            SolanaPubkey::new([2u8; 32]),
+            1,
+            SolanaHash([0u8; 32]),
+        );
+
+        let analyzer = TransactionRiskAnalyzer::new().with_blacklist(AddressList::new(ListMode::Deny, [payer, system_program]));
+        assert_eq!(
+            analyzer.check_suspicious_recipients(&tx),
+            vec![
+                RiskFactor::SuspiciousRecipient { address: payer, reason: FlagReason::Denylisted },
+                RiskFactor::SuspiciousRecipient { address: system_program, reason: FlagReason::Denylisted },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_address_list_load_from_file() {
+        let flagged = SolanaPubkey::new([66u8; 32]);
+        let path = std::env::temp_dir().join(format!("terminator_dancer_blacklist_{}.txt", std::process::id()));
+        std::fs::write(&path, format!("# known drainers\n{}\n\n", flagged)).unwrap();
+
+        let list = AddressList::load_from_file(path.to_str().unwrap(), ListMode::Deny).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.is_flagged(&flagged), Some(FlagReason::Denylisted));
+        assert_eq!(list.is_flagged(&SolanaPubkey::new([2u8; 32])), None);
+
+        std::fs::write(&path, "not-base58!\n").unwrap();
+        assert!(AddressList::load_from_file(path.to_str().unwrap(), ListMode::Deny).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,13 @@
 use crate::types::*;
+use crate::crypto::SolanaCrypto;
 use crate::{Result, TerminatorError};
+use crate::wire::read_u64_le;
+use std::collections::VecDeque;
 use std::fs;
 use tracing::{info, warn, debug};
 use std::sync::Once;
+#[cfg(feature = "tokio")]
+use tokio::{sync::{mpsc, oneshot}, task::JoinHandle};
 
 static INIT: Once = Once::new();
 
@@ -18,28 +23,58 @@ fn init_logging() {
 #[derive(Debug, Clone)]
 pub struct TerminatorRuntime {
     config: RuntimeConfig,
+    /// `config.logging.level`, parsed when the config is loaded
+    log_level: LogLevel,
     bank_state: BankState,
+    history: VecDeque<ExecutedTransaction>,
+    next_sequence: u64,
+}
+
+impl RuntimeConfig {
+    /// Load configuration from a TOML file
+    ///
+    /// A missing file falls back to `RuntimeConfig::default()`; a file that
+    /// exists but can't be read or parsed is an error.
+    pub fn load(config_path: &str) -> Result<Self> {
+        if fs::metadata(config_path).is_err() {
+            warn!("Config file {} not found, using defaults", config_path);
+            return Ok(RuntimeConfig::default());
+        }
+        
+        let config_str = fs::read_to_string(config_path)
+            .map_err(|e| TerminatorError::SerializationError(e.to_string()))?;
+        Self::from_toml_str(&config_str)
+    }
+    
+    /// Parse configuration from a TOML string, rejecting an unknown log level
+    pub fn from_toml_str(config_str: &str) -> Result<Self> {
+        let config: Self = toml::from_str(config_str)
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid config: {}", e)))?;
+        config.logging.level.parse::<LogLevel>()?;
+        Ok(config)
+    }
+}
+
+impl GenesisConfig {
+    /// Parse a genesis ledger from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid genesis: {}", e)))
+    }
 }
 
 impl TerminatorRuntime {
     pub async fn new(config_path: &str) -> Result<Self> {
         // Initialize logging for native builds only
-        #[cfg(all(feature = "tracing-subscriber", not(feature = "wasm")))]
-        tracing_subscriber::fmt::init();
+        init_logging();
         
-        let config = if fs::metadata(config_path).is_ok() {
-            let config_str = fs::read_to_string(config_path)
-                .map_err(|e| TerminatorError::SerializationError(e.to_string()))?;
-            toml::from_str(&config_str)
-                .map_err(|e| TerminatorError::SerializationError(e.to_string()))?
-        } else {
-            warn!("Config file {} not found, using defaults", config_path);
-            RuntimeConfig::default()
-        };
+        let config = RuntimeConfig::load(config_path)?;
+        let log_level = config.logging.level.parse()?;
         
         info!("Initializing Terminator Runtime with config: {:?}", config);
         
         let mut bank_state = BankState::new();
+        bank_state.fee_calculator.lamports_per_signature = config.bank.fee_lamports_per_signature;
         // Initialize with some lamports from config
         if config.bank.initial_lamports > 0 {
                     let system_account = Pubkey::system_program();
@@ -51,10 +86,18 @@ impl TerminatorRuntime {
         
         Ok(Self {
             config,
+            log_level,
             bank_state,
+            history: VecDeque::new(),
+            next_sequence: 0,
         })
     }
 
+    /// Get the loaded runtime configuration
+    pub fn config(&self) -> &RuntimeConfig {
+        &self.config
+    }
+
     pub async fn start(&self) -> Result<()> {
         info!("Starting Terminator Runtime...");
         info!("Configuration loaded:");
@@ -65,10 +108,81 @@ impl TerminatorRuntime {
         Ok(())
     }
 
+    /// Executed transactions, oldest first
+    pub fn history(&self) -> &VecDeque<ExecutedTransaction> {
+        &self.history
+    }
+
+    /// Look up the most recent executed transaction with the given signature
+    pub fn get_transaction(&self, signature: &[u8; 64]) -> Option<&ExecutedTransaction> {
+        self.history.iter().rev().find(|entry| &entry.signature == signature)
+    }
+
+    /// Change the history capacity, evicting the oldest entries if needed
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.config.logging.history_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Current Clock sysvar
+    pub fn get_sysvar_clock(&self) -> Clock {
+        self.bank_state.clock
+    }
+
+    /// Move the bank to the next slot
+    pub fn advance_slot(&mut self) {
+        self.bank_state.advance_slot();
+    }
+
     pub fn execute_transaction(&mut self, txn: &Transaction) -> Result<TransactionResult> {
+        let mut execution_context = ExecutionContext::new(self.config.runtime.compute_budget)
+            .with_cost_model(self.config.runtime.cost_model)
+            .with_clock(self.bank_state.clock)
+            .with_log_level(self.log_level)
+            .with_logger(&TracingLogger);
+        let result = self.execute_transaction_with_context(txn, &mut execution_context);
+        
+        let compute_units_consumed = self.config.runtime.compute_budget - execution_context.compute_units_remaining;
+        self.record_transaction(txn, &result, compute_units_consumed);
+        
+        // Each committed transaction moves the ledger forward
+        if result.is_ok() {
+            self.bank_state.advance_slots(self.config.runtime.slots_per_transaction);
+        }
+        
+        result
+    }
+
+    fn record_transaction(&mut self, txn: &Transaction, result: &Result<TransactionResult>, compute_units_consumed: u64) {
+        let capacity = self.config.logging.history_capacity;
+        if capacity == 0 {
+            return;
+        }
+        
+        let serialized = bincode::serialize(txn).unwrap_or_default();
+        let entry = ExecutedTransaction {
+            signature: txn.signatures.first().copied().unwrap_or([0u8; 64]),
+            hash: SolanaCrypto::sha256_hash(&serialized),
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| TransactionError::from_terminator_error(e, None)),
+            fee: self.bank_state.fee_calculator.lamports_per_signature * txn.signatures.len() as u64,
+            compute_units_consumed,
+            slot: self.bank_state.slot(),
+            sequence: self.next_sequence,
+        };
+        self.next_sequence += 1;
+        
+        while self.history.len() >= capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(entry);
+    }
+
+    fn execute_transaction_with_context(&mut self, txn: &Transaction, execution_context: &mut ExecutionContext) -> Result<TransactionResult> {
         info!("Executing transaction with {} instructions", txn.instructions.len());
         
-        let mut execution_context = ExecutionContext::new(self.config.runtime.compute_budget);
         let mut logs = Vec::new();
         
         // Validate transaction size
@@ -83,14 +197,12 @@ impl TerminatorRuntime {
 
         // Execute each instruction
         for (i, instruction) in txn.instructions.iter().enumerate() {
-            if !execution_context.consume_compute_units(1000) {
-                return Err(TerminatorError::TransactionExecutionFailed(
-                    "Compute budget exceeded".to_string()
-                ));
+            if !execution_context.consume_compute_units(execution_context.cost_model.instruction_base) {
+                return Err(TerminatorError::ComputeBudgetExceeded);
             }
             
             debug!("Processing instruction {}: {:?}", i, instruction.program_id);
-            self.process_instruction(instruction, &mut execution_context)?;
+            self.process_instruction(instruction, execution_context)?;
             logs.push(format!("Instruction {} processed successfully", i));
         }
         
@@ -100,8 +212,16 @@ impl TerminatorRuntime {
         Ok(TransactionResult {
             success: true,
             compute_units_consumed: self.config.runtime.compute_budget - execution_context.compute_units_remaining,
-            logs: execution_context.log_messages,
+            logs: execution_context.log_messages.clone(),
             error: None,
+            account_deltas: Vec::new(),
+            memos: txn.instructions.iter()
+                .filter(|ix| crate::memo::is_memo_program(&ix.program_id.0))
+                .filter_map(|ix| match &ix.data {
+                    InstructionData::Generic { data } => Some(crate::memo::decode_memo(data)),
+                    _ => None,
+                })
+                .collect(),
         })
     }
 
@@ -124,8 +244,17 @@ impl TerminatorRuntime {
     fn handle_system_instruction(&mut self, instruction: &Instruction, context: &mut ExecutionContext) -> Result<()> {
         context.log("Processing system program instruction".to_string());
         
-        // Handle based on InstructionData
-        match &instruction.data {
+        // Handle based on InstructionData, decoding recognized raw payloads first
+        let decoded;
+        let data = match &instruction.data {
+            InstructionData::Generic { data } => {
+                let keys: Vec<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+                decoded = InstructionData::from_bytes(&instruction.program_id, data, &keys);
+                &decoded
+            }
+            data => data,
+        };
+        match data {
             InstructionData::Transfer { from, to, lamports } => {
                 self.handle_transfer_instruction(*from, *to, *lamports, context)
             }
@@ -188,8 +317,10 @@ impl TerminatorRuntime {
         
         context.log(format!("Creating account {:?} with {} lamports and {} bytes", to_key, lamports, space));
         
-        // Create new account
-        let new_account = Account::new(lamports, vec![0u8; space as usize], owner);
+        // Create new account, capped like the system program's allocations
+        Account::check_data_len(space)?;
+        let mut new_account = Account::new(lamports, Vec::new(), owner);
+        new_account.resize(space as usize)?;
         self.bank_state.accounts.insert(to_key, new_account);
         
         Ok(())
@@ -244,14 +375,7 @@ impl TerminatorRuntime {
         
         // Parse lamports from instruction data (simplified)
         let lamports = if let InstructionData::Generic { data } = &instruction.data {
-            if data.len() >= 9 {
-                u64::from_le_bytes([
-                    data[1], data[2], data[3], data[4],
-                    data[5], data[6], data[7], data[8],
-                ])
-            } else {
-                1000000 // Default transfer amount
-            }
+            read_u64_le(data, 1).unwrap_or(1000000) // Default transfer amount
         } else {
             1000000
         };
@@ -282,12 +406,60 @@ impl TerminatorRuntime {
     }
 
     fn handle_generic_instruction(&mut self, _instruction: &Instruction, context: &mut ExecutionContext) -> Result<()> {
-        context.log("Processing generic program instruction".to_string());
+        context.log(format!("Processing generic program instruction at slot {}", context.clock.slot));
         // Simplified generic instruction handling
         Ok(())
     }
 }
 
+/// A submitted transaction and where to send its result
+#[cfg(feature = "tokio")]
+type QueuedTransaction = (Transaction, oneshot::Sender<Result<TransactionResult>>);
+
+/// Handle for submitting transactions to a runtime owned by a worker task
+///
+/// Any number of clones may submit concurrently; the worker executes them
+/// one at a time in arrival order. When every handle is dropped the worker
+/// finishes and hands the runtime back through its `JoinHandle`.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone)]
+pub struct TransactionQueue {
+    sender: mpsc::Sender<QueuedTransaction>,
+}
+
+#[cfg(feature = "tokio")]
+impl TransactionQueue {
+    /// Queue `txn`, waiting while the queue is full
+    pub async fn submit(&self, txn: Transaction) -> Result<oneshot::Receiver<Result<TransactionResult>>> {
+        let (result_sender, result_receiver) = oneshot::channel();
+        self.sender.send((txn, result_sender)).await
+            .map_err(|_| TerminatorError::TransactionExecutionFailed("transaction queue stopped".to_string()))?;
+        Ok(result_receiver)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl TerminatorRuntime {
+    /// Move the runtime onto a worker task fed by a queue holding at most
+    /// `capacity` waiting transactions
+    ///
+    /// A `capacity` of 0 is treated as 1, since the queue must hold at least
+    /// one transaction to hand it to the worker. Execution is synchronous,
+    /// so the worker runs on tokio's blocking pool rather than tying up an
+    /// async executor thread.
+    pub fn spawn_queue(mut self, capacity: usize) -> (TransactionQueue, JoinHandle<TerminatorRuntime>) {
+        let (sender, mut receiver) = mpsc::channel::<QueuedTransaction>(capacity.max(1));
+        let worker = tokio::task::spawn_blocking(move || {
+            while let Some((txn, result_sender)) = receiver.blocking_recv() {
+                // The submitter may have stopped waiting; the transaction still ran
+                let _ = result_sender.send(self.execute_transaction(&txn));
+            }
+            self
+        });
+        (TransactionQueue { sender }, worker)
+    }
+}
+
 // Add bincode dependency for serialization
 impl Transaction {
     pub fn serialized_size(&self) -> usize {
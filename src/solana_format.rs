@@ -1,14 +1,56 @@
+use crate::prelude::*;
 use crate::{Result, TerminatorError};
+use crate::types::{AccountMeta, Pubkey};
+#[cfg(feature = "std")]
+use crate::wire::write_u32_le;
+use crate::wire::write_u64_le;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+#[cfg(feature = "std")]
+use bincode::Options;
 use serde::{Deserialize, Serialize};
 // use serde_with::{serde_as, Bytes}; // Unused imports
 
+/// Most account keys a message may hold, and most accounts one instruction
+/// may reference (Solana's per-transaction account lock limit)
+pub const MAX_ACCOUNTS: usize = 64;
+
+/// Most instructions a message may hold (Solana's instruction trace limit)
+pub const MAX_INSTRUCTIONS: usize = 64;
+
+/// Largest serialized transaction that fits in one network packet, and so
+/// also the most data any one instruction can carry
+pub const PACKET_DATA_SIZE: usize = 1232;
+
 /// Real Solana transaction format compatible with Solana's wire format
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolanaTransaction {
+    #[serde(with = "short_vec")]
     pub signatures: Vec<SolanaSignature>,
     pub message: SolanaMessage,
 }
 
+/// Domain separator Solana hashes ahead of a message's bytes
+const MESSAGE_HASH_PREFIX: &[u8] = b"solana-tx-message-v1";
+
+impl SolanaTransaction {
+    /// The first (fee payer's) signature, which identifies the transaction
+    pub fn signature(&self) -> Option<&SolanaSignature> {
+        self.signatures.first()
+    }
+
+    /// BLAKE3 hash of the message's wire bytes, as Solana computes it
+    ///
+    /// Fails when the message can't be serialized, e.g. a list longer than a
+    /// compact-u16 length can encode.
+    pub fn message_hash(&self) -> Result<[u8; 32]> {
+        let message_data = SolanaTransactionParser::message_data(&self.message)?;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(MESSAGE_HASH_PREFIX);
+        hasher.update(&message_data);
+        Ok(hasher.finalize().into())
+    }
+}
+
 /// Versioned transaction that supports both legacy and v0 formats
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionedTransaction {
@@ -27,9 +69,12 @@ pub enum VersionedMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct V0Message {
     pub header: MessageHeader,
+    #[serde(with = "short_vec")]
     pub account_keys: Vec<SolanaPubkey>,
     pub recent_blockhash: SolanaHash,
+    #[serde(with = "short_vec")]
     pub instructions: Vec<CompiledInstruction>,
+    #[serde(with = "short_vec")]
     pub address_table_lookups: Vec<MessageAddressTableLookup>,
 }
 
@@ -37,15 +82,19 @@ pub struct V0Message {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageAddressTableLookup {
     pub account_key: SolanaPubkey,
+    #[serde(with = "short_vec")]
     pub writable_indexes: Vec<u8>,
+    #[serde(with = "short_vec")]
     pub readonly_indexes: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolanaMessage {
     pub header: MessageHeader,
+    #[serde(with = "short_vec")]
     pub account_keys: Vec<SolanaPubkey>,
     pub recent_blockhash: SolanaHash,
+    #[serde(with = "short_vec")]
     pub instructions: Vec<CompiledInstruction>,
 }
 
@@ -57,36 +106,155 @@ pub struct MessageHeader {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct SolanaPubkey(#[serde(with = "serde_bytes")] pub [u8; 32]);
+pub struct SolanaPubkey(#[serde(with = "byte_array")] pub [u8; 32]);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SolanaSignature(#[serde(with = "serde_bytes")] pub [u8; 64]);
+pub struct SolanaSignature(#[serde(with = "byte_array")] pub [u8; 64]);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SolanaHash(#[serde(with = "serde_bytes")] pub [u8; 32]);
+pub struct SolanaHash(#[serde(with = "byte_array")] pub [u8; 32]);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CompiledInstruction {
     pub program_id_index: u8,
+    #[serde(with = "short_vec")]
     pub accounts: Vec<u8>, // Account indices
+    #[serde(with = "short_vec")]
     pub data: Vec<u8>,
 }
 
+/// Serde adapter writing `Vec` lengths as compact-u16, as on the wire
+///
+/// Binary formats (bincode) get Solana's layout; human-readable formats
+/// (JSON) keep plain sequences.
+mod short_vec {
+    use crate::prelude::*;
+    use core::{fmt, marker::PhantomData};
+    use serde::de::{self, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{self, SerializeTuple, Serializer};
+    use serde::{Deserialize, Serialize};
+
+    pub fn serialize<S: Serializer, T: Serialize>(elements: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return elements.serialize(serializer);
+        }
+
+        let len = u16::try_from(elements.len())
+            .map_err(|_| ser::Error::custom("Length exceeds compact-u16 range"))?;
+        let prefix = super::encode_shortvec_len(len);
+        let mut tuple = serializer.serialize_tuple(prefix.len() + elements.len())?;
+        for byte in &prefix {
+            tuple.serialize_element(byte)?;
+        }
+        for element in elements {
+            tuple.serialize_element(element)?;
+        }
+        tuple.end()
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return Vec::deserialize(deserializer);
+        }
+        // The element count isn't known until the prefix is read
+        deserializer.deserialize_tuple(usize::MAX, ShortVecVisitor(PhantomData))
+    }
+
+    struct ShortVecVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ShortVecVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a compact-u16 length followed by that many elements")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut prefix = Vec::with_capacity(3);
+            let len = loop {
+                let byte: u8 = seq.next_element()?
+                    .ok_or_else(|| de::Error::custom("Incomplete compact-u16 encoding"))?;
+                prefix.push(byte);
+                if byte & 0x80 == 0 || prefix.len() == 3 {
+                    break super::decode_shortvec_len(&prefix).map_err(de::Error::custom)?.0;
+                }
+            };
+
+            let mut elements = Vec::new();
+            for i in 0..len {
+                let element = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                elements.push(element);
+            }
+            Ok(elements)
+        }
+    }
+}
+
+/// Serde adapter for fixed-size byte arrays: raw bytes in binary formats,
+/// `serde_bytes` in human-readable ones
+mod byte_array {
+    use core::fmt;
+    use serde::de::{self, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{SerializeTuple, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serde_bytes::serialize(bytes, serializer);
+        }
+
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for byte in bytes {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+        if deserializer.is_human_readable() {
+            return serde_bytes::deserialize(deserializer);
+        }
+        deserializer.deserialize_tuple(N, ByteArrayVisitor::<N>)
+    }
+
+    struct ByteArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} bytes", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+            let mut bytes = [0u8; N];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+impl CompiledInstruction {
+    /// The instruction's accounts with the privileges the message header grants them
+    pub fn account_metas(&self, header: &MessageHeader, account_keys: &[SolanaPubkey]) -> Result<Vec<AccountMeta>> {
+        account_metas(&self.accounts, header, account_keys)
+    }
+}
+
 impl SolanaPubkey {
     pub fn new(bytes: [u8; 32]) -> Self {
         Self(bytes)
     }
 
+    /// Fresh pubkey, distinct from every other `new_unique` key in this process
+    #[cfg(feature = "std")]
     pub fn new_unique() -> Self {
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(&std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-            .to_le_bytes());
-        let hash: [u8; 32] = hasher.finalize().into();
-        Self(hash)
+        Self(Pubkey::new_unique().0)
     }
 
     /// System program ID
@@ -123,66 +291,431 @@ impl SolanaPubkey {
     }
 }
 
-impl std::fmt::Display for SolanaPubkey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SolanaPubkey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.to_string())
     }
 }
 
+impl AsRef<[u8; 32]> for SolanaPubkey {
+    fn as_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<SolanaPubkey> for Pubkey {
+    fn from(key: SolanaPubkey) -> Self {
+        Pubkey(key.0)
+    }
+}
+
+impl From<Pubkey> for SolanaPubkey {
+    fn from(key: Pubkey) -> Self {
+        SolanaPubkey(key.0)
+    }
+}
+
+/// Whether the account at `index` is writable under Solana's privilege layout
+///
+/// Account keys are ordered as writable signers, readonly signers, writable
+/// non-signers, then readonly non-signers; the header counts the readonly
+/// tail of each group, so the fee payer at index 0 is a writable signer in
+/// any header `validate_transaction_format` accepts. A header whose readonly
+/// counts exceed their group leaves the whole group readonly rather than
+/// spilling into the other one.
+pub fn is_writable(index: usize, header: &MessageHeader, num_account_keys: usize) -> bool {
+    let num_signed = header.num_required_signatures as usize;
+    if index >= num_account_keys {
+        return false;
+    }
+
+    if index < num_signed {
+        index < num_signed.saturating_sub(header.num_readonly_signed_accounts as usize)
+    } else {
+        index < num_account_keys.saturating_sub(header.num_readonly_unsigned_accounts as usize)
+    }
+}
+
+/// Whether the account at `index` must sign: the first `num_required_signatures` keys
+pub fn is_signer(index: usize, header: &MessageHeader) -> bool {
+    index < header.num_required_signatures as usize
+}
+
+/// The accounts at `account_indices` with the privileges `header` grants them
+pub fn account_metas(
+    account_indices: &[u8],
+    header: &MessageHeader,
+    account_keys: &[SolanaPubkey],
+) -> Result<Vec<AccountMeta>> {
+    account_indices.iter()
+        .map(|&index| {
+            let index = index as usize;
+            let key = account_keys.get(index).ok_or(TerminatorError::InvalidAccountIndex)?;
+            Ok(AccountMeta {
+                pubkey: Pubkey::from(*key),
+                is_signer: is_signer(index, header),
+                is_writable: is_writable(index, header, account_keys.len()),
+            })
+        })
+        .collect()
+}
+
+/// Decode a compact-u16 ("shortvec") length, returning the value and bytes consumed
+pub fn decode_shortvec_len(data: &[u8]) -> Result<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, &byte) in data.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            if value > u16::MAX as usize {
+                return Err(TerminatorError::SerializationError("Compact-u16 overflow".to_string()));
+            }
+            return Ok((value, i + 1));
+        }
+    }
+    let reason = if data.len() >= 3 { "Compact-u16 overflow" } else { "Incomplete compact-u16 encoding" };
+    Err(TerminatorError::SerializationError(reason.to_string()))
+}
+
+/// Encode a length as compact-u16 ("shortvec")
+pub fn encode_shortvec_len(len: u16) -> Vec<u8> {
+    let mut out = Vec::with_capacity(3);
+    let mut remaining = len;
+    loop {
+        let byte = (remaining & 0x7f) as u8;
+        remaining >>= 7;
+        if remaining == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Failure from the manual wire-format parsers, pinpointing the offending byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Offset into the parsed buffer where `expected` should have started
+    pub offset: usize,
+    /// What the parser was reading
+    pub expected: &'static str,
+    /// What it found instead
+    pub found: String,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "at offset {} expected {}, found {}", self.offset, self.expected, self.found)
+    }
+}
+
+/// Reject wire data too large for one packet before decoding any of it
+fn check_packet_size(data: &[u8]) -> ParseResult<()> {
+    if data.len() > PACKET_DATA_SIZE {
+        return Err(ParseError {
+            offset: PACKET_DATA_SIZE,
+            expected: "end of packet",
+            found: format!("{} bytes (limit {})", data.len(), PACKET_DATA_SIZE),
+        });
+    }
+    Ok(())
+}
+
+impl From<ParseError> for TerminatorError {
+    fn from(error: ParseError) -> Self {
+        TerminatorError::SerializationError(error.to_string())
+    }
+}
+
+pub type ParseResult<T> = core::result::Result<T, ParseError>;
+
+/// Cursor over wire-format bytes that reports failures by absolute offset
+struct ByteReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.offset..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.offset).copied()
+    }
+
+    fn error(&self, expected: &'static str, found: String) -> ParseError {
+        ParseError { offset: self.offset, expected, found }
+    }
+
+    fn read_bytes(&mut self, len: usize, expected: &'static str) -> ParseResult<&'a [u8]> {
+        let available = self.data.len() - self.offset;
+        if available < len {
+            let found = match available {
+                0 => "end of data".to_string(),
+                _ => format!("{} of {} bytes", available, len),
+            };
+            return Err(self.error(expected, found));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self, expected: &'static str) -> ParseResult<u8> {
+        Ok(self.read_bytes(1, expected)?[0])
+    }
+
+    fn read_array<const N: usize>(&mut self, expected: &'static str) -> ParseResult<[u8; N]> {
+        Ok(self.read_bytes(N, expected)?.try_into().unwrap())
+    }
+
+    /// Read a compact-u16 length
+    fn read_shortvec(&mut self, expected: &'static str) -> ParseResult<usize> {
+        let (len, consumed) = decode_shortvec_len(self.remaining()).map_err(|e| {
+            let found = match e {
+                TerminatorError::SerializationError(reason) => reason,
+                other => other.to_string(),
+            };
+            self.error(expected, found)
+        })?;
+        self.offset += consumed;
+        Ok(len)
+    }
+
+    /// Read a compact-u16 length no greater than `max`
+    fn read_bounded_len(&mut self, expected: &'static str, max: usize) -> ParseResult<usize> {
+        let start = self.offset;
+        let len = self.read_shortvec(expected)?;
+        if len > max {
+            return Err(ParseError { offset: start, expected, found: format!("{} (limit {})", len, max) });
+        }
+        Ok(len)
+    }
+}
+
+/// Append `items` to `out` behind their compact-u16 count
+fn write_shortvec<T>(
+    out: &mut Vec<u8>,
+    items: &[T],
+    mut write: impl FnMut(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    let len = u16::try_from(items.len())
+        .map_err(|_| TerminatorError::SerializationError(format!("Length {} exceeds compact-u16 range", items.len())))?;
+    out.extend(encode_shortvec_len(len));
+    items.iter().try_for_each(|item| write(out, item))
+}
+
+fn write_byte_vec(out: &mut Vec<u8>, bytes: &[u8]) -> Result<()> {
+    write_shortvec(out, bytes, |out, byte| {
+        out.push(*byte);
+        Ok(())
+    })
+}
+
+/// Append the wire encoding shared by legacy and v0 messages: header,
+/// account keys, blockhash and instructions
+fn write_message_body(
+    out: &mut Vec<u8>,
+    header: &MessageHeader,
+    account_keys: &[SolanaPubkey],
+    recent_blockhash: &SolanaHash,
+    instructions: &[CompiledInstruction],
+) -> Result<()> {
+    out.extend([header.num_required_signatures, header.num_readonly_signed_accounts, header.num_readonly_unsigned_accounts]);
+    write_shortvec(out, account_keys, |out, key| {
+        out.extend_from_slice(&key.0);
+        Ok(())
+    })?;
+    out.extend_from_slice(&recent_blockhash.0);
+    write_shortvec(out, instructions, |out, instruction| {
+        out.push(instruction.program_id_index);
+        write_byte_vec(out, &instruction.accounts)?;
+        write_byte_vec(out, &instruction.data)
+    })
+}
+
+fn write_legacy_message(out: &mut Vec<u8>, message: &SolanaMessage) -> Result<()> {
+    write_message_body(out, &message.header, &message.account_keys, &message.recent_blockhash, &message.instructions)
+}
+
+/// Append a v0 message behind its `0x80` version prefix
+fn write_v0_message(out: &mut Vec<u8>, message: &V0Message) -> Result<()> {
+    out.push(0x80);
+    write_message_body(out, &message.header, &message.account_keys, &message.recent_blockhash, &message.instructions)?;
+    write_shortvec(out, &message.address_table_lookups, |out, lookup| {
+        out.extend_from_slice(&lookup.account_key.0);
+        write_byte_vec(out, &lookup.writable_indexes)?;
+        write_byte_vec(out, &lookup.readonly_indexes)
+    })
+}
+
+/// Iterator over shortvec-length-prefixed transactions in one buffer
+struct TransactionStream<'a> {
+    data: &'a [u8],
+    offset: usize,
+    failed: bool,
+}
+
+impl Iterator for TransactionStream<'_> {
+    type Item = Result<SolanaTransaction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.data.len() {
+            return None;
+        }
+
+        let entry = decode_shortvec_len(&self.data[self.offset..]).and_then(|(len, prefix)| {
+            let start = self.offset + prefix;
+            let end = start + len;
+            if end > self.data.len() {
+                return Err(TerminatorError::SerializationError(format!(
+                    "Truncated transaction at offset {}: need {} bytes, {} remain",
+                    self.offset, len, self.data.len() - start
+                )));
+            }
+            self.offset = end;
+            SolanaTransactionParser::parse_transaction(&self.data[start..end])
+        });
+
+        // A malformed entry leaves no reliable boundary for the next one
+        if entry.is_err() {
+            self.failed = true;
+        }
+        Some(entry)
+    }
+}
+
+// Field accessors for `parse_rpc_transaction_json`, which needs serde_json
+#[cfg(feature = "std")]
+fn rpc_error(reason: String) -> TerminatorError {
+    TerminatorError::SerializationError(format!("Invalid RPC transaction: {}", reason))
+}
+
+#[cfg(feature = "std")]
+fn rpc_field<'a>(value: &'a serde_json::Value, key: &str) -> Result<&'a serde_json::Value> {
+    value.get(key).ok_or_else(|| rpc_error(format!("missing {}", key)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_array<'a>(value: &'a serde_json::Value, what: &str) -> Result<&'a Vec<serde_json::Value>> {
+    value.as_array().ok_or_else(|| rpc_error(format!("{} is not an array", what)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_str<'a>(value: &'a serde_json::Value, what: &str) -> Result<&'a str> {
+    value.as_str().ok_or_else(|| rpc_error(format!("{} is not a string", what)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_bool(value: &serde_json::Value, key: &str) -> Result<bool> {
+    rpc_field(value, key)?.as_bool().ok_or_else(|| rpc_error(format!("{} is not a bool", key)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_u8(value: &serde_json::Value, what: &str) -> Result<u8> {
+    value.as_u64()
+        .and_then(|n| u8::try_from(n).ok())
+        .ok_or_else(|| rpc_error(format!("{} is not a u8", what)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_base58(value: &serde_json::Value, what: &str) -> Result<Vec<u8>> {
+    bs58::decode(rpc_str(value, what)?)
+        .into_vec()
+        .map_err(|e| rpc_error(format!("{} is not base58: {}", what, e)))
+}
+
+#[cfg(feature = "std")]
+fn rpc_base58_array<const N: usize>(value: &serde_json::Value, what: &str) -> Result<[u8; N]> {
+    let bytes = rpc_base58(value, what)?;
+    bytes.as_slice()
+        .try_into()
+        .map_err(|_| rpc_error(format!("{} is {} bytes, expected {}", what, bytes.len(), N)))
+}
+
 /// Real Solana transaction parser and builder with v0 support
 pub struct SolanaTransactionParser;
 
 impl SolanaTransactionParser {
+    /// Parse a base64-encoded transaction (as returned by RPC `getTransaction`)
+    pub fn parse_transaction_base64(encoded: &str) -> Result<SolanaTransaction> {
+        let data = BASE64.decode(encoded.trim())
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid base64: {}", e)))?;
+        Self::parse_transaction(&data)
+    }
+
+    /// Parse a base58-encoded transaction (as printed by the Solana CLI)
+    pub fn parse_transaction_base58(encoded: &str) -> Result<SolanaTransaction> {
+        let data = bs58::decode(encoded.trim())
+            .into_vec()
+            .map_err(|e| TerminatorError::SerializationError(format!("Invalid base58: {}", e)))?;
+        Self::parse_transaction(&data)
+    }
+
+    /// Parse a buffer of concatenated transactions, each prefixed with its
+    /// shortvec-encoded length
+    ///
+    /// Iteration stops at the end of the buffer, or after yielding the first error.
+    pub fn parse_stream(data: &[u8]) -> impl Iterator<Item = Result<SolanaTransaction>> + '_ {
+        TransactionStream { data, offset: 0, failed: false }
+    }
+
     /// Parse a transaction from Solana's wire format (supports both legacy and v0)
     pub fn parse_transaction(data: &[u8]) -> Result<SolanaTransaction> {
-        // First try direct bincode deserialization
-        if let Ok(tx) = bincode::deserialize::<SolanaTransaction>(data) {
-            return Ok(tx);
+        check_packet_size(data)?;
+
+        // Legacy transactions deserialize straight from the wire bytes. A
+        // versioned message's prefix byte would be misread as the header, so
+        // those go through the manual parser, as everything does without std
+        #[cfg(feature = "std")]
+        {
+            let fast_path = bincode::DefaultOptions::new()
+                .with_fixint_encoding()
+                .reject_trailing_bytes()
+                .deserialize::<SolanaTransaction>(data);
+            if let Ok(tx) = fast_path {
+                if tx.message.header.num_required_signatures & 0x80 == 0 && Self::check_limits(&tx.message).is_ok() {
+                    return Ok(tx);
+                }
+            }
         }
 
-        // If that fails, try manual parsing for complex cases
-        Self::parse_transaction_manual(data)
+        // If that fails, try manual parsing for complex cases; it also
+        // reports where a count exceeds its limit
+        Ok(Self::parse_transaction_manual(data)?)
     }
 
     /// Manual transaction parsing for cases where bincode fails
-    fn parse_transaction_manual(data: &[u8]) -> Result<SolanaTransaction> {
-        if data.is_empty() {
-            return Err(TerminatorError::SerializationError("Empty transaction data".to_string()));
-        }
-
-        let mut offset = 0;
-        
-        // Parse signature count
-        let num_signatures = data[0] as usize;
-        offset += 1;
+    ///
+    /// Errors carry the offset of the offending byte within `data`.
+    pub fn parse_transaction_manual(data: &[u8]) -> ParseResult<SolanaTransaction> {
+        check_packet_size(data)?;
+        let mut reader = ByteReader::new(data);
 
         // Parse signatures
+        let num_signatures = reader.read_shortvec("signature count")?;
         let mut signatures = Vec::new();
         for _ in 0..num_signatures {
-            if offset + 64 > data.len() {
-                return Err(TerminatorError::SerializationError("Incomplete signature data".to_string()));
-            }
-            let mut sig_bytes = [0u8; 64];
-            sig_bytes.copy_from_slice(&data[offset..offset + 64]);
-            signatures.push(SolanaSignature(sig_bytes));
-            offset += 64;
-        }
-
-        // Check for compact encoding before message
-        // Solana RPC often includes message length as compact-u16
-        if offset < data.len() && data[offset] >= 0x80 {
-            // Skip compact-encoded length field
-            if data[offset] < 0x80 {
-                offset += 1; // Single byte
-            } else if offset + 1 < data.len() {
-                offset += 2; // Two byte compact encoding
-            } else {
-                return Err(TerminatorError::SerializationError("Incomplete compact encoding".to_string()));
-            }
+            signatures.push(SolanaSignature(reader.read_array("signature")?));
+        }
+
+        // The message follows the signatures directly. Versioned messages
+        // start with a prefix byte (0x80 | version); legacy messages start
+        // with the header, whose first byte is < 0x80
+        if reader.peek().is_some_and(|byte| byte & 0x80 != 0) {
+            reader.read_u8("version prefix")?;
         }
 
         // Parse message
-        let message = Self::parse_message_manual(&data[offset..])?;
+        let message = Self::parse_message_manual(&mut reader)?;
 
         Ok(SolanaTransaction {
             signatures,
@@ -191,171 +724,59 @@ impl SolanaTransactionParser {
     }
 
     /// Manual message parsing
-    fn parse_message_manual(data: &[u8]) -> Result<SolanaMessage> {
-        let mut offset = 0;
-        
-        // First try to deserialize the message portion directly
-        if let Ok(message) = bincode::deserialize::<SolanaMessage>(data) {
-            return Ok(message);
-        }
-
-        // If bincode fails, try manual parsing
-        if offset + 3 > data.len() {
-            return Err(TerminatorError::SerializationError("Incomplete message header".to_string()));
-        }
-
-        // Parse message header
+    fn parse_message_manual(reader: &mut ByteReader) -> ParseResult<SolanaMessage> {
+        let header_offset = reader.offset();
+        let header_bytes: [u8; 3] = reader.read_array("message header")?;
         let header = MessageHeader {
-            num_required_signatures: data[offset],
-            num_readonly_signed_accounts: data[offset + 1],
-            num_readonly_unsigned_accounts: data[offset + 2],
+            num_required_signatures: header_bytes[0],
+            num_readonly_signed_accounts: header_bytes[1],
+            num_readonly_unsigned_accounts: header_bytes[2],
         };
-        offset += 3;
 
         // Validate header makes sense
         if header.num_required_signatures > 16 || 
            header.num_readonly_signed_accounts > 16 || 
            header.num_readonly_unsigned_accounts > 16 {
-            return Err(TerminatorError::SerializationError(
-                format!("Invalid header values: req_sigs={}, ro_signed={}, ro_unsigned={}", 
-                    header.num_required_signatures, 
-                    header.num_readonly_signed_accounts, 
-                    header.num_readonly_unsigned_accounts)
-            ));
-        }
-
-        // Parse account keys count
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing account keys count".to_string()));
-        }
-        let num_account_keys = data[offset] as usize;
-        offset += 1;
-
-        // Validate account keys count
-        if num_account_keys > 64 {
-            return Err(TerminatorError::SerializationError(
-                format!("Too many account keys: {}", num_account_keys)
-            ));
+            return Err(ParseError {
+                offset: header_offset,
+                expected: "message header",
+                found: format!("num_required_signatures={}, num_readonly_signed_accounts={}, num_readonly_unsigned_accounts={}",
+                    header.num_required_signatures,
+                    header.num_readonly_signed_accounts,
+                    header.num_readonly_unsigned_accounts),
+            });
         }
 
         // Parse account keys
+        let num_account_keys = reader.read_bounded_len("account key count", MAX_ACCOUNTS)?;
         let mut account_keys = Vec::new();
         for _ in 0..num_account_keys {
-            if offset + 32 > data.len() {
-                return Err(TerminatorError::SerializationError("Incomplete account key".to_string()));
-            }
-            let mut key_bytes = [0u8; 32];
-            key_bytes.copy_from_slice(&data[offset..offset + 32]);
-            account_keys.push(SolanaPubkey(key_bytes));
-            offset += 32;
+            account_keys.push(SolanaPubkey(reader.read_array("account key")?));
         }
 
         // Parse recent blockhash
-        if offset + 32 > data.len() {
-            return Err(TerminatorError::SerializationError("Incomplete recent blockhash".to_string()));
-        }
-        let mut blockhash_bytes = [0u8; 32];
-        blockhash_bytes.copy_from_slice(&data[offset..offset + 32]);
-        let recent_blockhash = SolanaHash(blockhash_bytes);
-        offset += 32;
-
-        // Parse instructions count
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing instructions count".to_string()));
-        }
-        let num_instructions = data[offset] as usize;
-        offset += 1;
-
-        // Validate instructions count
-        if num_instructions > 64 {
-            return Err(TerminatorError::SerializationError(
-                format!("Too many instructions: {}", num_instructions)
-            ));
-        }
+        let recent_blockhash = SolanaHash(reader.read_array("recent blockhash")?);
 
         // Parse instructions
+        let num_instructions = reader.read_bounded_len("instruction count", MAX_INSTRUCTIONS)?;
         let mut instructions = Vec::new();
-        for i in 0..num_instructions {
-            // Parse program_id_index
-            if offset >= data.len() {
-                return Err(TerminatorError::SerializationError(
-                    format!("Missing program_id_index for instruction {}", i)
-                ));
-            }
-            let program_id_index = data[offset];
-            offset += 1;
-
-            // Validate program_id_index
-            if program_id_index >= num_account_keys as u8 {
-                return Err(TerminatorError::SerializationError(
-                    format!("Invalid program_id_index {} for instruction {}", program_id_index, i)
-                ));
-            }
-
-            // Parse accounts count
-            if offset >= data.len() {
-                return Err(TerminatorError::SerializationError(
-                    format!("Missing accounts count for instruction {}", i)
-                ));
-            }
-            let accounts_count = data[offset] as usize;
-            offset += 1;
-
-            // Validate accounts count
-            if accounts_count > 64 {
-                return Err(TerminatorError::SerializationError(
-                    format!("Too many accounts {} for instruction {}", accounts_count, i)
-                ));
-            }
-
-            // Parse account indices
-            if offset + accounts_count > data.len() {
-                return Err(TerminatorError::SerializationError(
-                    format!("Incomplete accounts for instruction {}", i)
-                ));
-            }
-            let accounts = data[offset..offset + accounts_count].to_vec();
-            offset += accounts_count;
-
-            // Validate account indices
-            for &account_index in &accounts {
-                if account_index >= num_account_keys as u8 {
-                    return Err(TerminatorError::SerializationError(
-                        format!("Invalid account index {} for instruction {}", account_index, i)
-                    ));
-                }
-            }
-
-            // Parse instruction data length
-            if offset >= data.len() {
-                return Err(TerminatorError::SerializationError(
-                    format!("Missing data length for instruction {}", i)
-                ));
-            }
-            let data_length = data[offset] as usize;
-            offset += 1;
-
-            // Validate data length
-            if data_length > 1232 { // Solana instruction data limit
-                return Err(TerminatorError::SerializationError(
-                    format!("Instruction data too large: {} bytes for instruction {}", data_length, i)
-                ));
-            }
-
-            // Parse instruction data
-            if offset + data_length > data.len() {
-                return Err(TerminatorError::SerializationError(
-                    format!("Incomplete instruction data for instruction {}", i)
-                ));
+        for _ in 0..num_instructions {
+            let index_offset = reader.offset();
+            let instruction = Self::parse_compiled_instruction(reader)?;
+
+            // Validate program_id_index and account indices
+            let out_of_range = core::iter::once(&instruction.program_id_index)
+                .chain(&instruction.accounts)
+                .find(|&&index| index as usize >= num_account_keys);
+            if let Some(index) = out_of_range {
+                return Err(ParseError {
+                    offset: index_offset,
+                    expected: "account index below the account key count",
+                    found: format!("{} with {} account keys", index, num_account_keys),
+                });
             }
-            let instruction_data = data[offset..offset + data_length].to_vec();
-            offset += data_length;
 
-            instructions.push(CompiledInstruction {
-                program_id_index,
-                accounts,
-                data: instruction_data,
-            });
+            instructions.push(instruction);
         }
 
         Ok(SolanaMessage {
@@ -368,43 +789,31 @@ impl SolanaTransactionParser {
 
     /// Parse versioned transaction (v0 or legacy)
     pub fn parse_versioned_transaction(data: &[u8]) -> Result<VersionedTransaction> {
-        if data.is_empty() {
-            return Err(TerminatorError::SerializationError("Empty transaction data".to_string()));
-        }
+        check_packet_size(data)?;
 
-        let first_byte = data[0];
-        
         // Check if this is a v0 transaction (first byte has MSB set)
-        if first_byte & 0x80 != 0 {
-            Self::parse_v0_transaction(data)
-        } else {
-            Self::parse_legacy_versioned_transaction(data)
+        match data.first() {
+            None => Err(TerminatorError::SerializationError("Empty transaction data".to_string())),
+            Some(first_byte) if first_byte & 0x80 != 0 => Ok(Self::parse_v0_transaction(data)?),
+            Some(_) => Self::parse_legacy_versioned_transaction(data),
         }
     }
 
     /// Parse v0 transaction format
-    fn parse_v0_transaction(data: &[u8]) -> Result<VersionedTransaction> {
-        let mut offset = 0;
+    fn parse_v0_transaction(data: &[u8]) -> ParseResult<VersionedTransaction> {
+        let mut reader = ByteReader::new(data);
         
         // Parse signature count (first byte with MSB cleared)
-        let num_signatures = (data[0] & 0x7F) as usize;
-        offset += 1;
+        let num_signatures = (reader.read_u8("signature count")? & 0x7F) as usize;
         
         // Parse signatures
         let mut signatures = Vec::new();
         for _ in 0..num_signatures {
-            if offset + 64 > data.len() {
-                return Err(TerminatorError::SerializationError("Invalid signature data".to_string()));
-            }
-            let mut sig_bytes = [0u8; 64];
-            sig_bytes.copy_from_slice(&data[offset..offset + 64]);
-            signatures.push(SolanaSignature(sig_bytes));
-            offset += 64;
+            signatures.push(SolanaSignature(reader.read_array("signature")?));
         }
 
         // Parse v0 message
-        let message_data = &data[offset..];
-        let v0_message = Self::parse_v0_message(message_data)?;
+        let v0_message = Self::parse_v0_message(&mut reader)?;
 
         Ok(VersionedTransaction {
             signatures,
@@ -413,71 +822,38 @@ impl SolanaTransactionParser {
     }
 
     /// Parse v0 message format
-    fn parse_v0_message(data: &[u8]) -> Result<V0Message> {
-        let mut offset = 0;
-        
+    fn parse_v0_message(reader: &mut ByteReader) -> ParseResult<V0Message> {
         // Parse header
-        if offset + 3 > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid header data".to_string()));
-        }
+        let header_bytes: [u8; 3] = reader.read_array("message header")?;
         let header = MessageHeader {
-            num_required_signatures: data[offset],
-            num_readonly_signed_accounts: data[offset + 1],
-            num_readonly_unsigned_accounts: data[offset + 2],
+            num_required_signatures: header_bytes[0],
+            num_readonly_signed_accounts: header_bytes[1],
+            num_readonly_unsigned_accounts: header_bytes[2],
         };
-        offset += 3;
 
         // Parse account keys length and keys
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing account keys length".to_string()));
-        }
-        let num_account_keys = data[offset] as usize;
-        offset += 1;
-
+        let num_account_keys = reader.read_bounded_len("account key count", MAX_ACCOUNTS)?;
         let mut account_keys = Vec::new();
         for _ in 0..num_account_keys {
-            if offset + 32 > data.len() {
-                return Err(TerminatorError::SerializationError("Invalid account key data".to_string()));
-            }
-            let mut key_bytes = [0u8; 32];
-            key_bytes.copy_from_slice(&data[offset..offset + 32]);
-            account_keys.push(SolanaPubkey(key_bytes));
-            offset += 32;
+            account_keys.push(SolanaPubkey(reader.read_array("account key")?));
         }
 
         // Parse recent blockhash
-        if offset + 32 > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid blockhash data".to_string()));
-        }
-        let mut blockhash_bytes = [0u8; 32];
-        blockhash_bytes.copy_from_slice(&data[offset..offset + 32]);
-        let recent_blockhash = SolanaHash(blockhash_bytes);
-        offset += 32;
+        let recent_blockhash = SolanaHash(reader.read_array("recent blockhash")?);
 
         // Parse instructions
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing instructions length".to_string()));
-        }
-        let num_instructions = data[offset] as usize;
-        offset += 1;
-
+        let num_instructions = reader.read_bounded_len("instruction count", MAX_INSTRUCTIONS)?;
         let mut instructions = Vec::new();
         for _ in 0..num_instructions {
-            let (instruction, consumed) = Self::parse_compiled_instruction(&data[offset..])?;
-            instructions.push(instruction);
-            offset += consumed;
+            instructions.push(Self::parse_compiled_instruction(reader)?);
         }
 
         // Parse address table lookups
         let mut address_table_lookups = Vec::new();
-        if offset < data.len() {
-            let num_lookups = data[offset] as usize;
-            offset += 1;
-
+        if reader.peek().is_some() {
+            let num_lookups = reader.read_shortvec("address table lookup count")?;
             for _ in 0..num_lookups {
-                let (lookup, consumed) = Self::parse_address_table_lookup(&data[offset..])?;
-                address_table_lookups.push(lookup);
-                offset += consumed;
+                address_table_lookups.push(Self::parse_address_table_lookup(reader)?);
             }
         }
 
@@ -491,98 +867,46 @@ impl SolanaTransactionParser {
     }
 
     /// Parse compiled instruction from bytes
-    fn parse_compiled_instruction(data: &[u8]) -> Result<(CompiledInstruction, usize)> {
-        let mut offset = 0;
-        
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing program_id_index".to_string()));
-        }
-        let program_id_index = data[offset];
-        offset += 1;
+    fn parse_compiled_instruction(reader: &mut ByteReader) -> ParseResult<CompiledInstruction> {
+        let program_id_index = reader.read_u8("program_id_index")?;
 
         // Parse accounts length and indices
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing accounts length".to_string()));
-        }
-        let num_accounts = data[offset] as usize;
-        offset += 1;
-
-        if offset + num_accounts > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid accounts data".to_string()));
-        }
-        let accounts = data[offset..offset + num_accounts].to_vec();
-        offset += num_accounts;
+        let num_accounts = reader.read_bounded_len("instruction account count", MAX_ACCOUNTS)?;
+        let accounts = reader.read_bytes(num_accounts, "instruction account indices")?.to_vec();
 
         // Parse instruction data length and data
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing instruction data length".to_string()));
-        }
-        let data_length = data[offset] as usize;
-        offset += 1;
-
-        if offset + data_length > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid instruction data".to_string()));
-        }
-        let instruction_data = data[offset..offset + data_length].to_vec();
-        offset += data_length;
+        let data_length = reader.read_bounded_len("instruction data length", PACKET_DATA_SIZE)?;
+        let data = reader.read_bytes(data_length, "instruction data")?.to_vec();
 
-        Ok((CompiledInstruction {
+        Ok(CompiledInstruction {
             program_id_index,
             accounts,
-            data: instruction_data,
-        }, offset))
+            data,
+        })
     }
 
     /// Parse address table lookup from bytes
-    fn parse_address_table_lookup(data: &[u8]) -> Result<(MessageAddressTableLookup, usize)> {
-        let mut offset = 0;
-        
-        // Parse account key
-        if offset + 32 > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid lookup table key".to_string()));
-        }
-        let mut key_bytes = [0u8; 32];
-        key_bytes.copy_from_slice(&data[offset..offset + 32]);
-        let account_key = SolanaPubkey(key_bytes);
-        offset += 32;
+    fn parse_address_table_lookup(reader: &mut ByteReader) -> ParseResult<MessageAddressTableLookup> {
+        let account_key = SolanaPubkey(reader.read_array("lookup table key")?);
 
         // Parse writable indexes
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing writable indexes length".to_string()));
-        }
-        let num_writable = data[offset] as usize;
-        offset += 1;
-
-        if offset + num_writable > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid writable indexes".to_string()));
-        }
-        let writable_indexes = data[offset..offset + num_writable].to_vec();
-        offset += num_writable;
+        let num_writable = reader.read_shortvec("writable index count")?;
+        let writable_indexes = reader.read_bytes(num_writable, "writable indexes")?.to_vec();
 
         // Parse readonly indexes
-        if offset >= data.len() {
-            return Err(TerminatorError::SerializationError("Missing readonly indexes length".to_string()));
-        }
-        let num_readonly = data[offset] as usize;
-        offset += 1;
+        let num_readonly = reader.read_shortvec("readonly index count")?;
+        let readonly_indexes = reader.read_bytes(num_readonly, "readonly indexes")?.to_vec();
 
-        if offset + num_readonly > data.len() {
-            return Err(TerminatorError::SerializationError("Invalid readonly indexes".to_string()));
-        }
-        let readonly_indexes = data[offset..offset + num_readonly].to_vec();
-        offset += num_readonly;
-
-        Ok((MessageAddressTableLookup {
+        Ok(MessageAddressTableLookup {
             account_key,
             writable_indexes,
             readonly_indexes,
-        }, offset))
+        })
     }
 
-    /// Parse legacy transaction as versioned
+    /// Parse legacy transaction as versioned, with the same limits as `parse_transaction`
     fn parse_legacy_versioned_transaction(data: &[u8]) -> Result<VersionedTransaction> {
-        let legacy_tx: SolanaTransaction = bincode::deserialize(data)
-            .map_err(|e| TerminatorError::SerializationError(format!("Failed to parse legacy transaction: {}", e)))?;
+        let legacy_tx = Self::parse_transaction(data)?;
         
         Ok(VersionedTransaction {
             signatures: legacy_tx.signatures,
@@ -591,46 +915,277 @@ impl SolanaTransactionParser {
     }
 
     /// Convert v0 message to legacy format by resolving lookup tables
-    fn v0_to_legacy_message(v0_message: V0Message) -> Result<SolanaMessage> {
-        let mut all_account_keys = v0_message.account_keys.clone();
-        
-        // For demo purposes, we'll create placeholder accounts for lookup table entries
-        // In a real implementation, you'd resolve these from the blockchain state
+    ///
+    /// `resolve_table` returns the addresses stored in a lookup table. Loaded
+    /// addresses are non-signers, so the writable ones are placed ahead of
+    /// the static readonly non-signers and the readonly ones at the end,
+    /// keeping the legacy header's privilege layout; instruction indices
+    /// are remapped to match.
+    pub fn v0_to_legacy_message<F>(v0_message: &V0Message, mut resolve_table: F) -> Result<SolanaMessage>
+    where
+        F: FnMut(&SolanaPubkey) -> Option<Vec<SolanaPubkey>>,
+    {
+        let mut loaded_writable = Vec::new();
+        let mut loaded_readonly = Vec::new();
         for lookup in &v0_message.address_table_lookups {
-            // Add placeholder accounts for writable indexes
-            for _ in &lookup.writable_indexes {
-                all_account_keys.push(SolanaPubkey::new_unique());
-            }
-            // Add placeholder accounts for readonly indexes  
-            for _ in &lookup.readonly_indexes {
-                all_account_keys.push(SolanaPubkey::new_unique());
-            }
+            let addresses = resolve_table(&lookup.account_key).ok_or_else(|| {
+                TerminatorError::AccountNotFound(format!("Address lookup table {}", lookup.account_key))
+            })?;
+            let select = |indexes: &[u8]| -> Result<Vec<SolanaPubkey>> {
+                indexes.iter()
+                    .map(|&index| addresses.get(index as usize).copied().ok_or_else(|| {
+                        TerminatorError::TransactionExecutionFailed(format!(
+                            "Lookup table {} has no address at index {}", lookup.account_key, index
+                        ))
+                    }))
+                    .collect()
+            };
+            loaded_writable.extend(select(&lookup.writable_indexes)?);
+            loaded_readonly.extend(select(&lookup.readonly_indexes)?);
+        }
+
+        let num_static = v0_message.account_keys.len();
+        let num_readonly_unsigned = v0_message.header.num_readonly_unsigned_accounts as usize;
+        let readonly_unsigned_start = num_static.checked_sub(num_readonly_unsigned).ok_or_else(|| {
+            TerminatorError::TransactionExecutionFailed("Header marks more readonly accounts than keys".to_string())
+        })?;
+        let num_loaded_writable = loaded_writable.len();
+
+        // Legacy order: static up to the readonly non-signers, loaded writable,
+        // static readonly non-signers, loaded readonly
+        let account_keys: Vec<SolanaPubkey> = v0_message.account_keys[..readonly_unsigned_start].iter()
+            .chain(&loaded_writable)
+            .chain(&v0_message.account_keys[readonly_unsigned_start..])
+            .chain(&loaded_readonly)
+            .copied()
+            .collect();
+        if account_keys.len() > u8::MAX as usize + 1 {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "Too many account keys after resolving lookup tables".to_string()
+            ));
         }
 
-        // Update instructions to use the expanded account list
-        let updated_instructions = v0_message.instructions;
+        // v0 indices run static, loaded writable, loaded readonly
+        let remap = |index: u8| -> Result<u8> {
+            let index = index as usize;
+            let remapped = if index < readonly_unsigned_start {
+                index
+            } else if index < num_static {
+                index + num_loaded_writable
+            } else if index < account_keys.len() {
+                let loaded = index - num_static;
+                if loaded < num_loaded_writable {
+                    readonly_unsigned_start + loaded
+                } else {
+                    index
+                }
+            } else {
+                return Err(TerminatorError::TransactionExecutionFailed(format!(
+                    "Account index {} out of range for {} keys", index, account_keys.len()
+                )));
+            };
+            Ok(remapped as u8)
+        };
+
+        let instructions = v0_message.instructions.iter()
+            .map(|instruction| {
+                Ok(CompiledInstruction {
+                    program_id_index: remap(instruction.program_id_index)?,
+                    accounts: instruction.accounts.iter().map(|&index| remap(index)).collect::<Result<_>>()?,
+                    data: instruction.data.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
 
         Ok(SolanaMessage {
-            header: v0_message.header,
-            account_keys: all_account_keys,
-            recent_blockhash: v0_message.recent_blockhash,
-            instructions: updated_instructions,
+            header: MessageHeader {
+                num_readonly_unsigned_accounts: (num_readonly_unsigned + loaded_readonly.len()) as u8,
+                ..v0_message.header.clone()
+            },
+            account_keys,
+            recent_blockhash: v0_message.recent_blockhash.clone(),
+            instructions,
         })
     }
 
     /// Serialize transaction to Solana's wire format
     pub fn serialize_transaction(tx: &SolanaTransaction) -> Result<Vec<u8>> {
-        bincode::serialize(tx)
-            .map_err(|e| TerminatorError::SerializationError(format!("Failed to serialize transaction: {}", e)))
+        let mut data = Vec::new();
+        write_shortvec(&mut data, &tx.signatures, |out, signature| {
+            out.extend_from_slice(&signature.0);
+            Ok(())
+        })?;
+        write_legacy_message(&mut data, &tx.message)?;
+        Ok(data)
+    }
+
+    /// Check that `data` parses into a transaction which re-serializes to
+    /// exactly `data`
+    ///
+    /// A parse that succeeds but lands on the wrong structure (a versioned
+    /// message read as legacy, say) shows up here as the first differing byte.
+    pub fn validate_roundtrip(data: &[u8]) -> Result<()> {
+        let tx = Self::parse_transaction(data)?;
+        let wire = Self::serialize_transaction(&tx)?;
+        if wire == data {
+            return Ok(());
+        }
+
+        let offset = data.iter().zip(&wire).position(|(a, b)| a != b).unwrap_or(data.len().min(wire.len()));
+        let window = |bytes: &[u8]| hex::encode(&bytes[offset.min(bytes.len())..(offset + 16).min(bytes.len())]);
+        Err(TerminatorError::SerializationError(format!(
+            "Round-trip mismatch at byte {}: input is {} bytes, re-serialized is {} bytes; input {} vs wire {}",
+            offset,
+            data.len(),
+            wire.len(),
+            window(data),
+            window(&wire),
+        )))
+    }
+
+    /// Parse a JSON-RPC `getTransaction` response
+    ///
+    /// Accepts the whole response, its `result`, or just its `transaction`,
+    /// in `json`, `jsonParsed`, `base58` or `base64` encoding. Of the parsed
+    /// instruction forms only system `transfer` is understood, and v0
+    /// messages that load accounts from lookup tables are rejected.
+    #[cfg(feature = "std")]
+    pub fn parse_rpc_transaction_json(value: &serde_json::Value) -> Result<SolanaTransaction> {
+        let value = value.get("result").unwrap_or(value);
+        let transaction = value.get("transaction").unwrap_or(value);
+
+        // Binary encodings arrive as [data, encoding]
+        if let Some([data, encoding]) = transaction.as_array().map(Vec::as_slice) {
+            let data = rpc_str(data, "transaction data")?;
+            return match encoding.as_str() {
+                Some("base64") => Self::parse_transaction_base64(data),
+                Some("base58") => Self::parse_transaction_base58(data),
+                other => Err(rpc_error(format!("unsupported encoding {}", other.unwrap_or("(missing)")))),
+            };
+        }
+
+        let signatures = rpc_array(rpc_field(transaction, "signatures")?, "signatures")?
+            .iter()
+            .map(|signature| Ok(SolanaSignature(rpc_base58_array(signature, "signature")?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let message = rpc_field(transaction, "message")?;
+        if message.get("addressTableLookups")
+            .and_then(serde_json::Value::as_array)
+            .is_some_and(|lookups| !lookups.is_empty())
+        {
+            return Err(rpc_error("address table lookups are not supported".to_string()));
+        }
+
+        // `json` lists bare keys alongside a header; `jsonParsed` flags each key instead
+        let mut account_keys = Vec::new();
+        let mut privileges = Vec::new();
+        for key in rpc_array(rpc_field(message, "accountKeys")?, "accountKeys")? {
+            match key.get("pubkey") {
+                Some(pubkey) => {
+                    account_keys.push(SolanaPubkey(rpc_base58_array(pubkey, "account key")?));
+                    privileges.push((rpc_bool(key, "signer")?, rpc_bool(key, "writable")?));
+                }
+                None => account_keys.push(SolanaPubkey(rpc_base58_array(key, "account key")?)),
+            }
+        }
+
+        let header = match message.get("header") {
+            Some(header) => MessageHeader {
+                num_required_signatures: rpc_u8(rpc_field(header, "numRequiredSignatures")?, "numRequiredSignatures")?,
+                num_readonly_signed_accounts: rpc_u8(rpc_field(header, "numReadonlySignedAccounts")?, "numReadonlySignedAccounts")?,
+                num_readonly_unsigned_accounts: rpc_u8(rpc_field(header, "numReadonlyUnsignedAccounts")?, "numReadonlyUnsignedAccounts")?,
+            },
+            None if privileges.len() == account_keys.len() => {
+                let count = |signer: bool, writable: bool| {
+                    privileges.iter().filter(|&&privilege| privilege == (signer, writable)).count() as u8
+                };
+                MessageHeader {
+                    num_required_signatures: count(true, true) + count(true, false),
+                    num_readonly_signed_accounts: count(true, false),
+                    num_readonly_unsigned_accounts: count(false, false),
+                }
+            }
+            None => return Err(rpc_error("message has no header and unflagged account keys".to_string())),
+        };
+
+        let recent_blockhash = SolanaHash(rpc_base58_array(rpc_field(message, "recentBlockhash")?, "recentBlockhash")?);
+
+        let key_index = |value: &serde_json::Value, what: &str| -> Result<u8> {
+            let key = SolanaPubkey(rpc_base58_array(value, what)?);
+            account_keys.iter()
+                .position(|candidate| *candidate == key)
+                .map(|index| index as u8)
+                .ok_or_else(|| rpc_error(format!("{} {} is not an account key", what, key)))
+        };
+        let instructions = rpc_array(rpc_field(message, "instructions")?, "instructions")?
+            .iter()
+            .map(|instruction| {
+                // `json` encoding: indices into the account keys
+                if let Some(program_id_index) = instruction.get("programIdIndex") {
+                    return Ok(CompiledInstruction {
+                        program_id_index: rpc_u8(program_id_index, "programIdIndex")?,
+                        accounts: rpc_array(rpc_field(instruction, "accounts")?, "accounts")?
+                            .iter()
+                            .map(|index| rpc_u8(index, "account index"))
+                            .collect::<Result<_>>()?,
+                        data: rpc_base58(rpc_field(instruction, "data")?, "instruction data")?,
+                    });
+                }
+
+                // `jsonParsed` encoding: keys spelled out, data decoded for known programs
+                let program_id_index = key_index(rpc_field(instruction, "programId")?, "programId")?;
+                let (accounts, data) = match instruction.get("parsed") {
+                    Some(parsed) => {
+                        let kind = rpc_str(rpc_field(parsed, "type")?, "type")?;
+                        if account_keys[program_id_index as usize] != SolanaPubkey::system_program() || kind != "transfer" {
+                            return Err(rpc_error(format!("unsupported parsed instruction {}", kind)));
+                        }
+                        let info = rpc_field(parsed, "info")?;
+                        let lamports = rpc_field(info, "lamports")?.as_u64()
+                            .ok_or_else(|| rpc_error("lamports is not a u64".to_string()))?;
+                        // Wire encoding: u32 instruction tag then the amount
+                        let mut data = Vec::new();
+                        write_u32_le(&mut data, 2);
+                        write_u64_le(&mut data, lamports);
+                        let accounts = vec![
+                            key_index(rpc_field(info, "source")?, "source")?,
+                            key_index(rpc_field(info, "destination")?, "destination")?,
+                        ];
+                        (accounts, data)
+                    }
+                    None => {
+                        let accounts = rpc_array(rpc_field(instruction, "accounts")?, "accounts")?
+                            .iter()
+                            .map(|key| key_index(key, "account"))
+                            .collect::<Result<_>>()?;
+                        (accounts, rpc_base58(rpc_field(instruction, "data")?, "instruction data")?)
+                    }
+                };
+                Ok(CompiledInstruction { program_id_index, accounts, data })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SolanaTransaction {
+            signatures,
+            message: SolanaMessage {
+                header,
+                account_keys,
+                recent_blockhash,
+                instructions,
+            },
+        })
     }
 
-    /// Parse transaction from JSON (like Solana RPC)
+    /// Parse transaction from the JSON `transaction_to_json` produces
+    #[cfg(feature = "std")]
     pub fn parse_transaction_json(json: &str) -> Result<SolanaTransaction> {
         serde_json::from_str(json)
             .map_err(|e| TerminatorError::SerializationError(format!("Failed to parse JSON transaction: {}", e)))
     }
 
     /// Convert transaction to JSON
+    #[cfg(feature = "std")]
     pub fn transaction_to_json(tx: &SolanaTransaction) -> Result<String> {
         serde_json::to_string_pretty(tx)
             .map_err(|e| TerminatorError::SerializationError(format!("Failed to serialize JSON: {}", e)))
@@ -645,7 +1200,7 @@ impl SolanaTransactionParser {
     ) -> SolanaTransaction {
         // System program transfer instruction data
         let mut instruction_data = vec![2u8]; // Transfer instruction
-        instruction_data.extend_from_slice(&lamports.to_le_bytes());
+        write_u64_le(&mut instruction_data, lamports);
 
         let instruction = CompiledInstruction {
             program_id_index: 2, // System program will be at index 2
@@ -672,39 +1227,127 @@ impl SolanaTransactionParser {
 
     /// Extract message for signing (without signatures)
     pub fn message_data(message: &SolanaMessage) -> Result<Vec<u8>> {
-        bincode::serialize(message)
-            .map_err(|e| TerminatorError::SerializationError(format!("Failed to serialize message: {}", e)))
+        let mut data = Vec::new();
+        write_legacy_message(&mut data, message)?;
+        Ok(data)
+    }
+
+    /// Bytes a versioned message's signatures cover: the legacy encoding, or
+    /// the v0 encoding behind its `0x80` version prefix
+    pub fn versioned_message_data(message: &VersionedMessage) -> Result<Vec<u8>> {
+        match message {
+            VersionedMessage::Legacy(message) => Self::message_data(message),
+            VersionedMessage::V0(message) => {
+                let mut data = Vec::new();
+                write_v0_message(&mut data, message)?;
+                Ok(data)
+            }
+        }
     }
 
     /// Validate transaction format
     pub fn validate_transaction_format(tx: &SolanaTransaction) -> Result<()> {
-        // Check signature count matches required signatures
-        if tx.signatures.len() != tx.message.header.num_required_signatures as usize {
+        let num_required_signatures = tx.message.header.num_required_signatures as usize;
+        
+        // The fee payer (account 0) must sign, so at least one signature is required
+        if num_required_signatures == 0 {
             return Err(TerminatorError::TransactionExecutionFailed(
-                "Signature count mismatch".to_string()
+                "Transaction requires no signatures; the fee payer must sign".to_string()
             ));
         }
 
+        // Every required signer must be an account key
+        if num_required_signatures > tx.message.account_keys.len() {
+            return Err(TerminatorError::TransactionExecutionFailed(format!(
+                "Header requires {} signers but only {} account keys are present",
+                num_required_signatures,
+                tx.message.account_keys.len()
+            )));
+        }
+
+        // The fee payer's lamports are debited, so it can't be readonly
+        if !is_writable(0, &tx.message.header, tx.message.account_keys.len()) {
+            return Err(TerminatorError::TransactionExecutionFailed("fee payer must be writable".to_string()));
+        }
+
+        // The readonly non-signers are the tail of the non-signer keys, so
+        // the header can't claim more of them than there are
+        let num_unsigned = tx.message.account_keys.len() - num_required_signatures;
+        if tx.message.header.num_readonly_unsigned_accounts as usize > num_unsigned {
+            return Err(TerminatorError::TransactionExecutionFailed(format!(
+                "Header marks {} non-signers readonly but only {} account keys are non-signers",
+                tx.message.header.num_readonly_unsigned_accounts,
+                num_unsigned
+            )));
+        }
+
+        // Check signature count matches required signatures
+        if tx.signatures.len() != num_required_signatures {
+            return Err(TerminatorError::TransactionExecutionFailed(format!(
+                "Signature count mismatch: header requires {}, found {}",
+                num_required_signatures,
+                tx.signatures.len()
+            )));
+        }
+
+        Self::check_limits(&tx.message)?;
+
+        // The whole transaction has to fit in one packet
+        let size = Self::serialize_transaction(tx)?.len();
+        if size > PACKET_DATA_SIZE {
+            return Err(TerminatorError::TransactionExecutionFailed(format!(
+                "Transaction is {} bytes, exceeding PACKET_DATA_SIZE ({})",
+                size, PACKET_DATA_SIZE
+            )));
+        }
+
         // Check account indices are valid
         let num_accounts = tx.message.account_keys.len() as u8;
         for instruction in &tx.message.instructions {
             if instruction.program_id_index >= num_accounts {
+                return Err(TerminatorError::InvalidAccountIndex);
+            }
+            
+            // A program can't pay fees, so the fee payer is never invoked
+            if instruction.program_id_index == 0 {
                 return Err(TerminatorError::TransactionExecutionFailed(
-                    "Invalid program_id_index".to_string()
+                    "fee payer cannot be invoked as a program".to_string()
                 ));
             }
             
             for &account_index in &instruction.accounts {
                 if account_index >= num_accounts {
-                    return Err(TerminatorError::TransactionExecutionFailed(
-                        "Invalid account index".to_string()
-                    ));
+                    return Err(TerminatorError::InvalidAccountIndex);
                 }
             }
         }
 
         Ok(())
     }
+
+    /// Fail if the message holds more account keys or instructions, or an
+    /// instruction references more accounts, than Solana allows
+    fn check_limits(message: &SolanaMessage) -> Result<()> {
+        let exceeded = |what: &str, count: usize, limit: &str, max: usize| {
+            Err(TerminatorError::TransactionExecutionFailed(format!(
+                "Transaction has {} {}, exceeding {} ({})",
+                count, what, limit, max
+            )))
+        };
+        if message.account_keys.len() > MAX_ACCOUNTS {
+            return exceeded("account keys", message.account_keys.len(), "MAX_ACCOUNTS", MAX_ACCOUNTS);
+        }
+        if message.instructions.len() > MAX_INSTRUCTIONS {
+            return exceeded("instructions", message.instructions.len(), "MAX_INSTRUCTIONS", MAX_INSTRUCTIONS);
+        }
+        if let Some(instruction) = message.instructions.iter().find(|ix| ix.accounts.len() > MAX_ACCOUNTS) {
+            return exceeded("instruction accounts", instruction.accounts.len(), "MAX_ACCOUNTS", MAX_ACCOUNTS);
+        }
+        if let Some(instruction) = message.instructions.iter().find(|ix| ix.data.len() > PACKET_DATA_SIZE) {
+            return exceeded("bytes of instruction data", instruction.data.len(), "PACKET_DATA_SIZE", PACKET_DATA_SIZE);
+        }
+        Ok(())
+    }
 }
 
 /// Advanced Solana features with v0 support
@@ -755,7 +1398,7 @@ impl SolanaFeatures {
 
     /// Check if transaction is v0 format
     pub fn is_v0_transaction(data: &[u8]) -> bool {
-        !data.is_empty() && (data[0] & 0x80) != 0
+        data.first().is_some_and(|byte| byte & 0x80 != 0)
     }
 }
 
@@ -803,6 +1446,197 @@ mod tests {
         assert!(result.is_ok(), "Valid transaction should pass validation");
     }
 
+    #[test]
+    fn test_validation_rejects_bad_signer_counts() {
+        let tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([2u8; 32]), 1000, SolanaHash([3u8; 32]),
+        );
+
+        let mut unsigned = tx.clone();
+        unsigned.signatures.clear();
+        unsigned.message.header.num_required_signatures = 0;
+        assert!(matches!(
+            SolanaTransactionParser::validate_transaction_format(&unsigned),
+            Err(TerminatorError::TransactionExecutionFailed(ref msg)) if msg.contains("fee payer must sign")
+        ));
+
+        let mut too_many_signers = tx;
+        too_many_signers.message.header.num_required_signatures = 4;
+        too_many_signers.signatures = vec![SolanaSignature([0u8; 64]); 4];
+        assert!(matches!(
+            SolanaTransactionParser::validate_transaction_format(&too_many_signers),
+            Err(TerminatorError::TransactionExecutionFailed(ref msg)) if msg.contains("only 3 account keys")
+        ));
+    }
+
+    #[test]
+    fn test_readonly_fee_payer_rejected() {
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]),
+            SolanaPubkey::new([2u8; 32]),
+            1_000,
+            SolanaHash([0u8; 32]),
+        );
+        assert!(SolanaTransactionParser::validate_transaction_format(&tx).is_ok());
+
+        // With its only signer readonly, the fee payer at index 0 can't be debited
+        tx.message.header.num_readonly_signed_accounts = 1;
+        let result = SolanaTransactionParser::validate_transaction_format(&tx);
+        assert!(matches!(result, Err(TerminatorError::TransactionExecutionFailed(msg)) if msg == "fee payer must be writable"));
+    }
+
+    #[test]
+    fn test_instruction_limit_enforced_by_parser_and_validator() {
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([2u8; 32]), 1000, SolanaHash([3u8; 32]),
+        );
+        let transfer = tx.message.instructions[0].clone();
+        tx.message.instructions = vec![transfer; MAX_INSTRUCTIONS];
+        let wire = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        assert!(SolanaTransactionParser::parse_transaction(&wire).is_ok());
+        assert!(SolanaTransactionParser::validate_transaction_format(&tx).is_ok());
+
+        tx.message.instructions.push(tx.message.instructions[0].clone());
+        assert!(matches!(
+            SolanaTransactionParser::validate_transaction_format(&tx),
+            Err(TerminatorError::TransactionExecutionFailed(ref msg))
+                if msg == "Transaction has 65 instructions, exceeding MAX_INSTRUCTIONS (64)"
+        ));
+        let wire = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        let error = SolanaTransactionParser::parse_transaction_manual(&wire).unwrap_err();
+        assert_eq!((error.expected, error.found.as_str()), ("instruction count", "65 (limit 64)"));
+        assert!(SolanaTransactionParser::parse_transaction(&wire).is_err());
+    }
+
+    #[test]
+    fn test_oversized_transaction_rejected_by_every_parser() {
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([2u8; 32]), 1000, SolanaHash([3u8; 32]),
+        );
+        // Fill the packet exactly; the data's length prefix grows to two bytes
+        let base_len = SolanaTransactionParser::serialize_transaction(&tx).unwrap().len() - tx.message.instructions[0].data.len();
+        tx.message.instructions[0].data = vec![0u8; PACKET_DATA_SIZE - base_len - 1];
+        let legacy = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        assert_eq!(legacy.len(), PACKET_DATA_SIZE);
+        assert!(SolanaTransactionParser::parse_transaction(&legacy).is_ok());
+        assert!(SolanaTransactionParser::validate_transaction_format(&tx).is_ok());
+
+        tx.message.instructions[0].data.push(0);
+        let legacy = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        // The same message as v0: one signature, no lookup tables
+        let mut v0 = vec![0x81];
+        v0.extend_from_slice(&legacy[1..]);
+        v0.push(0);
+
+        let legacy_error = SolanaTransactionParser::parse_transaction(&legacy).unwrap_err().to_string();
+        assert!(legacy_error.contains("expected end of packet, found 1233 bytes (limit 1232)"), "{}", legacy_error);
+        assert_eq!(SolanaTransactionParser::parse_versioned_transaction(&legacy).unwrap_err().to_string(), legacy_error);
+        assert!(SolanaTransactionParser::parse_versioned_transaction(&v0).is_err());
+        assert!(SolanaTransactionParser::parse_transaction_manual(&legacy).is_err());
+        assert!(SolanaTransactionParser::validate_transaction_format(&tx).is_err());
+    }
+
+    #[test]
+    fn test_message_hash_covers_blockhash() {
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        let tx = SolanaTransactionParser::create_transfer_transaction(from, to, 1000, SolanaHash([3u8; 32]));
+        let other_blockhash = SolanaTransactionParser::create_transfer_transaction(from, to, 1000, SolanaHash([4u8; 32]));
+
+        assert_eq!(tx.message_hash().unwrap(), tx.clone().message_hash().unwrap());
+        assert_ne!(tx.message_hash().unwrap(), other_blockhash.message_hash().unwrap());
+        assert_eq!(tx.signature().map(|signature| signature.0), Some([0u8; 64]));
+
+        let mut expected = MESSAGE_HASH_PREFIX.to_vec();
+        expected.extend(SolanaTransactionParser::message_data(&tx.message).unwrap());
+        assert_eq!(tx.message_hash().unwrap(), *blake3::hash(&expected).as_bytes());
+
+        let mut too_many_keys = tx;
+        too_many_keys.message.account_keys = vec![from; u16::MAX as usize + 1];
+        assert!(too_many_keys.message_hash().is_err());
+    }
+
+    #[test]
+    fn test_is_writable_layout() {
+        // 2 signers (1 readonly), 3 non-signers (1 readonly)
+        let header = MessageHeader {
+            num_required_signatures: 2,
+            num_readonly_signed_accounts: 1,
+            num_readonly_unsigned_accounts: 1,
+        };
+
+        assert!(is_writable(0, &header, 5));
+        assert!(!is_writable(1, &header, 5));
+        assert!(is_writable(2, &header, 5));
+        assert!(is_writable(3, &header, 5));
+        assert!(!is_writable(4, &header, 5));
+        assert!(!is_writable(5, &header, 5));
+    }
+
+    #[test]
+    fn test_privileges_across_header_permutations() {
+        for num_keys in 1..=6usize {
+            for signers in 0..=3u8 {
+                for readonly_signed in 0..=3u8 {
+                    for readonly_unsigned in 0..=3u8 {
+                        let header = MessageHeader {
+                            num_required_signatures: signers,
+                            num_readonly_signed_accounts: readonly_signed,
+                            num_readonly_unsigned_accounts: readonly_unsigned,
+                        };
+                        let tx = SolanaTransaction {
+                            signatures: vec![SolanaSignature([0u8; 64]); signers as usize],
+                            message: SolanaMessage {
+                                header: header.clone(),
+                                account_keys: (0..num_keys as u8).map(|i| SolanaPubkey::new([i; 32])).collect(),
+                                recent_blockhash: SolanaHash([0u8; 32]),
+                                instructions: vec![],
+                            },
+                        };
+                        let case = format!("{} keys, header {:?}", num_keys, header);
+
+                        // Solana's sanitize rules: a signing fee payer that stays
+                        // writable, and readonly counts that fit their group
+                        let (signers, readonly_signed, readonly_unsigned) =
+                            (signers as usize, readonly_signed as usize, readonly_unsigned as usize);
+                        let valid = signers >= 1
+                            && readonly_signed < signers
+                            && signers + readonly_unsigned <= num_keys;
+                        assert_eq!(SolanaTransactionParser::validate_transaction_format(&tx).is_ok(), valid, "{}", case);
+                        if !valid {
+                            continue;
+                        }
+
+                        // (signer, writable) for each key, built group by group
+                        let expected: Vec<(bool, bool)> = std::iter::repeat((true, true)).take(signers - readonly_signed)
+                            .chain(std::iter::repeat((true, false)).take(readonly_signed))
+                            .chain(std::iter::repeat((false, true)).take(num_keys - signers - readonly_unsigned))
+                            .chain(std::iter::repeat((false, false)).take(readonly_unsigned))
+                            .collect();
+                        let actual: Vec<(bool, bool)> = (0..num_keys)
+                            .map(|i| (is_signer(i, &header), is_writable(i, &header, num_keys)))
+                            .collect();
+                        assert_eq!(actual, expected, "{}", case);
+                        assert_eq!(actual[0], (true, true), "fee payer, {}", case);
+                        assert!(!is_writable(num_keys, &header, num_keys), "{}", case);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fee_payer_cannot_be_invoked() {
+        let mut tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([2u8; 32]), 1000, SolanaHash([3u8; 32]),
+        );
+        tx.message.instructions[0].program_id_index = 0;
+        assert!(matches!(
+            SolanaTransactionParser::validate_transaction_format(&tx),
+            Err(TerminatorError::TransactionExecutionFailed(ref msg)) if msg == "fee payer cannot be invoked as a program"
+        ));
+    }
+
     #[test]
     fn test_v0_transaction_detection() {
         let v0_data = vec![0x81, 0x00]; // v0 transaction with 1 signature
@@ -828,6 +1662,360 @@ mod tests {
         assert_eq!(tx.message.account_keys.len(), parsed.message.account_keys.len());
     }
 
+    /// Mainnet SOL transfer, as checked in to `examples/debug_tx_bytes.rs`
+    const MAINNET_TRANSFER_BASE64: &str = "AWDBlrdyFjzjDgf9gWioXrCB/YJpHeENZcIEwNPzflGviVkElIKpUR7yvnwrNsz0cuq5MGm0FlR/7gf8piruIw6AAQABA/NGAeBeYMRrJvmYo4E2q+pEKIVjl40S0g00e/NP8G7JAGBZvnD3SSIz2B5EgB+fk5vSvVThak5kIyxG8n1zLKIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAVYxfd1NZLpMnJgbaVBboof2ZjR+cEKxQwMiWhlFusxAQICAAEMAgAAAGgKHwAAAAAAAA==";
+
+    #[test]
+    fn test_parse_transaction_encodings() {
+        let tx = SolanaTransactionParser::parse_transaction_base64(MAINNET_TRANSFER_BASE64).unwrap();
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.message.account_keys.len(), 3);
+        assert_eq!(tx.message.instructions.len(), 1);
+
+        let raw = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        let from_base58 = SolanaTransactionParser::parse_transaction_base58(&bs58::encode(&raw).into_string()).unwrap();
+        assert_eq!(from_base58.message.account_keys, tx.message.account_keys);
+    }
+
+    #[test]
+    fn test_parse_mainnet_transfer_keys() {
+        let raw = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        let tx = SolanaTransactionParser::parse_transaction_manual(&raw).unwrap();
+
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.message.header.num_required_signatures, 1);
+        assert_eq!(tx.message.account_keys[0].to_string(), "HNdz9s5NTkDxQCU5ri3GTRskzc9wD62ftoMY1QneeuLt");
+        assert_eq!(tx.message.account_keys[1].to_string(), "12UDMHLmX1DRUKJTsu5zMHx7dX7CsaUfoTFhj9BEBPz1");
+        assert_eq!(tx.message.account_keys[2], SolanaPubkey::system_program());
+
+        let transfer = &tx.message.instructions[0];
+        assert_eq!(transfer.accounts, vec![0, 1]);
+        assert_eq!(&transfer.data[..4], &[2, 0, 0, 0]);
+        assert_eq!(u64::from_le_bytes(transfer.data[4..12].try_into().unwrap()), 2_034_280);
+    }
+
+    #[test]
+    fn test_mainnet_transfer_lamports() {
+        // The amount follows the 4-byte instruction tag: 68 0a 1f 00 .. = 2,034,280,
+        // not the 2,034,200 `examples/debug_instruction_data.rs` was looking for
+        let raw = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        let tx = SolanaTransactionParser::parse_transaction(&raw).unwrap();
+        let instructions = tx.message.decompile_all().unwrap();
+
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(instructions[0].program_id, SolanaPubkey::system_program());
+        assert_eq!(
+            crate::system_program::SystemInstruction::decode(&instructions[0].data).unwrap(),
+            crate::system_program::SystemInstruction::Transfer { lamports: 2_034_280 }
+        );
+    }
+
+    #[test]
+    fn test_bincode_fast_path_reads_wire_format() {
+        let raw = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        let tx = SolanaTransactionParser::parse_transaction(&raw).unwrap();
+        let transfer = &tx.message.instructions[0];
+        assert_eq!(u64::from_le_bytes(transfer.data[4..12].try_into().unwrap()), 2_034_280);
+
+        // Dropping the v0 prefix and the empty lookup table list leaves the
+        // same message in legacy wire format, which bincode reads directly
+        let mut legacy = raw.clone();
+        legacy.remove(65);
+        assert_eq!(legacy.pop(), Some(0));
+        let fast: SolanaTransaction = bincode::deserialize(&legacy).unwrap();
+        assert_eq!(fast.message.account_keys, tx.message.account_keys);
+        assert_eq!(fast.message.instructions[0].data, transfer.data);
+        assert_eq!(SolanaTransactionParser::serialize_transaction(&fast).unwrap(), legacy);
+        assert_eq!(SolanaTransactionParser::message_data(&fast.message).unwrap(), bincode::serialize(&fast.message).unwrap());
+
+        let v0 = V0Message {
+            header: fast.message.header.clone(),
+            account_keys: fast.message.account_keys.clone(),
+            recent_blockhash: fast.message.recent_blockhash.clone(),
+            instructions: fast.message.instructions.clone(),
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: SolanaPubkey::new([7u8; 32]),
+                writable_indexes: vec![0, 2],
+                readonly_indexes: vec![1],
+            }],
+        };
+        let mut expected = vec![0x80];
+        expected.extend(bincode::serialize(&v0).unwrap());
+        assert_eq!(SolanaTransactionParser::versioned_message_data(&VersionedMessage::V0(v0)).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_validate_roundtrip_catches_misparsed_blobs() {
+        let tx = SolanaTransactionParser::create_transfer_transaction(
+            SolanaPubkey::new([1u8; 32]),
+            SolanaPubkey::new([2u8; 32]),
+            5_000,
+            SolanaHash([9u8; 32]),
+        );
+        let wire = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+        assert!(SolanaTransactionParser::validate_roundtrip(&wire).is_ok());
+
+        // A v0 blob assembled from our own bincode output parses, but as a legacy
+        // transaction without its version prefix or lookup tables
+        let v0 = VersionedMessage::V0(V0Message {
+            header: tx.message.header.clone(),
+            account_keys: tx.message.account_keys.clone(),
+            recent_blockhash: tx.message.recent_blockhash.clone(),
+            instructions: tx.message.instructions.clone(),
+            address_table_lookups: Vec::new(),
+        });
+        let mut blob = encode_shortvec_len(1);
+        blob.extend_from_slice(&[0u8; 64]);
+        blob.extend(SolanaTransactionParser::versioned_message_data(&v0).unwrap());
+        assert!(SolanaTransactionParser::parse_transaction(&blob).is_ok());
+
+        let error = SolanaTransactionParser::validate_roundtrip(&blob).unwrap_err().to_string();
+        assert!(error.contains("mismatch at byte 65"), "{}", error);
+        assert!(error.contains("input is 214 bytes, re-serialized is 212 bytes"), "{}", error);
+
+        let mut trailing = wire.clone();
+        trailing.push(0);
+        assert!(SolanaTransactionParser::validate_roundtrip(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_versioned_message_data_matches_signed_bytes() {
+        let raw = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        let v0 = SolanaTransactionParser::parse_v0_message(&mut ByteReader::new(&raw[66..])).unwrap();
+        let signed = SolanaTransactionParser::versioned_message_data(&VersionedMessage::V0(v0.clone())).unwrap();
+        assert_eq!(signed, raw[65..]);
+
+        let signature: [u8; 64] = raw[1..65].try_into().unwrap();
+        assert!(crate::crypto::SolanaCrypto::verify_ed25519_signature(&signature, &signed, &v0.account_keys[0].0).unwrap());
+    }
+
+    /// `getTransaction` response for the mainnet transfer, `json` encoding
+    const MAINNET_TRANSFER_RPC_JSON: &str = r#"{
+        "jsonrpc": "2.0",
+        "result": {
+            "blockTime": 1718035524,
+            "meta": { "err": null, "fee": 5000, "status": { "Ok": null } },
+            "slot": 270450245,
+            "transaction": {
+                "message": {
+                    "accountKeys": [
+                        "HNdz9s5NTkDxQCU5ri3GTRskzc9wD62ftoMY1QneeuLt",
+                        "12UDMHLmX1DRUKJTsu5zMHx7dX7CsaUfoTFhj9BEBPz1",
+                        "11111111111111111111111111111111"
+                    ],
+                    "addressTableLookups": [],
+                    "header": {
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1,
+                        "numRequiredSignatures": 1
+                    },
+                    "instructions": [
+                        { "accounts": [0, 1], "data": "3Bxs4JFyTAZwC3ZZ", "programIdIndex": 2, "stackHeight": null }
+                    ],
+                    "recentBlockhash": "MsYuNgeyZLCEPfxLvu3teiY9ynRzNYuwWaSuU9PctsS"
+                },
+                "signatures": [
+                    "2wCYd17DwhBFXayhjRZSrWgQRGfYDg4EgXig2JF17zTM53EzNACYDVUsdS63pQkvevUiGnummtcG3vzKArQncMpq"
+                ]
+            },
+            "version": 0
+        },
+        "id": 1
+    }"#;
+
+    /// The same response in `jsonParsed` encoding
+    const MAINNET_TRANSFER_RPC_JSON_PARSED: &str = r#"{
+        "jsonrpc": "2.0",
+        "result": {
+            "slot": 270450245,
+            "transaction": {
+                "message": {
+                    "accountKeys": [
+                        { "pubkey": "HNdz9s5NTkDxQCU5ri3GTRskzc9wD62ftoMY1QneeuLt", "signer": true, "source": "transaction", "writable": true },
+                        { "pubkey": "12UDMHLmX1DRUKJTsu5zMHx7dX7CsaUfoTFhj9BEBPz1", "signer": false, "source": "transaction", "writable": true },
+                        { "pubkey": "11111111111111111111111111111111", "signer": false, "source": "transaction", "writable": false }
+                    ],
+                    "addressTableLookups": [],
+                    "instructions": [
+                        {
+                            "parsed": {
+                                "info": {
+                                    "destination": "12UDMHLmX1DRUKJTsu5zMHx7dX7CsaUfoTFhj9BEBPz1",
+                                    "lamports": 2034280,
+                                    "source": "HNdz9s5NTkDxQCU5ri3GTRskzc9wD62ftoMY1QneeuLt"
+                                },
+                                "type": "transfer"
+                            },
+                            "program": "system",
+                            "programId": "11111111111111111111111111111111",
+                            "stackHeight": null
+                        }
+                    ],
+                    "recentBlockhash": "MsYuNgeyZLCEPfxLvu3teiY9ynRzNYuwWaSuU9PctsS"
+                },
+                "signatures": [
+                    "2wCYd17DwhBFXayhjRZSrWgQRGfYDg4EgXig2JF17zTM53EzNACYDVUsdS63pQkvevUiGnummtcG3vzKArQncMpq"
+                ]
+            },
+            "version": 0
+        },
+        "id": 1
+    }"#;
+
+    #[test]
+    fn test_parse_rpc_transaction_json() {
+        let wire = SolanaTransactionParser::parse_transaction_base64(MAINNET_TRANSFER_BASE64).unwrap();
+        let wire_message = SolanaTransactionParser::message_data(&wire.message).unwrap();
+
+        for response in [MAINNET_TRANSFER_RPC_JSON, MAINNET_TRANSFER_RPC_JSON_PARSED] {
+            let value: serde_json::Value = serde_json::from_str(response).unwrap();
+            let tx = SolanaTransactionParser::parse_rpc_transaction_json(&value).unwrap();
+
+            assert_eq!(tx.signatures[0].0, wire.signatures[0].0);
+            assert_eq!(SolanaTransactionParser::message_data(&tx.message).unwrap(), wire_message);
+            let transfer = &tx.message.instructions[0];
+            assert_eq!(u64::from_le_bytes(transfer.data[4..12].try_into().unwrap()), 2_034_280);
+        }
+
+        // Binary encodings go through the wire parser
+        let base64 = serde_json::json!({ "transaction": [MAINNET_TRANSFER_BASE64, "base64"] });
+        let tx = SolanaTransactionParser::parse_rpc_transaction_json(&base64).unwrap();
+        assert_eq!(tx.message.account_keys, wire.message.account_keys);
+    }
+
+    #[test]
+    fn test_transfer_account_metas() {
+        let from = SolanaPubkey::new([1u8; 32]);
+        let to = SolanaPubkey::new([2u8; 32]);
+        let tx = SolanaTransactionParser::create_transfer_transaction(from, to, 100, SolanaHash([0u8; 32]));
+
+        let metas = tx.message.instructions[0].account_metas(&tx.message.header, &tx.message.account_keys).unwrap();
+        assert_eq!(metas, vec![
+            AccountMeta { pubkey: from.into(), is_signer: true, is_writable: true },
+            AccountMeta { pubkey: to.into(), is_signer: false, is_writable: true },
+        ]);
+
+        let bad_index = CompiledInstruction { program_id_index: 2, accounts: vec![0, 3], data: vec![] };
+        assert!(bad_index.account_metas(&tx.message.header, &tx.message.account_keys).is_err());
+    }
+
+    #[test]
+    fn test_signature_count_is_compact_u16() {
+        let mut data = encode_shortvec_len(130);
+        data.extend(vec![0u8; 64]);
+        // Only one of the 130 announced signatures is present
+        assert_eq!(
+            SolanaTransactionParser::parse_transaction_manual(&data).unwrap_err(),
+            ParseError { offset: 66, expected: "signature", found: "end of data".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_parse_error_offset_mid_account_key() {
+        let data = BASE64.decode(MAINNET_TRANSFER_BASE64).unwrap();
+        // 1 signature, the v0 prefix, the header and the key count put the
+        // first key at 70; cut the second key off 8 bytes in
+        let error = SolanaTransactionParser::parse_transaction_manual(&data[..110]).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError { offset: 102, expected: "account key", found: "8 of 32 bytes".to_string() }
+        );
+        assert_eq!(
+            TerminatorError::from(error).to_string(),
+            TerminatorError::SerializationError(
+                "at offset 102 expected account key, found 8 of 32 bytes".to_string()
+            ).to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_transaction_bad_encoding() {
+        let corrupted = MAINNET_TRANSFER_BASE64.replacen('A', "!", 1);
+        assert!(matches!(
+            SolanaTransactionParser::parse_transaction_base64(&corrupted),
+            Err(TerminatorError::SerializationError(_))
+        ));
+        assert!(matches!(
+            SolanaTransactionParser::parse_transaction_base58("0OIl"),
+            Err(TerminatorError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_unique_is_distinct() {
+        let keys: std::collections::HashSet<SolanaPubkey> = (0..10_000)
+            .map(|_| SolanaPubkey::new_unique())
+            .collect();
+        assert_eq!(keys.len(), 10_000);
+    }
+
+    fn stream_of_transfers(count: u8) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for i in 0..count {
+            let tx = SolanaTransactionParser::create_transfer_transaction(
+                SolanaPubkey::new([1u8; 32]), SolanaPubkey::new([i + 2; 32]), 1_000 * (i as u64 + 1), SolanaHash([0u8; 32]),
+            );
+            let bytes = SolanaTransactionParser::serialize_transaction(&tx).unwrap();
+            stream.extend(encode_shortvec_len(bytes.len() as u16));
+            stream.extend(bytes);
+        }
+        stream
+    }
+
+    fn assert_every_parser_rejects(data: &[u8]) {
+        assert!(SolanaTransactionParser::parse_transaction(data).is_err());
+        assert!(SolanaTransactionParser::parse_versioned_transaction(data).is_err());
+        assert!(SolanaTransactionParser::parse_transaction_manual(data).is_err());
+    }
+
+    #[test]
+    fn test_short_inputs_never_panic_sampled() {
+        // Every byte string of length 0 through 2, but only the 3-byte strings
+        // whose last byte is a count or shortvec boundary; the full 3-byte
+        // sweep is `test_all_three_byte_inputs_never_panic`
+        let short = (0..=2usize).flat_map(|len| (0..1u32 << (8 * len)).map(move |value| (len, value.to_le_bytes())));
+        let three = [0x00u8, 0x01, 0x7F, 0x80, 0xFF]
+            .into_iter()
+            .flat_map(|last| (0..1u32 << 16).map(move |value| (3, (value | (last as u32) << 16).to_le_bytes())));
+
+        for (len, bytes) in short.chain(three) {
+            assert_every_parser_rejects(&bytes[..len]);
+        }
+    }
+
+    #[test]
+    #[ignore = "sweeps all 16M 3-byte strings, which takes over a minute in a debug build"]
+    fn test_all_three_byte_inputs_never_panic() {
+        for value in 0..1u32 << 24 {
+            assert_every_parser_rejects(&value.to_le_bytes()[..3]);
+        }
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        let stream = stream_of_transfers(3);
+        let parsed: Vec<SolanaTransaction> = SolanaTransactionParser::parse_stream(&stream)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(parsed.len(), 3);
+        for (i, tx) in parsed.iter().enumerate() {
+            assert_eq!(tx.message.account_keys[1], SolanaPubkey::new([i as u8 + 2; 32]));
+        }
+        assert_eq!(decode_shortvec_len(&encode_shortvec_len(300)).unwrap(), (300, 2));
+    }
+
+    #[test]
+    fn test_parse_stream_truncated_entry() {
+        let mut stream = stream_of_transfers(2);
+        stream.truncate(stream.len() - 10);
+
+        let results: Vec<Result<SolanaTransaction>> = SolanaTransactionParser::parse_stream(&stream).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(TerminatorError::SerializationError(_))));
+    }
+
     #[test]
     fn test_system_program_ids() {
         let system = SolanaPubkey::system_program();
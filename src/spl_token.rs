@@ -0,0 +1,131 @@
+/// SPL Token instruction decoding
+/// Recognizes token transfers for analysis; token logic itself is not executed
+
+use crate::prelude::*;
+use crate::{Result, TerminatorError};
+use crate::describe::format_amount;
+use crate::solana_format::SolanaPubkey;
+use crate::wire::read_u64_le;
+
+/// `Transfer` instruction tag
+const TRANSFER_TAG: u8 = 3;
+/// `TransferChecked` instruction tag
+const TRANSFER_CHECKED_TAG: u8 = 12;
+
+/// The SPL Token instructions this crate understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenInstruction {
+    /// Accounts:
+    /// [0] Source token account (writable)
+    /// [1] Destination token account (writable)
+    /// [2] Owner or delegate (signer)
+    Transfer { amount: u64 },
+
+    /// Accounts:
+    /// [0] Source token account (writable)
+    /// [1] Token mint
+    /// [2] Destination token account (writable)
+    /// [3] Owner or delegate (signer)
+    TransferChecked { amount: u64, decimals: u8 },
+}
+
+impl TokenInstruction {
+    /// Decode a tag byte followed by a little-endian amount (and decimals, when checked)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        match data.split_first() {
+            Some((&TRANSFER_TAG, rest)) if rest.len() == 8 => Ok(TokenInstruction::Transfer { amount: read_u64_le(rest, 0)? }),
+            Some((&TRANSFER_CHECKED_TAG, rest)) if rest.len() == 9 => Ok(TokenInstruction::TransferChecked {
+                amount: read_u64_le(rest, 0)?,
+                decimals: rest[8],
+            }),
+            Some((&(TRANSFER_TAG | TRANSFER_CHECKED_TAG), _)) => Err(TerminatorError::SerializationError(
+                "Invalid token transfer data length".to_string()
+            )),
+            Some((tag, _)) => Err(TerminatorError::SerializationError(format!("Unsupported token instruction: {}", tag))),
+            None => Err(TerminatorError::SerializationError("Empty token instruction".to_string())),
+        }
+    }
+}
+
+impl TokenInstruction {
+    /// Human-readable summary of what the instruction does
+    pub fn describe(&self) -> String {
+        match self {
+            TokenInstruction::Transfer { amount } => format!("Transfer {} token base units", amount),
+            TokenInstruction::TransferChecked { amount, decimals } => {
+                format!("Transfer {} tokens", format_amount(*amount, *decimals))
+            }
+        }
+    }
+}
+
+/// A decoded token transfer, with its accounts resolved to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub source: SolanaPubkey,
+    pub destination: SolanaPubkey,
+    pub authority: SolanaPubkey,
+    /// Only known for `TransferChecked`
+    pub mint: Option<SolanaPubkey>,
+    /// Amount in the mint's base units
+    pub amount: u64,
+    /// Only known for `TransferChecked`
+    pub decimals: Option<u8>,
+}
+
+impl TokenTransfer {
+    /// Decode a token transfer from its instruction data and accounts, in instruction order
+    pub fn decode(data: &[u8], accounts: &[SolanaPubkey]) -> Result<Self> {
+        let account = |index: usize| {
+            accounts.get(index).copied().ok_or_else(|| {
+                TerminatorError::TransactionExecutionFailed("Token transfer is missing accounts".to_string())
+            })
+        };
+
+        match TokenInstruction::decode(data)? {
+            TokenInstruction::Transfer { amount } => Ok(TokenTransfer {
+                source: account(0)?,
+                destination: account(1)?,
+                authority: account(2)?,
+                mint: None,
+                amount,
+                decimals: None,
+            }),
+            TokenInstruction::TransferChecked { amount, decimals } => Ok(TokenTransfer {
+                source: account(0)?,
+                mint: Some(account(1)?),
+                destination: account(2)?,
+                authority: account(3)?,
+                amount,
+                decimals: Some(decimals),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_transfer_checked() {
+        let mut data = vec![12u8];
+        data.extend_from_slice(&1_500_000u64.to_le_bytes());
+        data.push(6);
+        assert_eq!(
+            TokenInstruction::decode(&data).unwrap(),
+            TokenInstruction::TransferChecked { amount: 1_500_000, decimals: 6 }
+        );
+
+        let keys: Vec<SolanaPubkey> = (1..=4).map(|i| SolanaPubkey::new([i; 32])).collect();
+        let transfer = TokenTransfer::decode(&data, &keys).unwrap();
+        assert_eq!(transfer.source, keys[0]);
+        assert_eq!(transfer.mint, Some(keys[1]));
+        assert_eq!(transfer.destination, keys[2]);
+        assert_eq!(transfer.authority, keys[3]);
+        assert_eq!((transfer.amount, transfer.decimals), (1_500_000, Some(6)));
+
+        assert!(TokenTransfer::decode(&data, &keys[..3]).is_err());
+        assert!(TokenInstruction::decode(&data[..9]).is_err());
+    }
+}
@@ -1,15 +1,161 @@
 /// Solana System Program Implementation
 /// Handles: Transfer, CreateAccount, Assign, Allocate, etc.
 
+use crate::prelude::*;
 use crate::{Result, TerminatorError};
-use crate::types::{Account, Pubkey, ExecutionContext};
+use crate::describe::{format_amount, SOL_DECIMALS};
+use crate::solana_format::SolanaPubkey;
+use crate::types::{Account, AccountMeta, InstructionAccounts, Pubkey, ExecutionContext, FeeCalculator, Rent};
+use crate::wire::{read_u32_le, read_u64_le};
 use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// Solana System Program ID (all zeros)
 pub const SYSTEM_PROGRAM_ID: [u8; 32] = [0u8; 32];
 
+/// Maximum account data size (10 MiB), matching Solana
+pub const MAX_PERMITTED_DATA_LENGTH: u64 = 10 * 1024 * 1024;
+
+/// Data length of a nonce account
+pub const NONCE_STATE_SIZE: usize = 80;
+
+/// State stored in a nonce account's data, behind a `NonceVersions` tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonceState {
+    Uninitialized,
+    Initialized(NonceData),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct NonceData {
+    /// Key that must sign to advance, withdraw from or re-authorize the nonce
+    pub authority: [u8; 32],
+    /// Value a durable transaction uses as its recent blockhash
+    pub durable_nonce: [u8; 32],
+    pub lamports_per_signature: u64,
+}
+
+/// Versioned layout of a nonce account's data; zeroed data is a legacy
+/// `Uninitialized` nonce
+///
+/// Enum tags are little-endian u32s, as in Solana's bincode encoding, so an
+/// initialized nonce fills `NONCE_STATE_SIZE` bytes exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonceVersions {
+    /// Durable nonce is the raw blockhash it was created at
+    Legacy(NonceState),
+    /// Durable nonce is domain-separated with `durable_nonce`
+    Current(NonceState),
+}
+
+fn invalid_tag(what: &'static str) -> borsh::io::Error {
+    borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, what)
+}
+
+impl BorshSerialize for NonceState {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        match self {
+            NonceState::Uninitialized => BorshSerialize::serialize(&0u32, writer),
+            NonceState::Initialized(data) => {
+                BorshSerialize::serialize(&1u32, writer)?;
+                BorshSerialize::serialize(data, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for NonceState {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        match u32::deserialize_reader(reader)? {
+            0 => Ok(NonceState::Uninitialized),
+            1 => Ok(NonceState::Initialized(NonceData::deserialize_reader(reader)?)),
+            _ => Err(invalid_tag("Invalid nonce state tag")),
+        }
+    }
+}
+
+impl BorshSerialize for NonceVersions {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        let (tag, state) = match self {
+            NonceVersions::Legacy(state) => (0u32, state),
+            NonceVersions::Current(state) => (1u32, state),
+        };
+        BorshSerialize::serialize(&tag, writer)?;
+        BorshSerialize::serialize(state, writer)
+    }
+}
+
+impl BorshDeserialize for NonceVersions {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        match u32::deserialize_reader(reader)? {
+            0 => Ok(NonceVersions::Legacy(NonceState::deserialize_reader(reader)?)),
+            1 => Ok(NonceVersions::Current(NonceState::deserialize_reader(reader)?)),
+            _ => Err(invalid_tag("Invalid nonce version tag")),
+        }
+    }
+}
+
+impl NonceVersions {
+    /// Decode a nonce account's data
+    pub fn from_account(account: &Account) -> Result<Self> {
+        Self::deserialize(&mut account.data.as_slice())
+            .map_err(|_| TerminatorError::ProgramError("Invalid nonce account data".to_string()))
+    }
+
+    /// Store the versioned state in a nonce account's data, which must already be sized for it
+    pub fn store(&self, account: &mut Account) -> Result<()> {
+        let bytes = borsh::to_vec(self)
+            .map_err(|e| TerminatorError::SerializationError(format!("Failed to serialize nonce state: {}", e)))?;
+        if bytes.len() > account.data.len() {
+            return Err(TerminatorError::ProgramError("Nonce account data too small".to_string()));
+        }
+        account.data[..bytes.len()].copy_from_slice(&bytes);
+        account.data[bytes.len()..].fill(0);
+        Ok(())
+    }
+
+    pub fn state(&self) -> &NonceState {
+        match self {
+            NonceVersions::Legacy(state) | NonceVersions::Current(state) => state,
+        }
+    }
+
+    /// The current-version equivalent of an initialized legacy nonce, or
+    /// `None` if there is nothing to upgrade
+    pub fn upgrade(self) -> Option<Self> {
+        match self {
+            NonceVersions::Legacy(NonceState::Initialized(mut data)) => {
+                data.durable_nonce = durable_nonce(&data.durable_nonce);
+                Some(NonceVersions::Current(NonceState::Initialized(data)))
+            }
+            NonceVersions::Legacy(NonceState::Uninitialized) | NonceVersions::Current(_) => None,
+        }
+    }
+}
+
+impl NonceState {
+    /// Decode a nonce account's data, whichever version it is stored as
+    pub fn from_account(account: &Account) -> Result<Self> {
+        NonceVersions::from_account(account).map(|versions| versions.state().clone())
+    }
+
+    /// Store the state in a nonce account's data as the current version
+    pub fn store(&self, account: &mut Account) -> Result<()> {
+        NonceVersions::Current(self.clone()).store(account)
+    }
+}
+
+/// Durable nonce for `blockhash`, domain-separated so it never equals a real blockhash
+pub fn durable_nonce(blockhash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"DURABLE_NONCE");
+    hasher.update(blockhash);
+    hasher.finalize().into()
+}
+
 /// System program instruction types (matches Solana exactly)
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub enum SystemInstruction {
     /// Create a new account
     /// Accounts:
@@ -49,6 +195,36 @@ pub enum SystemInstruction {
         owner: [u8; 32],
     },
     
+    /// Replace a nonce account's durable nonce with one from the current blockhash
+    /// Accounts:
+    /// [0] Nonce account (writable)
+    /// [1] Nonce authority (signer)
+    AdvanceNonceAccount,
+    
+    /// Withdraw lamports from a nonce account
+    /// Accounts:
+    /// [0] Nonce account (writable)
+    /// [1] Recipient account (writable)
+    /// [2] Nonce authority (signer)
+    WithdrawNonceAccount {
+        lamports: u64,
+    },
+    
+    /// Turn a rent-exempt, uninitialized account into a nonce account
+    /// Accounts:
+    /// [0] Nonce account (writable)
+    InitializeNonceAccount {
+        authority: [u8; 32],
+    },
+    
+    /// Hand a nonce account to a new authority
+    /// Accounts:
+    /// [0] Nonce account (writable)
+    /// [1] Nonce authority (signer)
+    AuthorizeNonceAccount {
+        new_authority: [u8; 32],
+    },
+    
     /// Allocate space for account data
     /// Accounts:
     /// [0] Account to allocate (signer, writable)
@@ -77,6 +253,11 @@ pub enum SystemInstruction {
         from_seed: String,
         from_owner: [u8; 32],
     },
+    
+    /// Migrate a legacy nonce account to the current nonce version
+    /// Accounts:
+    /// [0] Nonce account (writable)
+    UpgradeNonceAccount,
 }
 
 /// System Program processor
@@ -84,49 +265,71 @@ pub struct SystemProgram;
 
 impl SystemProgram {
     /// Process a system program instruction
+    ///
+    /// `account_metas` describe the instruction's accounts, parallel to `account_infos`.
     pub fn process_instruction(
         instruction_data: &[u8],
-        account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         context: &mut ExecutionContext,
     ) -> Result<()> {
-        let instruction = SystemInstruction::try_from_slice(instruction_data)
-            .map_err(|_| TerminatorError::SerializationError("Invalid system instruction".to_string()))?;
+        let instruction = SystemInstruction::decode(instruction_data)?;
+        
+        if account_metas.len() != account_infos.len() {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "One account meta is required per account".to_string()
+            ));
+        }
         
         context.log(format!("Processing system instruction: {:?}", instruction));
         
         match instruction {
             SystemInstruction::CreateAccount { lamports, space, owner } => {
-                Self::create_account(account_keys, account_infos, lamports, space, owner, context)
+                Self::create_account(account_metas, account_infos, lamports, space, owner, context)
             }
             SystemInstruction::Assign { owner } => {
-                Self::assign_account(account_infos, owner, context)
+                Self::assign_account(account_metas, account_infos, owner, context)
             }
             SystemInstruction::Transfer { lamports } => {
                 Self::transfer(account_infos, lamports, context)
             }
             SystemInstruction::CreateAccountWithSeed { base, seed, lamports, space, owner } => {
-                Self::create_account_with_seed(account_keys, account_infos, base, &seed, lamports, space, owner, context)
+                Self::create_account_with_seed(account_metas, account_infos, base, &seed, lamports, space, owner, context)
+            }
+            SystemInstruction::AdvanceNonceAccount => {
+                Self::advance_nonce_account(account_metas, account_infos, context)
+            }
+            SystemInstruction::WithdrawNonceAccount { lamports } => {
+                Self::withdraw_nonce_account(account_metas, account_infos, lamports, context)
+            }
+            SystemInstruction::InitializeNonceAccount { authority } => {
+                Self::initialize_nonce_account(account_infos, authority, context)
+            }
+            SystemInstruction::AuthorizeNonceAccount { new_authority } => {
+                Self::authorize_nonce_account(account_metas, account_infos, new_authority, context)
             }
             SystemInstruction::Allocate { space } => {
                 Self::allocate(account_infos, space, context)
             }
             SystemInstruction::AllocateWithSeed { base, seed, space, owner } => {
-                Self::allocate_with_seed(account_keys, account_infos, base, &seed, space, owner, context)
+                Self::allocate_with_seed(account_metas, account_infos, base, &seed, space, owner, context)
             }
             SystemInstruction::AssignWithSeed { base, seed, owner } => {
-                Self::assign_with_seed(account_keys, account_infos, base, &seed, owner, context)
+                Self::assign_with_seed(account_metas, account_infos, base, &seed, owner, context)
             }
             SystemInstruction::TransferWithSeed { lamports, from_seed, from_owner } => {
-                Self::transfer_with_seed(account_keys, account_infos, lamports, &from_seed, from_owner, context)
+                Self::transfer_with_seed(account_metas, account_infos, lamports, &from_seed, from_owner, context)
+            }
+            SystemInstruction::UpgradeNonceAccount => {
+                Self::upgrade_nonce_account(account_infos, context)
             }
         }
     }
     
     /// Create a new account
     fn create_account(
-        account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         lamports: u64,
         space: u64,
         owner: [u8; 32],
@@ -140,36 +343,42 @@ impl SystemProgram {
         
         context.log(format!(
             "Creating account {:?} with {} lamports, {} bytes, owner {:?}",
-            account_keys.get(1).unwrap_or(&account_keys[0]), lamports, space, owner
+            account_metas[1].pubkey, lamports, space, owner
         ));
         
+        Account::check_data_len(space)?;
+        
         // Check funding account has sufficient balance
         if account_infos[0].lamports < lamports {
             return Err(TerminatorError::InsufficientFunds);
         }
         
-        // Use split_at_mut to safely get mutable references
-        let (from_accounts, to_accounts) = account_infos.split_at_mut(1);
-        let from_account = &mut from_accounts[0];
-        let to_account = &mut to_accounts[0];
+        // The new account must not already be initialized. A pre-funded
+        // system account with no data is fine and keeps its balance.
+        if !account_infos[1].data.is_empty() || account_infos[1].owner != SYSTEM_PROGRAM_ID {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "account already in use".to_string()
+            ));
+        }
         
         // Transfer lamports
-        from_account.lamports -= lamports;
-        to_account.lamports = lamports;
+        account_infos.move_lamports(0, 1, lamports)?;
         
         // Set account properties
-        to_account.data = vec![0u8; space as usize];
+        let to_account = &mut account_infos[1];
+        to_account.resize(space as usize)?;
         to_account.owner = owner;
         to_account.executable = false;
         to_account.rent_epoch = 0;
         
-        context.consume_compute_units(1000);
+        context.charge_compute_units(context.cost_model.system_create_account)?;
         Ok(())
     }
     
     /// Assign account to a program
     fn assign_account(
-        account_infos: &mut [&mut Account],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         owner: [u8; 32],
         context: &mut ExecutionContext,
     ) -> Result<()> {
@@ -189,16 +398,27 @@ impl SystemProgram {
                 "Only system-owned accounts can be assigned".to_string()
             ));
         }
+        if !account_metas[0].is_writable {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "Assigned account must be writable".to_string()
+            ));
+        }
+        // An unfunded account handed to a program would be stranded there
+        if account.lamports == 0 && owner != SYSTEM_PROGRAM_ID {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "Cannot assign an account with no lamports".to_string()
+            ));
+        }
         
         account.owner = owner;
         
-        context.consume_compute_units(500);
+        context.charge_compute_units(context.cost_model.system_instruction)?;
         Ok(())
     }
     
     /// Transfer lamports between accounts
     fn transfer(
-        account_infos: &mut [&mut Account],
+        account_infos: &mut InstructionAccounts<'_>,
         lamports: u64,
         context: &mut ExecutionContext,
     ) -> Result<()> {
@@ -210,28 +430,24 @@ impl SystemProgram {
         
         context.log(format!("Transferring {} lamports", lamports));
         
-        // Check sufficient funds
-        if account_infos[0].lamports < lamports {
-            return Err(TerminatorError::InsufficientFunds);
+        // Once assigned away, only the new owner may debit the account
+        if account_infos[0].owner != SYSTEM_PROGRAM_ID {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "Transfer from must be owned by the system program".to_string()
+            ));
         }
         
-        // Use split_at_mut to safely get mutable references
-        let (from_accounts, to_accounts) = account_infos.split_at_mut(1);
-        let from_account = &mut from_accounts[0];
-        let to_account = &mut to_accounts[0];
-        
-        // Transfer
-        from_account.lamports -= lamports;
-        to_account.lamports += lamports;
+        // Transfer, failing on insufficient funds
+        account_infos.move_lamports(0, 1, lamports)?;
         
-        context.consume_compute_units(200);
+        context.charge_compute_units(context.cost_model.system_transfer)?;
         Ok(())
     }
     
     /// Create account with seed (simplified implementation)
     fn create_account_with_seed(
-        _account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         _base: [u8; 32],
         _seed: &str,
         lamports: u64,
@@ -240,12 +456,12 @@ impl SystemProgram {
         context: &mut ExecutionContext,
     ) -> Result<()> {
         // For now, treat like regular create account
-        Self::create_account(&[], account_infos, lamports, space, owner, context)
+        Self::create_account(account_metas, account_infos, lamports, space, owner, context)
     }
     
     /// Allocate space for account data
     fn allocate(
-        account_infos: &mut [&mut Account],
+        account_infos: &mut InstructionAccounts<'_>,
         space: u64,
         context: &mut ExecutionContext,
     ) -> Result<()> {
@@ -259,6 +475,8 @@ impl SystemProgram {
         
         context.log(format!("Allocating {} bytes", space));
         
+        Account::check_data_len(space)?;
+        
         // Only system-owned accounts can be allocated
         if account.owner != SYSTEM_PROGRAM_ID {
             return Err(TerminatorError::TransactionExecutionFailed(
@@ -266,16 +484,199 @@ impl SystemProgram {
             ));
         }
         
-        account.data = vec![0u8; space as usize];
+        account.data.clear();
+        account.resize(space as usize)?;
+        
+        context.charge_compute_units(context.cost_model.allocate(space))?;
+        Ok(())
+    }
+    
+    /// Turn an uninitialized, rent-exempt account into a nonce account
+    fn initialize_nonce_account(
+        account_infos: &mut InstructionAccounts<'_>,
+        authority: [u8; 32],
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_infos.is_empty() {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "InitializeNonceAccount requires 1 account".to_string()
+            ));
+        }
+        
+        let nonce_account = &mut account_infos[0];
+        if nonce_account.owner != SYSTEM_PROGRAM_ID || nonce_account.data.len() != NONCE_STATE_SIZE {
+            return Err(TerminatorError::ProgramError("Account is not a nonce account".to_string()));
+        }
+        if NonceState::from_account(nonce_account)? != NonceState::Uninitialized {
+            return Err(TerminatorError::ProgramError("Nonce account already initialized".to_string()));
+        }
+        if !nonce_account.is_rent_exempt(&Rent::default()) {
+            return Err(TerminatorError::InsufficientFunds);
+        }
+        
+        context.log(format!("Initializing nonce account with authority {:?}", authority));
+        
+        NonceState::Initialized(NonceData {
+            authority,
+            durable_nonce: durable_nonce(&context.blockhash),
+            lamports_per_signature: FeeCalculator::default().lamports_per_signature,
+        })
+        .store(nonce_account)?;
+        
+        context.charge_compute_units(context.cost_model.system_instruction)?;
+        Ok(())
+    }
+    
+    /// Move an initialized nonce to the current blockhash's durable nonce
+    fn advance_nonce_account(
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_infos.is_empty() {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "AdvanceNonceAccount requires 1 account".to_string()
+            ));
+        }
+        
+        let nonce_account = &mut account_infos[0];
+        let mut data = initialized_nonce(nonce_account)?;
+        require_nonce_authority(account_metas, &data)?;
+        
+        let next_nonce = durable_nonce(&context.blockhash);
+        if data.durable_nonce == next_nonce {
+            return Err(TerminatorError::ProgramError(
+                "Nonce can only advance once the blockhash has changed".to_string()
+            ));
+        }
+        
+        context.log("Advancing nonce account".to_string());
+        
+        data.durable_nonce = next_nonce;
+        NonceState::Initialized(data).store(nonce_account)?;
+        
+        context.charge_compute_units(context.cost_model.system_instruction)?;
+        Ok(())
+    }
+    
+    /// Withdraw from a nonce account
+    ///
+    /// A partial withdrawal must leave the account rent exempt. Withdrawing
+    /// the whole balance closes the account, which is only allowed once the
+    /// stored nonce is no longer the current blockhash's, so a pending
+    /// durable transaction can't be replayed against a recreated nonce.
+    fn withdraw_nonce_account(
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
+        lamports: u64,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_infos.len() < 2 {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "WithdrawNonceAccount requires 2 accounts".to_string()
+            ));
+        }
+        
+        context.log(format!("Withdrawing {} lamports from nonce account", lamports));
+        
+        let nonce_account = &mut account_infos[0];
+        
+        if lamports > nonce_account.lamports {
+            return Err(TerminatorError::InsufficientFunds);
+        }
+        let closing = lamports == nonce_account.lamports;
+        
+        match NonceState::from_account(nonce_account)? {
+            // An uninitialized nonce account is withdrawn from like a plain system account
+            NonceState::Uninitialized => {
+                let signed = account_metas.first().is_some_and(|meta| meta.is_signer);
+                if !signed {
+                    return Err(TerminatorError::ProgramError("Nonce account must sign".to_string()));
+                }
+            }
+            NonceState::Initialized(data) => {
+                require_nonce_authority(account_metas, &data)?;
+                if closing {
+                    if data.durable_nonce == durable_nonce(&context.blockhash) {
+                        return Err(TerminatorError::ProgramError(
+                            "Nonce account cannot be closed until the blockhash advances".to_string()
+                        ));
+                    }
+                    NonceState::Uninitialized.store(nonce_account)?;
+                } else {
+                    let minimum_balance = Rent::default().minimum_balance(nonce_account.data.len());
+                    if nonce_account.lamports - lamports < minimum_balance {
+                        return Err(TerminatorError::ProgramError(
+                            "Withdrawal would leave the nonce account below rent exemption".to_string()
+                        ));
+                    }
+                }
+            }
+        }
+        
+        account_infos.move_lamports(0, 1, lamports)?;
+        
+        context.charge_compute_units(context.cost_model.system_instruction)?;
+        Ok(())
+    }
+    
+    /// Hand an initialized nonce account to a new authority
+    fn authorize_nonce_account(
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
+        new_authority: [u8; 32],
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_infos.is_empty() {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "AuthorizeNonceAccount requires 1 account".to_string()
+            ));
+        }
+        
+        let nonce_account = &mut account_infos[0];
+        let mut data = initialized_nonce(nonce_account)?;
+        require_nonce_authority(account_metas, &data)?;
+        
+        context.log(format!("Authorizing {:?} for nonce account", new_authority));
+        
+        data.authority = new_authority;
+        NonceState::Initialized(data).store(nonce_account)?;
         
-        context.consume_compute_units(space / 100); // Proportional to space
+        context.charge_compute_units(context.cost_model.system_instruction)?;
+        Ok(())
+    }
+    
+    /// Rewrite a legacy nonce in the current layout; anyone may upgrade a nonce
+    fn upgrade_nonce_account(
+        account_infos: &mut InstructionAccounts<'_>,
+        context: &mut ExecutionContext,
+    ) -> Result<()> {
+        if account_infos.is_empty() {
+            return Err(TerminatorError::TransactionExecutionFailed(
+                "UpgradeNonceAccount requires 1 account".to_string()
+            ));
+        }
+        
+        let nonce_account = &mut account_infos[0];
+        if nonce_account.owner != SYSTEM_PROGRAM_ID {
+            return Err(TerminatorError::ProgramError("Account is not a nonce account".to_string()));
+        }
+        let upgraded = NonceVersions::from_account(nonce_account)?
+            .upgrade()
+            .ok_or_else(|| TerminatorError::ProgramError("Nonce account is not an initialized legacy nonce".to_string()))?;
+        
+        context.log("Upgrading nonce account".to_string());
+        
+        upgraded.store(nonce_account)?;
+        
+        context.charge_compute_units(context.cost_model.system_instruction)?;
         Ok(())
     }
     
     /// Placeholder implementations for seed-based operations
     fn allocate_with_seed(
-        _account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        _account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         _base: [u8; 32],
         _seed: &str,
         space: u64,
@@ -286,19 +687,19 @@ impl SystemProgram {
     }
     
     fn assign_with_seed(
-        _account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         _base: [u8; 32],
         _seed: &str,
         owner: [u8; 32],
         context: &mut ExecutionContext,
     ) -> Result<()> {
-        Self::assign_account(account_infos, owner, context)
+        Self::assign_account(account_metas, account_infos, owner, context)
     }
     
     fn transfer_with_seed(
-        _account_keys: &[Pubkey],
-        account_infos: &mut [&mut Account],
+        _account_metas: &[AccountMeta],
+        account_infos: &mut InstructionAccounts<'_>,
         lamports: u64,
         _from_seed: &str,
         _from_owner: [u8; 32],
@@ -308,8 +709,159 @@ impl SystemProgram {
     }
 }
 
+/// Data of a system-owned nonce account that has been initialized
+fn initialized_nonce(account: &Account) -> Result<NonceData> {
+    if account.owner != SYSTEM_PROGRAM_ID {
+        return Err(TerminatorError::ProgramError("Account is not a nonce account".to_string()));
+    }
+    match NonceState::from_account(account)? {
+        NonceState::Initialized(data) => Ok(data),
+        NonceState::Uninitialized => Err(TerminatorError::ProgramError("Nonce account is not initialized".to_string())),
+    }
+}
+
+/// Fail unless the nonce's authority is among the instruction's signers
+fn require_nonce_authority(account_metas: &[AccountMeta], data: &NonceData) -> Result<()> {
+    let signed = account_metas.iter().any(|meta| meta.is_signer && meta.pubkey.0 == data.authority);
+    if !signed {
+        return Err(TerminatorError::ProgramError("Nonce authority must sign".to_string()));
+    }
+    Ok(())
+}
+
+/// Cursor over a system instruction in Solana's bincode layout
+struct WireReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl WireReader<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8]> {
+        let bytes = self.offset.checked_add(len)
+            .and_then(|end| self.data.get(self.offset..end))
+            .ok_or_else(|| TerminatorError::SerializationError("Truncated system instruction".to_string()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let value = read_u32_le(self.data, self.offset)?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let value = read_u64_le(self.data, self.offset)?;
+        self.offset += 8;
+        Ok(value)
+    }
+
+    fn key(&mut self) -> Result<[u8; 32]> {
+        Ok(self.bytes(32)?.try_into().unwrap())
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = usize::try_from(self.u64()?)
+            .map_err(|_| TerminatorError::SerializationError("Seed length overflows usize".to_string()))?;
+        let bytes = self.bytes(len)?.to_vec();
+        String::from_utf8(bytes).map_err(|_| TerminatorError::SerializationError("Seed is not UTF-8".to_string()))
+    }
+}
+
 /// Helper functions for creating system instructions
 impl SystemInstruction {
+    /// Decode instruction data in this crate's Borsh encoding or in Solana's
+    /// wire encoding (bincode, with a u32 variant tag)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if let Ok(instruction) = Self::try_from_slice(data) {
+            return Ok(instruction);
+        }
+        Self::decode_wire(data)
+            .map_err(|_| TerminatorError::SerializationError("Invalid system instruction".to_string()))
+    }
+
+    /// Decode Solana's bincode layout: fixed-width little-endian integers,
+    /// and seeds behind a u64 length
+    fn decode_wire(data: &[u8]) -> Result<Self> {
+        let mut reader = WireReader { data, offset: 0 };
+        let instruction = match reader.u32()? {
+            0 => SystemInstruction::CreateAccount { lamports: reader.u64()?, space: reader.u64()?, owner: reader.key()? },
+            1 => SystemInstruction::Assign { owner: reader.key()? },
+            2 => SystemInstruction::Transfer { lamports: reader.u64()? },
+            3 => SystemInstruction::CreateAccountWithSeed {
+                base: reader.key()?,
+                seed: reader.string()?,
+                lamports: reader.u64()?,
+                space: reader.u64()?,
+                owner: reader.key()?,
+            },
+            4 => SystemInstruction::AdvanceNonceAccount,
+            5 => SystemInstruction::WithdrawNonceAccount { lamports: reader.u64()? },
+            6 => SystemInstruction::InitializeNonceAccount { authority: reader.key()? },
+            7 => SystemInstruction::AuthorizeNonceAccount { new_authority: reader.key()? },
+            8 => SystemInstruction::Allocate { space: reader.u64()? },
+            9 => SystemInstruction::AllocateWithSeed {
+                base: reader.key()?,
+                seed: reader.string()?,
+                space: reader.u64()?,
+                owner: reader.key()?,
+            },
+            10 => SystemInstruction::AssignWithSeed { base: reader.key()?, seed: reader.string()?, owner: reader.key()? },
+            11 => SystemInstruction::TransferWithSeed {
+                lamports: reader.u64()?,
+                from_seed: reader.string()?,
+                from_owner: reader.key()?,
+            },
+            12 => SystemInstruction::UpgradeNonceAccount,
+            tag => return Err(TerminatorError::SerializationError(format!("Unknown system instruction tag {}", tag))),
+        };
+        if reader.offset != data.len() {
+            return Err(TerminatorError::SerializationError("Trailing bytes after system instruction".to_string()));
+        }
+        Ok(instruction)
+    }
+    
+    /// Human-readable summary of what the instruction does
+    pub fn describe(&self) -> String {
+        let sol = |lamports: u64| format!("{} SOL", format_amount(lamports, SOL_DECIMALS));
+        let key = |bytes: &[u8; 32]| SolanaPubkey::new(*bytes).to_string();
+        match self {
+            SystemInstruction::CreateAccount { lamports, space, owner } => {
+                format!("Create account with {} bytes owned by {}, funded with {}", space, key(owner), sol(*lamports))
+            }
+            SystemInstruction::Assign { owner } => format!("Assign account to {}", key(owner)),
+            SystemInstruction::Transfer { lamports } => format!("Transfer {}", sol(*lamports)),
+            SystemInstruction::CreateAccountWithSeed { seed, lamports, space, owner, .. } => format!(
+                "Create account from seed {:?} with {} bytes owned by {}, funded with {}",
+                seed,
+                space,
+                key(owner),
+                sol(*lamports)
+            ),
+            SystemInstruction::AdvanceNonceAccount => "Advance nonce account".to_string(),
+            SystemInstruction::WithdrawNonceAccount { lamports } => {
+                format!("Withdraw {} from nonce account", sol(*lamports))
+            }
+            SystemInstruction::InitializeNonceAccount { authority } => {
+                format!("Initialize nonce account with authority {}", key(authority))
+            }
+            SystemInstruction::AuthorizeNonceAccount { new_authority } => {
+                format!("Set nonce account authority to {}", key(new_authority))
+            }
+            SystemInstruction::Allocate { space } => format!("Allocate {} bytes", space),
+            SystemInstruction::AllocateWithSeed { seed, space, owner, .. } => {
+                format!("Allocate {} bytes from seed {:?} owned by {}", space, seed, key(owner))
+            }
+            SystemInstruction::AssignWithSeed { seed, owner, .. } => {
+                format!("Assign account from seed {:?} to {}", seed, key(owner))
+            }
+            SystemInstruction::TransferWithSeed { lamports, from_seed, .. } => {
+                format!("Transfer {} from seed {:?}", sol(*lamports), from_seed)
+            }
+            SystemInstruction::UpgradeNonceAccount => "Upgrade nonce account".to_string(),
+        }
+    }
+    
     /// Create a transfer instruction
     pub fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> (Self, Vec<Pubkey>) {
         let instruction = SystemInstruction::Transfer { lamports };
@@ -340,6 +892,29 @@ impl SystemInstruction {
         let accounts = vec![*account];
         (instruction, accounts)
     }
+    
+    /// Create an allocate instruction
+    pub fn allocate(account: &Pubkey, space: u64) -> (Self, Vec<Pubkey>) {
+        let instruction = SystemInstruction::Allocate { space };
+        let accounts = vec![*account];
+        (instruction, accounts)
+    }
+    
+    /// Fund, allocate and assign `to` as three instructions, the equivalent
+    /// of `create_account` for an address that may already hold lamports
+    pub fn create_account_allocate_assign(
+        from: &Pubkey,
+        to: &Pubkey,
+        lamports: u64,
+        space: u64,
+        owner: &[u8; 32],
+    ) -> Vec<(Self, Vec<Pubkey>)> {
+        vec![
+            Self::transfer(from, to, lamports),
+            Self::allocate(to, space),
+            Self::assign(to, owner),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -358,6 +933,264 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_decode_accepts_solana_wire_format() {
+        let wire = [2, 0, 0, 0, 0x68, 0x0a, 0x1f, 0, 0, 0, 0, 0];
+        assert_eq!(SystemInstruction::decode(&wire).unwrap(), SystemInstruction::Transfer { lamports: 2_034_280 });
+        
+        let borsh_data = borsh::to_vec(&SystemInstruction::Transfer { lamports: 7 }).unwrap();
+        assert_eq!(SystemInstruction::decode(&borsh_data).unwrap(), SystemInstruction::Transfer { lamports: 7 });
+        assert!(SystemInstruction::decode(&[2, 0, 0]).is_err());
+    }
+    
+    #[test]
+    fn test_system_instruction_round_trip() {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+        
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let seeds = ["", "seed", "ünïcødé", "種子🌱", &"x".repeat(32)];
+        
+        for i in 0..500 {
+            let seed = seeds[i % seeds.len()].to_string();
+            let variants = vec![
+                SystemInstruction::CreateAccount { lamports: rng.gen(), space: rng.gen(), owner: rng.gen() },
+                SystemInstruction::Assign { owner: rng.gen() },
+                SystemInstruction::Transfer { lamports: rng.gen() },
+                SystemInstruction::CreateAccountWithSeed {
+                    base: rng.gen(),
+                    seed: seed.clone(),
+                    lamports: rng.gen(),
+                    space: rng.gen(),
+                    owner: rng.gen(),
+                },
+                SystemInstruction::AdvanceNonceAccount,
+                SystemInstruction::WithdrawNonceAccount { lamports: rng.gen() },
+                SystemInstruction::InitializeNonceAccount { authority: rng.gen() },
+                SystemInstruction::AuthorizeNonceAccount { new_authority: rng.gen() },
+                SystemInstruction::Allocate { space: rng.gen() },
+                SystemInstruction::AllocateWithSeed { base: rng.gen(), seed: seed.clone(), space: rng.gen(), owner: rng.gen() },
+                SystemInstruction::AssignWithSeed { base: rng.gen(), seed: seed.clone(), owner: rng.gen() },
+                SystemInstruction::TransferWithSeed { lamports: rng.gen(), from_seed: seed, from_owner: rng.gen() },
+                SystemInstruction::UpgradeNonceAccount,
+            ];
+            
+            for instruction in variants {
+                let serialized = borsh::to_vec(&instruction).unwrap();
+                let deserialized: SystemInstruction = borsh::from_slice(&serialized).unwrap();
+                assert_eq!(instruction, deserialized);
+                
+                let serialized = bincode::serialize(&instruction).unwrap();
+                let deserialized: SystemInstruction = bincode::deserialize(&serialized).unwrap();
+                assert_eq!(instruction, deserialized);
+                assert_eq!(SystemInstruction::decode_wire(&serialized).unwrap(), instruction);
+                assert!(SystemInstruction::decode_wire(&serialized[..serialized.len() - 1]).is_err());
+            }
+        }
+    }
+    
+    fn signer_meta(key: [u8; 32]) -> AccountMeta {
+        AccountMeta { pubkey: Pubkey::new(key), is_signer: true, is_writable: true }
+    }
+    
+    fn run_create_account(from: &mut Account, to: &mut Account, lamports: u64, space: u64) -> Result<()> {
+        let data = borsh::to_vec(&SystemInstruction::CreateAccount { lamports, space, owner: [9u8; 32] }).unwrap();
+        let metas = [signer_meta([1u8; 32]), signer_meta([2u8; 32])];
+        let mut context = ExecutionContext::new(1_400_000);
+        SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![from, to]), &mut context)
+    }
+    
+    #[test]
+    fn test_create_account_already_in_use() {
+        let mut from = Account::new(10_000, vec![], SYSTEM_PROGRAM_ID);
+        let mut to = Account::new(500, vec![1, 2, 3], [9u8; 32]);
+        
+        let result = run_create_account(&mut from, &mut to, 1_000, 16);
+        assert!(matches!(
+            result,
+            Err(TerminatorError::TransactionExecutionFailed(ref msg)) if msg == "account already in use"
+        ));
+        assert_eq!(from.lamports, 10_000);
+        assert_eq!(to.lamports, 500);
+        assert_eq!(to.data, vec![1, 2, 3]);
+    }
+    
+    #[test]
+    fn test_create_account_adds_to_prefunded_balance() {
+        let mut from = Account::new(10_000, vec![], SYSTEM_PROGRAM_ID);
+        let mut to = Account::new(250, vec![], SYSTEM_PROGRAM_ID);
+        
+        run_create_account(&mut from, &mut to, 1_000, 16).unwrap();
+        assert_eq!(from.lamports, 9_000);
+        assert_eq!(to.lamports, 1_250);
+        assert_eq!(to.data.len(), 16);
+        assert_eq!(to.owner, [9u8; 32]);
+    }
+    
+    fn run_allocate(account: &mut Account, space: u64) -> Result<()> {
+        let data = borsh::to_vec(&SystemInstruction::Allocate { space }).unwrap();
+        let metas = [signer_meta([1u8; 32])];
+        let mut context = ExecutionContext::new(1_400_000);
+        SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![account]), &mut context)
+    }
+    
+    fn is_size_error(result: &Result<()>) -> bool {
+        matches!(
+            result,
+            Err(TerminatorError::TransactionExecutionFailed(msg)) if msg == "requested data size exceeds maximum"
+        )
+    }
+    
+    #[test]
+    fn test_allocate_data_size_limit() {
+        let mut account = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        run_allocate(&mut account, MAX_PERMITTED_DATA_LENGTH).unwrap();
+        assert_eq!(account.data.len() as u64, MAX_PERMITTED_DATA_LENGTH);
+        
+        let mut account = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        assert!(is_size_error(&run_allocate(&mut account, MAX_PERMITTED_DATA_LENGTH + 1)));
+        assert!(is_size_error(&run_allocate(&mut account, u64::MAX)));
+        assert!(account.data.is_empty());
+    }
+    
+    #[test]
+    fn test_create_account_data_size_limit() {
+        let mut from = Account::new(10_000, vec![], SYSTEM_PROGRAM_ID);
+        let mut to = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        assert!(is_size_error(&run_create_account(&mut from, &mut to, 1_000, u64::MAX)));
+        assert!(is_size_error(&run_create_account(&mut from, &mut to, 1_000, MAX_PERMITTED_DATA_LENGTH + 1)));
+        assert_eq!(from.lamports, 10_000);
+        
+        run_create_account(&mut from, &mut to, 1_000, MAX_PERMITTED_DATA_LENGTH).unwrap();
+        assert_eq!(to.data.len() as u64, MAX_PERMITTED_DATA_LENGTH);
+    }
+    
+    #[test]
+    fn test_assigned_account_cannot_transfer() {
+        let token_program = Pubkey::token_program().0;
+        let assign = borsh::to_vec(&SystemInstruction::Assign { owner: token_program }).unwrap();
+        let mut context = ExecutionContext::new(1_400_000);
+        
+        let mut unfunded = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        assert!(SystemProgram::process_instruction(&assign, &[signer_meta([1u8; 32])], &mut InstructionAccounts::new(vec![&mut unfunded]), &mut context).is_err());
+        assert_eq!(unfunded.owner, SYSTEM_PROGRAM_ID);
+        
+        let mut account = Account::new(10_000, vec![], SYSTEM_PROGRAM_ID);
+        let mut to = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        SystemProgram::process_instruction(&assign, &[signer_meta([1u8; 32])], &mut InstructionAccounts::new(vec![&mut account]), &mut context).unwrap();
+        assert_eq!(account.owner, token_program);
+        
+        let transfer = borsh::to_vec(&SystemInstruction::Transfer { lamports: 1_000 }).unwrap();
+        let metas = [signer_meta([1u8; 32]), signer_meta([2u8; 32])];
+        assert!(SystemProgram::process_instruction(&transfer, &metas, &mut InstructionAccounts::new(vec![&mut account, &mut to]), &mut context).is_err());
+        assert_eq!((account.lamports, to.lamports), (10_000, 0));
+    }
+    
+    const NONCE_AUTHORITY: [u8; 32] = [7u8; 32];
+    
+    /// Nonce account initialized at blockhash [1; 32] holding `lamports`
+    fn nonce_account(lamports: u64) -> Account {
+        let mut account = Account::new(lamports, vec![0u8; NONCE_STATE_SIZE], SYSTEM_PROGRAM_ID);
+        let data = borsh::to_vec(&SystemInstruction::InitializeNonceAccount { authority: NONCE_AUTHORITY }).unwrap();
+        let mut context = ExecutionContext::new(1_400_000).with_blockhash([1u8; 32]);
+        let metas = [AccountMeta { pubkey: Pubkey::new([5u8; 32]), is_signer: false, is_writable: true }];
+        SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![&mut account]), &mut context).unwrap();
+        account
+    }
+    
+    fn run_withdraw_nonce(
+        nonce: &mut Account,
+        to: &mut Account,
+        lamports: u64,
+        authority_signs: bool,
+        blockhash: [u8; 32],
+    ) -> Result<()> {
+        let data = borsh::to_vec(&SystemInstruction::WithdrawNonceAccount { lamports }).unwrap();
+        let metas = [
+            AccountMeta { pubkey: Pubkey::new([5u8; 32]), is_signer: false, is_writable: true },
+            AccountMeta { pubkey: Pubkey::new([6u8; 32]), is_signer: false, is_writable: true },
+            AccountMeta { pubkey: Pubkey::new(NONCE_AUTHORITY), is_signer: authority_signs, is_writable: false },
+        ];
+        let mut authority = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        let mut context = ExecutionContext::new(1_400_000).with_blockhash(blockhash);
+        SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![nonce, to, &mut authority]), &mut context)
+    }
+    
+    fn program_error(result: &Result<()>) -> &str {
+        match result {
+            Err(TerminatorError::ProgramError(msg)) => msg,
+            other => panic!("expected a program error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_withdraw_nonce_full_drain_closes_account() {
+        let balance = Rent::default().minimum_balance(NONCE_STATE_SIZE) + 1_000;
+        let mut nonce = nonce_account(balance);
+        let mut to = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        
+        // Still the current blockhash's nonce, so closing is refused
+        let result = run_withdraw_nonce(&mut nonce, &mut to, balance, true, [1u8; 32]);
+        assert_eq!(program_error(&result), "Nonce account cannot be closed until the blockhash advances");
+        
+        run_withdraw_nonce(&mut nonce, &mut to, balance, true, [2u8; 32]).unwrap();
+        assert_eq!(nonce.lamports, 0);
+        assert_eq!(to.lamports, balance);
+        assert_eq!(NonceState::from_account(&nonce).unwrap(), NonceState::Uninitialized);
+    }
+    
+    #[test]
+    fn test_withdraw_nonce_partial_keeps_rent_exemption() {
+        let minimum_balance = Rent::default().minimum_balance(NONCE_STATE_SIZE);
+        let mut nonce = nonce_account(minimum_balance + 1_000);
+        let mut to = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        
+        let result = run_withdraw_nonce(&mut nonce, &mut to, 1_001, true, [2u8; 32]);
+        assert_eq!(program_error(&result), "Withdrawal would leave the nonce account below rent exemption");
+        assert_eq!(nonce.lamports, minimum_balance + 1_000);
+        
+        run_withdraw_nonce(&mut nonce, &mut to, 1_000, true, [2u8; 32]).unwrap();
+        assert_eq!(nonce.lamports, minimum_balance);
+        assert_eq!(to.lamports, 1_000);
+    }
+    
+    #[test]
+    fn test_withdraw_nonce_requires_authority_signature() {
+        let mut nonce = nonce_account(Rent::default().minimum_balance(NONCE_STATE_SIZE) + 1_000);
+        let mut to = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
+        
+        let result = run_withdraw_nonce(&mut nonce, &mut to, 500, false, [2u8; 32]);
+        assert_eq!(program_error(&result), "Nonce authority must sign");
+        assert_eq!(to.lamports, 0);
+    }
+    
+    #[test]
+    fn test_upgrade_legacy_nonce_account() {
+        let blockhash = [3u8; 32];
+        let mut legacy = Vec::new();
+        legacy.extend_from_slice(&0u32.to_le_bytes());
+        legacy.extend_from_slice(&1u32.to_le_bytes());
+        legacy.extend_from_slice(&NONCE_AUTHORITY);
+        legacy.extend_from_slice(&blockhash);
+        legacy.extend_from_slice(&5_000u64.to_le_bytes());
+        assert_eq!(legacy.len(), NONCE_STATE_SIZE);
+        let mut nonce = Account::new(1_000_000, legacy.clone(), SYSTEM_PROGRAM_ID);
+        
+        let data = borsh::to_vec(&SystemInstruction::UpgradeNonceAccount).unwrap();
+        let metas = [AccountMeta { pubkey: Pubkey::new([5u8; 32]), is_signer: false, is_writable: true }];
+        let mut context = ExecutionContext::new(1_400_000);
+        SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![&mut nonce]), &mut context).unwrap();
+        
+        let mut expected = legacy;
+        expected[..4].copy_from_slice(&1u32.to_le_bytes());
+        expected[40..72].copy_from_slice(&durable_nonce(&blockhash));
+        assert_eq!(nonce.data, expected);
+        assert!(matches!(NonceVersions::from_account(&nonce).unwrap(), NonceVersions::Current(NonceState::Initialized(_))));
+        
+        let result = SystemProgram::process_instruction(&data, &metas, &mut InstructionAccounts::new(vec![&mut nonce]), &mut context);
+        assert_eq!(program_error(&result), "Nonce account is not an initialized legacy nonce");
+    }
+    
     #[test]
     fn test_create_transfer_instruction() {
         let from = Pubkey::new([1u8; 32]);
@@ -0,0 +1,57 @@
+/// Test Fixtures
+/// Reproducible ed25519 keypairs for tests and examples that need real signatures
+
+use crate::crypto::SolanaCrypto;
+use crate::solana_format::SolanaPubkey;
+use ed25519_dalek::SigningKey;
+
+/// An ed25519 signing key, as used by `TransactionBuilder`
+pub type Keypair = SigningKey;
+
+/// Domain separator hashed with the index to form each keypair's seed
+const KEYPAIR_SEED_DOMAIN: &[u8] = b"terminator-dancer test keypair";
+
+/// Keypair `index` of a fixed set, identical on every run and machine
+pub fn deterministic_keypair(index: u8) -> Keypair {
+    let mut seed_input = KEYPAIR_SEED_DOMAIN.to_vec();
+    seed_input.push(index);
+    SigningKey::from_bytes(&SolanaCrypto::sha256_hash(&seed_input))
+}
+
+/// Address of `keypair`
+pub fn pubkey(keypair: &Keypair) -> SolanaPubkey {
+    SolanaPubkey::new(keypair.verifying_key().to_bytes())
+}
+
+/// Conventional fee payer, keypair 0
+pub fn payer() -> Keypair {
+    deterministic_keypair(0)
+}
+
+/// Keypair 1
+pub fn alice() -> Keypair {
+    deterministic_keypair(1)
+}
+
+/// Keypair 2
+pub fn bob() -> Keypair {
+    deterministic_keypair(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Signer;
+
+    #[test]
+    fn test_deterministic_keypair_is_stable_and_signs() {
+        let keypair = deterministic_keypair(0);
+        assert_eq!(pubkey(&keypair).to_string(), "8nr7ptPU6TXuXyf6JBdU5Rh3qBPDPHyFr55qvNJLZxkR");
+        assert_eq!(pubkey(&keypair), pubkey(&payer()));
+        assert_ne!(pubkey(&alice()), pubkey(&bob()));
+
+        let message = b"reproducible";
+        let signature = keypair.sign(message).to_bytes();
+        assert!(SolanaCrypto::verify_ed25519_signature(&signature, message, &pubkey(&keypair).0).unwrap());
+    }
+}
@@ -0,0 +1,407 @@
+/// Transaction Builder
+/// Compiles high-level instructions into a Solana message and signs it, in one step or several
+
+use crate::{Result, TerminatorError};
+use crate::system_program::SystemInstruction;
+use crate::types::Instruction;
+use crate::solana_format::{
+    account_metas, CompiledInstruction, MessageHeader, SolanaHash, SolanaMessage, SolanaPubkey, SolanaSignature,
+    SolanaTransaction, SolanaTransactionParser,
+};
+use ed25519_dalek::{Signer, SigningKey};
+
+/// An account referenced by an instruction, with the privileges it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolanaAccountMeta {
+    pub pubkey: SolanaPubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl SolanaAccountMeta {
+    /// Writable account
+    pub fn new(pubkey: SolanaPubkey, is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: true }
+    }
+
+    /// Readonly account
+    pub fn new_readonly(pubkey: SolanaPubkey, is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: false }
+    }
+}
+
+/// An instruction before its accounts are compiled to indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolanaInstruction {
+    pub program_id: SolanaPubkey,
+    pub accounts: Vec<SolanaAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl SolanaInstruction {
+    pub fn new(program_id: SolanaPubkey, accounts: Vec<SolanaAccountMeta>, data: Vec<u8>) -> Self {
+        Self { program_id, accounts, data }
+    }
+}
+
+impl SolanaMessage {
+    /// Resolve a compiled instruction's indices back to keys, with the
+    /// privileges this message's header grants each account
+    pub fn decompile_instruction(&self, instruction: &CompiledInstruction) -> Result<SolanaInstruction> {
+        let program_id = *self.account_keys.get(instruction.program_id_index as usize)
+            .ok_or_else(|| TerminatorError::TransactionExecutionFailed("Invalid program id index".to_string()))?;
+        let accounts = account_metas(&instruction.accounts, &self.header, &self.account_keys)?
+            .into_iter()
+            .map(|meta| SolanaAccountMeta {
+                pubkey: meta.pubkey.into(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect();
+        Ok(SolanaInstruction::new(program_id, accounts, instruction.data.clone()))
+    }
+
+    /// Decompile every instruction, in order
+    pub fn decompile_all(&self) -> Result<Vec<SolanaInstruction>> {
+        self.instructions.iter().map(|instruction| self.decompile_instruction(instruction)).collect()
+    }
+}
+
+impl From<&Instruction> for SolanaInstruction {
+    /// Compile an internal instruction, encoding its data with `InstructionData::to_bytes`
+    fn from(instruction: &Instruction) -> Self {
+        let accounts = instruction.accounts.iter()
+            .map(|meta| SolanaAccountMeta {
+                pubkey: meta.pubkey.into(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect();
+        SolanaInstruction::new(instruction.program_id.into(), accounts, instruction.data.to_bytes())
+    }
+}
+
+/// System program instruction in this crate's Borsh encoding
+fn system_instruction(instruction: &SystemInstruction, accounts: Vec<SolanaAccountMeta>) -> SolanaInstruction {
+    let data = borsh::to_vec(instruction).expect("System instructions always serialize");
+    SolanaInstruction::new(SolanaPubkey::system_program(), accounts, data)
+}
+
+/// Builds transactions from high-level instructions
+pub struct TransactionBuilder {
+    fee_payer: SolanaPubkey,
+    recent_blockhash: SolanaHash,
+    instructions: Vec<SolanaInstruction>,
+}
+
+impl TransactionBuilder {
+    /// Start a transaction paid for by `fee_payer`
+    pub fn new(fee_payer: SolanaPubkey, recent_blockhash: SolanaHash) -> Self {
+        Self {
+            fee_payer,
+            recent_blockhash,
+            instructions: Vec::new(),
+        }
+    }
+
+    pub fn add_instruction(mut self, instruction: SolanaInstruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Create `new_account` owned by `owner` with one CreateAccount, which
+    /// both `from` and `new_account` sign
+    pub fn add_create_account(
+        self,
+        from: SolanaPubkey,
+        new_account: SolanaPubkey,
+        lamports: u64,
+        space: u64,
+        owner: [u8; 32],
+    ) -> Self {
+        let (instruction, _) = SystemInstruction::create_account(
+            &from.into(), &new_account.into(), lamports, space, &owner,
+        );
+        self.add_instruction(system_instruction(
+            &instruction,
+            vec![SolanaAccountMeta::new(from, true), SolanaAccountMeta::new(new_account, true)],
+        ))
+    }
+
+    /// Fund `account` from `from`, then allocate and assign it, for an
+    /// address such as a PDA that has no key to sign with; only `from` signs
+    pub fn add_fund_allocate_assign(
+        mut self,
+        from: SolanaPubkey,
+        account: SolanaPubkey,
+        lamports: u64,
+        space: u64,
+        owner: [u8; 32],
+    ) -> Self {
+        let steps = SystemInstruction::create_account_allocate_assign(
+            &from.into(), &account.into(), lamports, space, &owner,
+        );
+        for (instruction, keys) in steps {
+            let accounts = keys.iter()
+                .map(|key| SolanaAccountMeta::new((*key).into(), key.0 == from.0))
+                .collect();
+            self = self.add_instruction(system_instruction(&instruction, accounts));
+        }
+        self
+    }
+
+    /// Compile the instructions into a message
+    ///
+    /// Each key takes the highest privilege any instruction asks of it, then
+    /// keys are ordered writable signers, readonly signers, writable
+    /// non-signers, readonly non-signers, with the fee payer first. Program
+    /// ids are readonly non-signers unless used with more privilege elsewhere.
+    pub fn compile_message(&self) -> SolanaMessage {
+        // (key, is_signer, is_writable) in first-use order
+        let mut keys: Vec<(SolanaPubkey, bool, bool)> = vec![(self.fee_payer, true, true)];
+        let mut merge = |pubkey: SolanaPubkey, is_signer: bool, is_writable: bool| {
+            match keys.iter_mut().find(|(key, _, _)| *key == pubkey) {
+                Some(entry) => {
+                    entry.1 |= is_signer;
+                    entry.2 |= is_writable;
+                }
+                None => keys.push((pubkey, is_signer, is_writable)),
+            }
+        };
+        for instruction in &self.instructions {
+            merge(instruction.program_id, false, false);
+            for meta in &instruction.accounts {
+                merge(meta.pubkey, meta.is_signer, meta.is_writable);
+            }
+        }
+
+        let bucket = |signer: bool, writable: bool| -> Vec<SolanaPubkey> {
+            keys.iter()
+                .filter(|(_, is_signer, is_writable)| *is_signer == signer && *is_writable == writable)
+                .map(|(key, _, _)| *key)
+                .collect()
+        };
+        let writable_signers = bucket(true, true);
+        let readonly_signers = bucket(true, false);
+        let writable_non_signers = bucket(false, true);
+        let readonly_non_signers = bucket(false, false);
+
+        let header = MessageHeader {
+            num_required_signatures: (writable_signers.len() + readonly_signers.len()) as u8,
+            num_readonly_signed_accounts: readonly_signers.len() as u8,
+            num_readonly_unsigned_accounts: readonly_non_signers.len() as u8,
+        };
+        let account_keys: Vec<SolanaPubkey> = writable_signers.into_iter()
+            .chain(readonly_signers)
+            .chain(writable_non_signers)
+            .chain(readonly_non_signers)
+            .collect();
+        let index_of = |key: &SolanaPubkey| account_keys.iter().position(|k| k == key).unwrap() as u8;
+
+        let instructions = self.instructions.iter()
+            .map(|instruction| CompiledInstruction {
+                program_id_index: index_of(&instruction.program_id),
+                accounts: instruction.accounts.iter().map(|meta| index_of(&meta.pubkey)).collect(),
+                data: instruction.data.clone(),
+            })
+            .collect();
+
+        SolanaMessage {
+            header,
+            account_keys,
+            recent_blockhash: self.recent_blockhash.clone(),
+            instructions,
+        }
+    }
+
+    /// Build the transaction with every signature slot zeroed
+    pub fn build_unsigned(&self) -> SolanaTransaction {
+        let message = self.compile_message();
+        SolanaTransaction {
+            signatures: vec![SolanaSignature([0u8; 64]); message.header.num_required_signatures as usize],
+            message,
+        }
+    }
+
+    /// Build the transaction and sign it with `keypairs`
+    ///
+    /// Signatures land at each signer's index in `account_keys`; slots for
+    /// signers not provided stay zeroed so they can be filled in later with
+    /// `sign`. Providing a keypair that isn't a required signer, or building
+    /// a transaction that fails `validate_transaction_format`, is an error.
+    pub fn build_partially_signed(&self, keypairs: &[SigningKey]) -> Result<SolanaTransaction> {
+        let mut tx = self.build_unsigned();
+        SolanaTransactionParser::validate_transaction_format(&tx)?;
+        for keypair in keypairs {
+            Self::sign(&mut tx, keypair)?;
+        }
+        Ok(tx)
+    }
+
+    /// Fill in `keypair`'s signature slot on an already-built transaction
+    pub fn sign(tx: &mut SolanaTransaction, keypair: &SigningKey) -> Result<()> {
+        let pubkey = SolanaPubkey::new(keypair.verifying_key().to_bytes());
+        let num_signers = tx.message.header.num_required_signatures as usize;
+        let slot = tx.message.account_keys.iter()
+            .take(num_signers)
+            .position(|key| *key == pubkey)
+            .ok_or_else(|| TerminatorError::TransactionExecutionFailed(
+                format!("{} is not a required signer", pubkey)
+            ))?;
+
+        if tx.signatures.len() != num_signers {
+            tx.signatures.resize(num_signers, SolanaSignature([0u8; 64]));
+        }
+        let message_data = SolanaTransactionParser::message_data(&tx.message)?;
+        tx.signatures[slot] = SolanaSignature(keypair.sign(&message_data).to_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrated_runtime::IntegratedRuntime;
+    use crate::types::{Pubkey, RuntimeConfig};
+
+    fn transfer(from: SolanaPubkey, to: SolanaPubkey, lamports: u64) -> SolanaInstruction {
+        let mut data = vec![2u8]; // SystemInstruction::Transfer
+        data.extend_from_slice(&lamports.to_le_bytes());
+        SolanaInstruction::new(
+            SolanaPubkey::system_program(),
+            vec![SolanaAccountMeta::new(from, true), SolanaAccountMeta::new(to, false)],
+            data,
+        )
+    }
+
+    #[test]
+    fn test_two_party_signing() {
+        let alice = SigningKey::from_bytes(&[11u8; 32]);
+        let bob = SigningKey::from_bytes(&[22u8; 32]);
+        let alice_key = SolanaPubkey::new(alice.verifying_key().to_bytes());
+        let bob_key = SolanaPubkey::new(bob.verifying_key().to_bytes());
+        let carol_key = SolanaPubkey::new([33u8; 32]);
+
+        let mut config = RuntimeConfig::default();
+        config.runtime.require_valid_signatures = true;
+        let mut runtime = IntegratedRuntime::with_config(&config).unwrap();
+
+        // Alice pays the fee and Bob moves funds, so both must sign
+        let builder = TransactionBuilder::new(alice_key, SolanaHash(runtime.blockhash()))
+            .add_instruction(transfer(bob_key, carol_key, 1_000));
+
+        let mut tx = builder.build_partially_signed(std::slice::from_ref(&alice)).unwrap();
+        assert_eq!(tx.message.header.num_required_signatures, 2);
+        assert_ne!(tx.signatures[0].0, [0u8; 64]);
+        assert_eq!(tx.signatures[1].0, [0u8; 64]);
+
+        TransactionBuilder::sign(&mut tx, &bob).unwrap();
+
+        runtime.fund_account(&alice_key.into(), 1_000_000);
+        runtime.fund_account(&bob_key.into(), 1_000_000);
+        runtime.execute_solana_transaction_parsed(&tx).unwrap();
+        assert_eq!(runtime.get_balance(&carol_key.into()), 1_000);
+    }
+
+    #[test]
+    fn test_account_ordering_and_header() {
+        let payer = SolanaPubkey::new([1u8; 32]);
+        let recipient = SolanaPubkey::new([2u8; 32]);
+        let authority = SolanaPubkey::new([3u8; 32]);
+        let program = SolanaPubkey::new([4u8; 32]);
+
+        let message = TransactionBuilder::new(payer, SolanaHash([0u8; 32]))
+            .add_instruction(SolanaInstruction::new(
+                program,
+                vec![
+                    SolanaAccountMeta::new_readonly(program, false),
+                    SolanaAccountMeta::new_readonly(recipient, false),
+                    SolanaAccountMeta::new_readonly(authority, true),
+                    SolanaAccountMeta::new_readonly(payer, false),
+                ],
+                vec![],
+            ))
+            // A later instruction upgrades the recipient to writable
+            .add_instruction(transfer(payer, recipient, 10))
+            .compile_message();
+
+        assert_eq!(
+            message.account_keys,
+            vec![payer, authority, recipient, program, SolanaPubkey::system_program()]
+        );
+        assert_eq!(message.header.num_required_signatures, 2);
+        assert_eq!(message.header.num_readonly_signed_accounts, 1);
+        assert_eq!(message.header.num_readonly_unsigned_accounts, 2);
+        assert_eq!(message.instructions[0].program_id_index, 3);
+        assert_eq!(message.instructions[0].accounts, vec![3, 2, 1, 0]);
+        assert_eq!(message.instructions[1].accounts, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_decompile_transfer() {
+        let payer = SolanaPubkey::new([1u8; 32]);
+        let recipient = SolanaPubkey::new([2u8; 32]);
+        let instruction = transfer(payer, recipient, 500);
+        let message = TransactionBuilder::new(payer, SolanaHash([0u8; 32]))
+            .add_instruction(instruction.clone())
+            .compile_message();
+
+        let decompiled = message.decompile_all().unwrap();
+        assert_eq!(decompiled, vec![instruction]);
+        assert_eq!(decompiled[0].program_id, SolanaPubkey::system_program());
+        assert_eq!(
+            decompiled[0].accounts,
+            vec![SolanaAccountMeta::new(payer, true), SolanaAccountMeta::new(recipient, false)]
+        );
+
+        let mut bad = message.instructions[0].clone();
+        bad.program_id_index = 9;
+        assert!(message.decompile_instruction(&bad).is_err());
+    }
+
+    #[test]
+    fn test_create_account_and_fund_allocate_assign() {
+        let payer = crate::testing::payer();
+        let new_account = crate::testing::alice();
+        let payer_key = crate::testing::pubkey(&payer);
+        let new_key = crate::testing::pubkey(&new_account);
+        let pda = SolanaPubkey::new([70u8; 32]);
+        let owner = [9u8; 32];
+
+        let mut config = RuntimeConfig::default();
+        config.runtime.require_valid_signatures = true;
+        let mut runtime = IntegratedRuntime::with_config(&config).unwrap();
+        runtime.fund_account(&payer_key.into(), 1_000_000_000);
+
+        let tx = TransactionBuilder::new(payer_key, SolanaHash(runtime.blockhash()))
+            .add_create_account(payer_key, new_key, 2_000_000, 64, owner)
+            .build_partially_signed(&[payer.clone(), new_account])
+            .unwrap();
+        assert_eq!(tx.message.header.num_required_signatures, 2);
+        runtime.execute_solana_transaction_parsed(&tx).unwrap();
+        let created = runtime.get_account(&new_key.into()).unwrap();
+        assert_eq!((created.owner, created.data.len()), (owner, 64));
+
+        let tx = TransactionBuilder::new(payer_key, SolanaHash(runtime.blockhash()))
+            .add_fund_allocate_assign(payer_key, pda, 2_000_000, 128, owner)
+            .build_partially_signed(&[payer])
+            .unwrap();
+        assert_eq!(tx.message.header.num_required_signatures, 1);
+        assert_eq!(tx.message.instructions.len(), 3);
+        runtime.execute_solana_transaction_parsed(&tx).unwrap();
+        let allocated = runtime.get_account(&pda.into()).unwrap();
+        assert_eq!((allocated.owner, allocated.data.len(), allocated.lamports), (owner, 128, 2_000_000));
+    }
+
+    #[test]
+    fn test_sign_rejects_non_signer() {
+        let payer = SigningKey::from_bytes(&[11u8; 32]);
+        let stranger = SigningKey::from_bytes(&[99u8; 32]);
+        let payer_key = SolanaPubkey::new(payer.verifying_key().to_bytes());
+
+        let builder = TransactionBuilder::new(payer_key, SolanaHash([5u8; 32]))
+            .add_instruction(transfer(payer_key, SolanaPubkey::new([2u8; 32]), 1));
+
+        assert!(builder.build_partially_signed(&[payer, stranger]).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+use crate::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
+use crate::solana_format::CompiledInstruction;
+use crate::system_program::SystemInstruction;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -10,15 +14,28 @@ impl Pubkey {
         Self(bytes)
     }
     
+    /// Fresh pubkey, distinct from every other `new_unique` key in this process
+    ///
+    /// A process-wide counter guarantees uniqueness; the timestamp only keeps
+    /// keys from repeating across runs.
+    #[cfg(feature = "std")]
     pub fn new_unique() -> Self {
+        use sha2::{Digest, Sha256};
+        use std::sync::atomic::{AtomicU64, Ordering};
         use std::time::{SystemTime, UNIX_EPOCH};
+
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
         let nanos = SystemTime::now()
             .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let mut bytes = [0u8; 32];
-        bytes[0..16].copy_from_slice(&nanos.to_le_bytes());
-        Self(bytes)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        let mut hasher = Sha256::new();
+        hasher.update(count.to_le_bytes());
+        hasher.update(nanos.to_le_bytes());
+        Self(hasher.finalize().into())
     }
 
     // Common Solana program IDs
@@ -32,9 +49,38 @@ impl Pubkey {
             28, 180, 133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
         ])
     }
+
+    /// Parse from base58 string, as `SolanaPubkey::from_str` does
+    pub fn from_base58(s: &str) -> crate::Result<Self> {
+        crate::solana_format::SolanaPubkey::from_str(s).map(Self::from)
+    }
+
+    /// Convert to base58 string, as `SolanaPubkey::to_string` does
+    pub fn to_base58(&self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    /// Parse from a hex string of exactly 32 bytes
+    pub fn from_hex(s: &str) -> crate::Result<Self> {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(s, &mut bytes).map_err(|e| match e {
+            hex::FromHexError::InvalidStringLength => crate::TerminatorError::SerializationError(format!(
+                "Invalid pubkey length: expected 32 bytes, got {}",
+                s.len() / 2
+            )),
+            e => crate::TerminatorError::SerializationError(format!("Invalid hex pubkey: {}", e)),
+        })?;
+        Ok(Self(bytes))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl AsRef<[u8; 32]> for Pubkey {
+    fn as_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Account {
     pub lamports: u64,
     pub data: Vec<u8>,
@@ -63,15 +109,165 @@ impl Account {
             rent_epoch: 0,
         }
     }
+
+    /// Whether the balance covers rent exemption for the current data size
+    pub fn is_rent_exempt(&self, rent: &Rent) -> bool {
+        self.lamports >= rent.minimum_balance(self.data.len())
+    }
+
+    /// Resize account data, zero-filling growth and truncating on shrink
+    pub fn resize(&mut self, new_len: usize) -> crate::Result<()> {
+        Self::check_data_len(new_len as u64)?;
+        self.data.resize(new_len, 0);
+        Ok(())
+    }
+
+    /// Reset to an empty, unfunded system account
+    pub fn clear(&mut self) {
+        self.lamports = 0;
+        self.data.clear();
+        self.owner = crate::system_program::SYSTEM_PROGRAM_ID;
+        self.executable = false;
+        self.rent_epoch = 0;
+    }
+
+    /// Reject data sizes above `MAX_PERMITTED_DATA_LENGTH`
+    pub fn check_data_len(len: u64) -> crate::Result<()> {
+        if len > crate::system_program::MAX_PERMITTED_DATA_LENGTH {
+            return Err(crate::TerminatorError::TransactionExecutionFailed(
+                "requested data size exceeds maximum".to_string()
+            ));
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Bytes of per-account metadata charged for rent on top of the data
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+/// Rent parameters, defaulting to mainnet's
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rent {
+    pub lamports_per_byte_year: u64,
+    pub exemption_threshold: f64,
+}
+
+impl Default for Rent {
+    fn default() -> Self {
+        Self {
+            lamports_per_byte_year: 3480,
+            exemption_threshold: 2.0,
+        }
+    }
+}
+
+impl Rent {
+    /// Minimum balance for an account holding `data_len` bytes to be rent exempt
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        let bytes = ACCOUNT_STORAGE_OVERHEAD + data_len as u64;
+        ((bytes * self.lamports_per_byte_year) as f64 * self.exemption_threshold) as u64
+    }
+
+    /// Rent owed for holding `data_len` bytes through one epoch of
+    /// `DEFAULT_SLOTS_PER_EPOCH` slots
+    pub fn due_per_epoch(&self, data_len: usize) -> u64 {
+        let bytes = ACCOUNT_STORAGE_OVERHEAD + data_len as u64;
+        let epoch_ms = DEFAULT_SLOTS_PER_EPOCH * DEFAULT_MS_PER_SLOT;
+        ((bytes * self.lamports_per_byte_year) as f64 * epoch_ms as f64 / MS_PER_YEAR) as u64
+    }
+}
+
+/// Milliseconds in a 365.25-day year, the period rent rates are quoted over
+const MS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccountMeta {
     pub pubkey: Pubkey,
     pub is_signer: bool,
     pub is_writable: bool,
 }
 
+/// The distinct keys among `account_metas` in first-seen order, and each
+/// meta's index into them
+pub fn distinct_accounts(account_metas: &[AccountMeta]) -> (Vec<Pubkey>, Vec<usize>) {
+    let mut keys: Vec<Pubkey> = Vec::new();
+    let positions = account_metas.iter()
+        .map(|meta| keys.iter().position(|key| *key == meta.pubkey).unwrap_or_else(|| {
+            keys.push(meta.pubkey);
+            keys.len() - 1
+        }))
+        .collect();
+    (keys, positions)
+}
+
+/// An instruction's accounts, indexed by their position in the instruction
+///
+/// Positions naming the same key share one account, so a write through one
+/// position is read back through the others.
+#[derive(Debug)]
+pub struct InstructionAccounts<'a> {
+    accounts: Vec<&'a mut Account>,
+    positions: Vec<usize>,
+}
+
+impl<'a> InstructionAccounts<'a> {
+    /// One position per account, in order
+    pub fn new(accounts: Vec<&'a mut Account>) -> Self {
+        let positions = (0..accounts.len()).collect();
+        Self { accounts, positions }
+    }
+
+    /// Positions that each index into `accounts`, possibly repeating
+    pub fn aliased(accounts: Vec<&'a mut Account>, positions: Vec<usize>) -> crate::Result<Self> {
+        if positions.iter().any(|&index| index >= accounts.len()) {
+            return Err(crate::TerminatorError::InvalidAccountIndex);
+        }
+        Ok(Self { accounts, positions })
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Whether two positions name the same account
+    pub fn is_same(&self, a: usize, b: usize) -> bool {
+        self.positions[a] == self.positions[b]
+    }
+
+    /// Move lamports between the accounts at two positions, which may be the same account
+    pub fn move_lamports(&mut self, from: usize, to: usize, lamports: u64) -> crate::Result<()> {
+        if self[from].lamports < lamports {
+            return Err(crate::TerminatorError::InsufficientFunds);
+        }
+        if self.is_same(from, to) {
+            return Ok(());
+        }
+        let credited = self[to].lamports.checked_add(lamports)
+            .ok_or_else(|| crate::TerminatorError::TransactionExecutionFailed("lamport overflow".to_string()))?;
+        self[from].lamports -= lamports;
+        self[to].lamports = credited;
+        Ok(())
+    }
+}
+
+impl core::ops::Index<usize> for InstructionAccounts<'_> {
+    type Output = Account;
+
+    fn index(&self, position: usize) -> &Account {
+        self.accounts[self.positions[position]]
+    }
+}
+
+impl core::ops::IndexMut<usize> for InstructionAccounts<'_> {
+    fn index_mut(&mut self, position: usize) -> &mut Account {
+        self.accounts[self.positions[position]]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instruction {
     pub program_id: Pubkey,
@@ -91,7 +287,7 @@ pub struct Transaction {
     pub recent_blockhash: [u8; 32],
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstructionData {
     Transfer {
         #[serde(with = "serde_bytes")]
@@ -121,15 +317,302 @@ pub enum InstructionData {
     },
 }
 
+impl InstructionData {
+    /// Bytes of the equivalent compiled instruction; system variants use the
+    /// system program's Borsh encoding, their keys becoming the accounts
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let instruction = match self {
+            InstructionData::Transfer { lamports, .. } => SystemInstruction::Transfer { lamports: *lamports },
+            InstructionData::CreateAccount { lamports, space, owner, .. } => {
+                SystemInstruction::CreateAccount { lamports: *lamports, space: *space, owner: *owner }
+            }
+            InstructionData::Assign { owner, .. } => SystemInstruction::Assign { owner: *owner },
+            InstructionData::Generic { data } => return data.clone(),
+        };
+        borsh::to_vec(&instruction).expect("System instructions always serialize")
+    }
+
+    /// Decode a compiled instruction's bytes sent to `program_id` with
+    /// `accounts`, falling back to `Generic` for anything not recognized
+    pub fn from_bytes(program_id: &Pubkey, data: &[u8], accounts: &[Pubkey]) -> Self {
+        let generic = || InstructionData::Generic { data: data.to_vec() };
+        if *program_id != Pubkey::system_program() {
+            return generic();
+        }
+        match (SystemInstruction::decode(data), accounts) {
+            (Ok(SystemInstruction::Transfer { lamports }), [from, to, ..]) => {
+                InstructionData::Transfer { from: from.0, to: to.0, lamports }
+            }
+            (Ok(SystemInstruction::CreateAccount { lamports, space, owner }), [from, to, ..]) => {
+                InstructionData::CreateAccount { from: from.0, to: to.0, lamports, space, owner }
+            }
+            (Ok(SystemInstruction::Assign { owner }), [account, ..]) => {
+                InstructionData::Assign { account: account.0, owner }
+            }
+            _ => generic(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionResult {
     pub success: bool,
     pub compute_units_consumed: u64,
     pub logs: Vec<String>,
-    pub error: Option<String>,
+    pub error: Option<TransactionError>,
+    /// Changes to every account the transaction's instructions referenced
+    #[serde(default)]
+    pub account_deltas: Vec<AccountDelta>,
+    /// Text of the transaction's Memo program instructions, in order
+    #[serde(default)]
+    pub memos: Vec<String>,
+}
+
+/// Outcome of executing a transaction against a scratch copy of the ledger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub success: bool,
+    pub compute_units_consumed: u64,
+    pub logs: Vec<String>,
+    pub error: Option<TransactionError>,
+    pub account_deltas: Vec<AccountDelta>,
+    /// Units consumed by each program's instructions; signature checks and
+    /// fee charging are not attributed to any program
+    #[cfg(feature = "std")]
+    #[serde(default)]
+    pub compute_units_by_program: HashMap<Pubkey, u64>,
+}
+
+/// Whether a transaction's accounts can be loaded and its fee paid, checked
+/// without executing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreflightReport {
+    /// One entry per account key, in message order
+    pub accounts: Vec<AccountPreflight>,
+    pub fee: u64,
+    /// Whether the fee payer exists and holds at least `fee`
+    pub fee_payer_funded: bool,
+}
+
+/// Load-time state of one account a transaction references
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountPreflight {
+    pub pubkey: Pubkey,
+    pub exists: bool,
+    pub lamports: u64,
+    pub is_fee_payer: bool,
+}
+
+/// How a single account changed across a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDelta {
+    pub pubkey: Pubkey,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub data_changed: bool,
+    pub data_len_before: usize,
+    pub data_len_after: usize,
+    pub owner_changed: bool,
+}
+
+impl AccountDelta {
+    /// Compare an account's state before and after; a missing account is
+    /// treated as empty and system-owned
+    pub fn between(pubkey: Pubkey, before: Option<&Account>, after: Option<&Account>) -> Self {
+        let data_before = before.map(|a| a.data.as_slice()).unwrap_or(&[]);
+        let data_after = after.map(|a| a.data.as_slice()).unwrap_or(&[]);
+        let owner_before = before.map(|a| a.owner).unwrap_or([0u8; 32]);
+        let owner_after = after.map(|a| a.owner).unwrap_or([0u8; 32]);
+
+        AccountDelta {
+            pubkey,
+            lamports_before: before.map(|a| a.lamports).unwrap_or(0),
+            lamports_after: after.map(|a| a.lamports).unwrap_or(0),
+            data_changed: data_before != data_after,
+            data_len_before: data_before.len(),
+            data_len_after: data_after.len(),
+            owner_changed: owner_before != owner_after,
+        }
+    }
+
+    /// Signed change in data length
+    pub fn data_len_change(&self) -> i64 {
+        self.data_len_after as i64 - self.data_len_before as i64
+    }
+
+    /// Signed lamport change (positive when the account gains lamports)
+    pub fn lamport_change(&self) -> i128 {
+        self.lamports_after as i128 - self.lamports_before as i128
+    }
+}
+
+/// A run of consecutive data bytes that differ between two account states
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataChange {
+    pub offset: usize,
+    /// Bytes before; shorter than `new` when the data grew
+    pub old: Vec<u8>,
+    /// Bytes after; shorter than `old` when the data shrank
+    pub new: Vec<u8>,
+}
+
+/// Field-by-field difference between two states of one account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    /// Signed lamport change (positive when the account gains lamports)
+    pub lamports_delta: i128,
+    /// Owner before and after, when it changed
+    pub owner_change: Option<([u8; 32], [u8; 32])>,
+    /// Executable flag before and after, when it changed
+    pub executable_change: Option<(bool, bool)>,
+    pub data_changes: Vec<DataChange>,
+}
+
+impl AccountDiff {
+    pub fn compute(before: &Account, after: &Account) -> Self {
+        let mut data_changes: Vec<DataChange> = Vec::new();
+        let len = before.data.len().max(after.data.len());
+        let mut offset = 0;
+        while offset < len {
+            if before.data.get(offset) == after.data.get(offset) {
+                offset += 1;
+                continue;
+            }
+            let start = offset;
+            while offset < len && before.data.get(offset) != after.data.get(offset) {
+                offset += 1;
+            }
+            let slice = |data: &[u8]| data[start.min(data.len())..offset.min(data.len())].to_vec();
+            data_changes.push(DataChange { offset: start, old: slice(&before.data), new: slice(&after.data) });
+        }
+
+        AccountDiff {
+            lamports_delta: after.lamports as i128 - before.lamports as i128,
+            owner_change: (before.owner != after.owner).then_some((before.owner, after.owner)),
+            executable_change: (before.executable != after.executable).then_some((before.executable, after.executable)),
+            data_changes,
+        }
+    }
+
+    /// The two states are identical in every compared field
+    pub fn is_empty(&self) -> bool {
+        self.lamports_delta == 0
+            && self.owner_change.is_none()
+            && self.executable_change.is_none()
+            && self.data_changes.is_empty()
+    }
+}
+
+impl core::fmt::Display for AccountDiff {
+    /// One line per changed field, then `-`/`+` hex lines for each changed data range
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ");
+
+        if self.lamports_delta != 0 {
+            writeln!(f, "lamports: {:+}", self.lamports_delta)?;
+        }
+        if let Some((old, new)) = &self.owner_change {
+            writeln!(f, "owner: {} -> {}", Pubkey(*old).to_base58(), Pubkey(*new).to_base58())?;
+        }
+        if let Some((old, new)) = self.executable_change {
+            writeln!(f, "executable: {} -> {}", old, new)?;
+        }
+        for change in &self.data_changes {
+            writeln!(f, "- {:08x}: {}", change.offset, hex(&change.old))?;
+            writeln!(f, "+ {:08x}: {}", change.offset, hex(&change.new))?;
+        }
+        Ok(())
+    }
+}
+
+/// Structured reason a transaction failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum TransactionError {
+    #[error("Insufficient funds")]
+    InsufficientFunds,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Account not found: {0}")]
+    AccountNotFound(String),
+
+    #[error("Invalid account index")]
+    InvalidAccountIndex,
+
+    #[error("Attempt to write to readonly account")]
+    ReadonlyAccountModified,
+
+    #[error("Compute budget exceeded")]
+    ComputeBudgetExceeded,
+
+    #[error("Instruction {index} failed: {message}")]
+    ProgramError { index: usize, message: String },
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl TransactionError {
+    /// Map a runtime error onto a structured error, attributing it to the
+    /// instruction that was executing when it occurred (if any)
+    pub fn from_terminator_error(error: &crate::TerminatorError, instruction_index: Option<usize>) -> Self {
+        use crate::TerminatorError as E;
+
+        match error {
+            E::InsufficientFunds => TransactionError::InsufficientFunds,
+            E::InvalidSignature | E::MalformedPublicKey(_) => TransactionError::InvalidSignature,
+            E::AccountNotFound(account) => TransactionError::AccountNotFound(account.clone()),
+            E::ComputeBudgetExceeded => TransactionError::ComputeBudgetExceeded,
+            E::InvalidAccountIndex => TransactionError::InvalidAccountIndex,
+            E::ReadonlyAccountModified => TransactionError::ReadonlyAccountModified,
+            E::TransactionExecutionFailed(message) | E::ProgramError(message) | E::BpfVmError(message) => {
+                Self::program_error(message, instruction_index)
+            }
+            E::SerializationError(message) => match instruction_index {
+                Some(index) => TransactionError::ProgramError { index, message: message.clone() },
+                None => TransactionError::SerializationError(message.clone()),
+            },
+            other => TransactionError::Other(other.to_string()),
+        }
+    }
+
+    fn program_error(message: &str, instruction_index: Option<usize>) -> Self {
+        match instruction_index {
+            Some(index) => TransactionError::ProgramError { index, message: message.to_string() },
+            None => TransactionError::Other(message.to_string()),
+        }
+    }
+}
+
+/// Record of a transaction executed by `TerminatorRuntime`
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutedTransaction {
+    /// First signature of the transaction (all zeros if unsigned)
+    #[serde_as(as = "Bytes")]
+    pub signature: [u8; 64],
+    /// SHA256 of the serialized transaction
+    #[serde_as(as = "Bytes")]
+    pub hash: [u8; 32],
+    pub success: bool,
+    pub error: Option<TransactionError>,
+    pub fee: u64,
+    pub compute_units_consumed: u64,
+    pub slot: u64,
+    /// Monotonic execution sequence number
+    pub sequence: u64,
 }
 
+/// Runtime configuration, loaded from TOML
+///
+/// Sections or fields missing from the file take the values from
+/// `RuntimeConfig::default()`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RuntimeConfig {
     pub runtime: RuntimeSettings,
     pub bank: BankSettings,
@@ -145,6 +628,41 @@ pub struct RuntimeSettings {
     pub max_transaction_size: usize,
     pub enable_fuzzing: bool,
     pub conformance_testing: bool,
+    /// Reject transactions whose signatures don't verify (default: false)
+    #[serde(default)]
+    pub require_valid_signatures: bool,
+    /// Maximum cross-program invocation depth (default: 4)
+    #[serde(default = "default_max_call_depth")]
+    pub max_call_depth: usize,
+    /// Enable JIT compilation in the BPF VM (default: true)
+    #[serde(default = "default_enable_jit")]
+    pub enable_jit: bool,
+    /// Slots the clock advances after each committed transaction (default: 1)
+    #[serde(default = "default_slots_per_transaction")]
+    pub slots_per_transaction: u64,
+    /// Compute units charged for each kind of work (default: the runtime's flat costs)
+    #[serde(default)]
+    pub cost_model: ComputeCostModel,
+    /// Most accounts the ledger may hold before instructions creating new
+    /// ones are rejected (default: unlimited)
+    #[serde(default)]
+    pub max_accounts: Option<usize>,
+}
+
+fn default_max_call_depth() -> usize {
+    4
+}
+
+fn default_enable_jit() -> bool {
+    true
+}
+
+fn default_slots_per_transaction() -> u64 {
+    1
+}
+
+fn default_fee_lamports_per_signature() -> u64 {
+    5000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,6 +670,9 @@ pub struct BankSettings {
     pub initial_lamports: u64,
     pub rent_collection_enabled: bool,
     pub fee_rate_governor_enabled: bool,
+    /// Base fee charged per transaction signature (default: 5000)
+    #[serde(default = "default_fee_lamports_per_signature")]
+    pub fee_lamports_per_signature: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,6 +680,13 @@ pub struct LoggingSettings {
     pub level: String,
     pub enable_transaction_logs: bool,
     pub enable_execution_traces: bool,
+    /// Number of executed transactions kept in the runtime history (default: 1000)
+    #[serde(default = "default_history_capacity")]
+    pub history_capacity: usize,
+}
+
+fn default_history_capacity() -> usize {
+    1000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,19 +714,27 @@ impl Default for RuntimeConfig {
         Self {
             runtime: RuntimeSettings {
                 compute_budget: 1_400_000,
-                max_transaction_size: 1232,
+                max_transaction_size: crate::solana_format::PACKET_DATA_SIZE,
                 enable_fuzzing: false,
                 conformance_testing: false,
+                require_valid_signatures: false,
+                max_call_depth: default_max_call_depth(),
+                enable_jit: default_enable_jit(),
+                slots_per_transaction: default_slots_per_transaction(),
+                cost_model: ComputeCostModel::default(),
+                max_accounts: None,
             },
             bank: BankSettings {
                 initial_lamports: 1_000_000_000_000,
                 rent_collection_enabled: true,
                 fee_rate_governor_enabled: true,
+                fee_lamports_per_signature: default_fee_lamports_per_signature(),
             },
             logging: LoggingSettings {
                 level: "info".to_string(),
                 enable_transaction_logs: true,
                 enable_execution_traces: true,
+                history_capacity: default_history_capacity(),
             },
             performance: PerformanceSettings {
                 max_concurrent_transactions: 1000,
@@ -218,23 +754,103 @@ impl Default for RuntimeConfig {
     }
 }
 
+/// Accounts a runtime's ledger starts with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisConfig {
+    pub accounts: Vec<(Pubkey, Account)>,
+}
+
+impl GenesisConfig {
+    pub fn new(accounts: Vec<(Pubkey, Account)>) -> Self {
+        Self { accounts }
+    }
+}
+
+impl Default for GenesisConfig {
+    /// The demo ledger: the system program and 10 SOL at `[1; 32]`
+    fn default() -> Self {
+        Self::new(vec![
+            (
+                Pubkey::new(crate::system_program::SYSTEM_PROGRAM_ID),
+                Account::new_executable(1, vec![], crate::system_program::SYSTEM_PROGRAM_ID),
+            ),
+            (
+                Pubkey::new([1u8; 32]),
+                Account::new(10_000_000_000, vec![], crate::system_program::SYSTEM_PROGRAM_ID),
+            ),
+        ])
+    }
+}
+
+/// Slots in an epoch on mainnet
+pub const DEFAULT_SLOTS_PER_EPOCH: u64 = 432_000;
+
+/// Target slot duration in milliseconds
+pub const DEFAULT_MS_PER_SLOT: u64 = 400;
+
+/// Clock sysvar: the bank's view of time
+///
+/// Time is derived from the slot alone, starting at the Unix epoch, so
+/// replaying the same slots always yields the same clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Clock {
+    pub slot: u64,
+    pub epoch: u64,
+    pub unix_timestamp: i64,
+}
+
+impl Clock {
+    /// Clock as it reads at `slot`
+    pub fn at_slot(slot: u64) -> Self {
+        Self {
+            slot,
+            epoch: slot / DEFAULT_SLOTS_PER_EPOCH,
+            unix_timestamp: (slot.saturating_mul(DEFAULT_MS_PER_SLOT) / 1000) as i64,
+        }
+    }
+
+    /// Move to the next slot
+    pub fn advance_slot(&mut self) {
+        self.advance_slots(1);
+    }
+
+    /// Move `slots` slots forward in one step, stopping at the last slot
+    pub fn advance_slots(&mut self, slots: u64) {
+        *self = Self::at_slot(self.slot.saturating_add(slots));
+    }
+}
+
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct BankState {
     pub accounts: HashMap<Pubkey, Account>,
-    pub slot: u64,
+    pub clock: Clock,
     pub blockhash: [u8; 32],
     pub fee_calculator: FeeCalculator,
 }
 
+#[cfg(feature = "std")]
 impl BankState {
     pub fn new() -> Self {
         Self {
             accounts: HashMap::new(),
-            slot: 0,
+            clock: Clock::default(),
             blockhash: [0u8; 32],
             fee_calculator: FeeCalculator::default(),
         }
     }
+
+    pub fn slot(&self) -> u64 {
+        self.clock.slot
+    }
+
+    pub fn advance_slot(&mut self) {
+        self.clock.advance_slot();
+    }
+
+    pub fn advance_slots(&mut self, slots: u64) {
+        self.clock.advance_slots(slots);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -250,10 +866,151 @@ impl Default for FeeCalculator {
     }
 }
 
+impl FeeCalculator {
+    pub fn new(lamports_per_signature: u64) -> Self {
+        Self { lamports_per_signature }
+    }
+
+    /// Total fee for a message: the signature fee plus the priority fee
+    ///
+    /// The priority fee is `compute_unit_price` micro-lamports for each
+    /// requested compute unit, rounded up to a whole lamport.
+    pub fn calculate_fee(
+        &self,
+        message: &crate::solana_format::SolanaMessage,
+        limits: &crate::compute_budget::ComputeBudgetLimits,
+    ) -> u64 {
+        let signature_fee = self.lamports_per_signature
+            .saturating_mul(message.header.num_required_signatures as u64);
+        let priority_micro_lamports = limits.compute_unit_price as u128 * limits.compute_unit_limit as u128;
+        let priority_fee = priority_micro_lamports.div_ceil(1_000_000);
+
+        signature_fee.saturating_add(u64::try_from(priority_fee).unwrap_or(u64::MAX))
+    }
+}
+
+/// Verbosity of an `ExecutionContext`'s program logs, from quietest to loudest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Info,
+    Debug,
+}
+
+impl core::str::FromStr for LogLevel {
+    type Err = crate::TerminatorError;
+
+    /// Parse a `[logging] level` config value; `warn` and `trace` map to the
+    /// nearest supported level
+    fn from_str(level: &str) -> crate::Result<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "error" | "warn" => Ok(LogLevel::Error),
+            "info" => Ok(LogLevel::Info),
+            "debug" | "trace" => Ok(LogLevel::Debug),
+            _ => Err(crate::TerminatorError::SerializationError(format!("Unknown log level: {}", level))),
+        }
+    }
+}
+
+/// Host-side sink for an `ExecutionContext`'s log messages, which by default
+/// discards them so the core needs no logging backend
+pub trait Logger: core::fmt::Debug + Sync {
+    fn log(&self, _level: LogLevel, _message: &str) {}
+}
+
+/// Logger that discards every message, used unless `with_logger` sets another
+#[derive(Debug)]
+pub struct NoopLogger;
+
+impl Logger for NoopLogger {}
+
+/// Forwards messages to `tracing` at the matching level
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct TracingLogger;
+
+#[cfg(feature = "std")]
+impl Logger for TracingLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        match level {
+            LogLevel::Off => {}
+            LogLevel::Error => tracing::error!("{}", message),
+            LogLevel::Info => tracing::info!("{}", message),
+            LogLevel::Debug => tracing::debug!("{}", message),
+        }
+    }
+}
+
+fn noop_logger() -> &'static dyn Logger {
+    &NoopLogger
+}
+
+/// Compute units charged for each kind of work
+///
+/// The defaults are the flat costs this runtime has always charged, not
+/// Solana's schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComputeCostModel {
+    /// Charged for every instruction before its program runs
+    pub instruction_base: u64,
+    /// Charged per signature verified; Solana prices signatures through the
+    /// fee instead, so this is 0 by default
+    pub signature_verify: u64,
+    /// Bytes of account space allocated per compute unit
+    pub allocate_bytes_per_unit: u64,
+    /// System program transfer
+    pub system_transfer: u64,
+    /// System program account creation
+    pub system_create_account: u64,
+    /// Every other system program instruction
+    pub system_instruction: u64,
+}
+
+impl Default for ComputeCostModel {
+    fn default() -> Self {
+        Self {
+            instruction_base: 1000,
+            signature_verify: 0,
+            allocate_bytes_per_unit: 100,
+            system_transfer: 200,
+            system_create_account: 1000,
+            system_instruction: 500,
+        }
+    }
+}
+
+impl ComputeCostModel {
+    /// Units to allocate `space` bytes
+    pub fn allocate(&self, space: u64) -> u64 {
+        space / self.allocate_bytes_per_unit.max(1)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionContext {
     pub compute_units_remaining: u64,
     pub log_messages: Vec<String>,
+    /// Most verbose messages recorded in `log_messages` (default: `Debug`, everything)
+    pub log_level: LogLevel,
+    /// Also receives every recorded message
+    #[serde(skip, default = "noop_logger")]
+    logger: &'static dyn Logger,
+    /// Clock sysvar as of the executing slot
+    pub clock: Clock,
+    /// Blockhash of the executing slot, which durable nonces are derived from
+    pub blockhash: [u8; 32],
+    /// Every instruction in the executing transaction, as the instructions sysvar exposes them
+    pub instructions: Vec<CompiledInstruction>,
+    /// Position of the executing instruction in `instructions`
+    pub(crate) current_instruction: usize,
+    /// Units each kind of work costs
+    pub cost_model: ComputeCostModel,
+    /// Units consumed by each program's instructions, in the order the
+    /// programs were first invoked
+    pub compute_units_by_program: Vec<(Pubkey, u64)>,
 }
 
 impl ExecutionContext {
@@ -261,9 +1018,65 @@ impl ExecutionContext {
         Self {
             compute_units_remaining: compute_budget,
             log_messages: Vec::new(),
+            log_level: LogLevel::Debug,
+            logger: noop_logger(),
+            clock: Clock::default(),
+            blockhash: [0u8; 32],
+            instructions: Vec::new(),
+            current_instruction: 0,
+            cost_model: ComputeCostModel::default(),
+            compute_units_by_program: Vec::new(),
         }
     }
 
+    pub fn with_cost_model(mut self, cost_model: ComputeCostModel) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    pub fn with_logger(mut self, logger: &'static dyn Logger) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_blockhash(mut self, blockhash: [u8; 32]) -> Self {
+        self.blockhash = blockhash;
+        self
+    }
+
+    pub fn with_instructions(mut self, instructions: Vec<CompiledInstruction>) -> Self {
+        self.instructions = instructions;
+        self.current_instruction = 0;
+        self
+    }
+
+    /// Instruction `index` of the executing transaction
+    pub fn get_instruction(&self, index: usize) -> Option<&CompiledInstruction> {
+        self.instructions.get(index)
+    }
+
+    /// Index of the executing instruction
+    pub fn current_instruction_index(&self) -> usize {
+        self.current_instruction
+    }
+
+    /// Instruction `offset` positions from the executing one, as
+    /// `sol_get_instruction_relative` resolves it
+    pub fn get_instruction_relative(&self, offset: i64) -> Option<&CompiledInstruction> {
+        let index = (self.current_instruction as i64).checked_add(offset)?;
+        usize::try_from(index).ok().and_then(|index| self.get_instruction(index))
+    }
+
     pub fn consume_compute_units(&mut self, units: u64) -> bool {
         if self.compute_units_remaining >= units {
             self.compute_units_remaining -= units;
@@ -273,7 +1086,218 @@ impl ExecutionContext {
         }
     }
 
+    /// Draw `units` from the budget, exhausting it and failing when too few remain
+    pub fn charge_compute_units(&mut self, units: u64) -> crate::Result<()> {
+        if !self.consume_compute_units(units) {
+            self.compute_units_remaining = 0;
+            return Err(crate::TerminatorError::ComputeBudgetExceeded);
+        }
+        Ok(())
+    }
+
+    /// Attribute `units` consumed by an instruction to `program`
+    pub fn record_program_units(&mut self, program: Pubkey, units: u64) {
+        match self.compute_units_by_program.iter_mut().find(|(id, _)| *id == program) {
+            Some((_, total)) => *total += units,
+            None => self.compute_units_by_program.push((program, units)),
+        }
+    }
+
+    /// Record an info-level message
     pub fn log(&mut self, message: String) {
-        self.log_messages.push(message);
+        self.log_at(LogLevel::Info, message);
+    }
+
+    /// Record a message about a failure
+    pub fn log_error(&mut self, message: String) {
+        self.log_at(LogLevel::Error, message);
+    }
+
+    /// Record a message only useful when debugging execution
+    pub fn log_debug(&mut self, message: String) {
+        self.log_at(LogLevel::Debug, message);
+    }
+
+    fn log_at(&mut self, level: LogLevel, message: String) {
+        if level <= self.log_level {
+            self.logger.log(level, &message);
+            self.log_messages.push(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_format::SolanaPubkey;
+
+    #[test]
+    fn test_instruction_data_round_trips_transfer() {
+        let from = Pubkey::new([1u8; 32]);
+        let to = Pubkey::new([2u8; 32]);
+        let transfer = InstructionData::Transfer { from: from.0, to: to.0, lamports: 42_000 };
+
+        let bytes = transfer.to_bytes();
+        assert_eq!(bytes[0], 2);
+        assert_eq!(bytes[1..], 42_000u64.to_le_bytes());
+        assert_eq!(InstructionData::from_bytes(&Pubkey::system_program(), &bytes, &[from, to]), transfer);
+
+        // Without both accounts, or for another program, the bytes stay generic
+        let generic = InstructionData::Generic { data: bytes.clone() };
+        assert_eq!(InstructionData::from_bytes(&Pubkey::system_program(), &bytes, &[from]), generic);
+        assert_eq!(InstructionData::from_bytes(&Pubkey::token_program(), &bytes, &[from, to]), generic);
+        assert_eq!(generic.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_pubkey_conversions_round_trip() {
+        let pubkey = Pubkey::token_program();
+        let solana: SolanaPubkey = pubkey.into();
+        assert_eq!(solana, SolanaPubkey::token_program());
+        assert_eq!(Pubkey::from(solana), pubkey);
+        assert_eq!(AsRef::<[u8; 32]>::as_ref(&pubkey), solana.as_ref());
+
+        let base58 = pubkey.to_base58();
+        assert_eq!(base58, solana.to_string());
+        assert_eq!(base58, "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+        assert_eq!(Pubkey::from_base58(&base58).unwrap(), pubkey);
+        assert!(Pubkey::from_base58("not-base58!").is_err());
+        assert!(Pubkey::from_base58("11111111").is_err());
+    }
+
+    #[test]
+    fn test_pubkey_from_hex() {
+        let hex = "07".repeat(32);
+        assert_eq!(Pubkey::from_hex(&hex).unwrap(), Pubkey::new([7u8; 32]));
+
+        let error = |s: &str| match Pubkey::from_hex(s) {
+            Err(crate::TerminatorError::SerializationError(msg)) => msg,
+            other => panic!("expected a serialization error, got {:?}", other),
+        };
+        assert!(error(&hex[..63]).starts_with("Invalid hex pubkey"));
+        assert!(error(&"zz".repeat(32)).starts_with("Invalid hex pubkey"));
+        assert_eq!(error(&hex[..62]), "Invalid pubkey length: expected 32 bytes, got 31");
+    }
+
+    #[test]
+    fn test_instruction_introspection() {
+        // Stands in for a program that only runs after a precompile verified its payload
+        fn guarded_program(context: &ExecutionContext) -> crate::Result<()> {
+            let data = &context.get_instruction(context.current_instruction_index()).unwrap().data;
+            match context.get_instruction_relative(-1) {
+                Some(precompile) if precompile.program_id_index == 3 && precompile.data == *data => Ok(()),
+                _ => Err(crate::TerminatorError::ProgramError("Payload not verified".to_string())),
+            }
+        }
+
+        let instruction = |program_id_index, data: &[u8]| CompiledInstruction {
+            program_id_index,
+            accounts: Vec::new(),
+            data: data.to_vec(),
+        };
+        let mut context = ExecutionContext::new(1_000).with_instructions(vec![
+            instruction(3, b"payload"),
+            instruction(4, b"payload"),
+            instruction(4, b"forged"),
+        ]);
+        assert!(guarded_program(&context).is_err());
+
+        context.current_instruction = 1;
+        assert!(guarded_program(&context).is_ok());
+        context.current_instruction = 2;
+        assert!(guarded_program(&context).is_err());
+
+        assert!(context.get_instruction_relative(1).is_none());
+        assert!(context.get_instruction_relative(-3).is_none());
+        assert!(context.get_instruction_relative(i64::MIN).is_none());
+    }
+
+    #[test]
+    fn test_account_diff() {
+        let before = Account::new(1_000, vec![0, 1, 2, 3, 4, 5, 6, 7], [1u8; 32]);
+        let mut after = before.clone();
+        after.lamports += 500;
+        after.data[3] = 0xAA;
+        after.data[4] = 0xBB;
+        after.data.push(9);
+
+        let diff = AccountDiff::compute(&before, &after);
+        assert_eq!(diff.lamports_delta, 500);
+        assert_eq!(diff.owner_change, None);
+        assert_eq!(diff.data_changes, vec![
+            DataChange { offset: 3, old: vec![3, 4], new: vec![0xAA, 0xBB] },
+            DataChange { offset: 8, old: vec![], new: vec![9] },
+        ]);
+        assert_eq!(
+            diff.to_string(),
+            "lamports: +500\n- 00000003: 03 04\n+ 00000003: aa bb\n- 00000008: \n+ 00000008: 09\n"
+        );
+        assert!(AccountDiff::compute(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_log_level_filters_messages() {
+        let mut context = ExecutionContext::new(1_000).with_log_level("info".parse().unwrap());
+        context.log_error("failed".to_string());
+        context.log("info".to_string());
+        context.log_debug("debug".to_string());
+        assert_eq!(context.log_messages, vec!["failed".to_string(), "info".to_string()]);
+
+        let mut context = ExecutionContext::new(1_000).with_log_level(LogLevel::Off);
+        context.log_error("failed".to_string());
+        assert!(context.log_messages.is_empty());
+        assert!("verbose".parse::<LogLevel>().is_err());
+    }
+
+    #[test]
+    fn test_account_rent_exemption() {
+        let rent = Rent::default();
+        assert_eq!(rent.minimum_balance(0), 890_880);
+
+        let mut account = Account::new(rent.minimum_balance(10), vec![0u8; 10], [0u8; 32]);
+        assert!(account.is_rent_exempt(&rent));
+        account.lamports -= 1;
+        assert!(!account.is_rent_exempt(&rent));
+    }
+
+    #[test]
+    fn test_advance_slot() {
+        let mut bank = BankState::new();
+        assert_eq!(bank.clock, Clock::default());
+
+        bank.advance_slot();
+        bank.advance_slot();
+        assert_eq!(bank.slot(), 2);
+        assert_eq!(bank.clock.epoch, 0);
+
+        let clock = Clock::at_slot(DEFAULT_SLOTS_PER_EPOCH + 5);
+        assert_eq!(clock.epoch, 1);
+        assert_eq!(clock.unix_timestamp, ((DEFAULT_SLOTS_PER_EPOCH + 5) * DEFAULT_MS_PER_SLOT / 1000) as i64);
+    }
+
+    #[test]
+    fn test_account_resize() {
+        let mut account = Account::new(0, vec![1, 2, 3], [0u8; 32]);
+
+        account.resize(5).unwrap();
+        assert_eq!(account.data, vec![1, 2, 3, 0, 0]);
+
+        account.resize(2).unwrap();
+        assert_eq!(account.data, vec![1, 2]);
+
+        let too_large = crate::system_program::MAX_PERMITTED_DATA_LENGTH as usize + 1;
+        assert!(account.resize(too_large).is_err());
+        assert_eq!(account.data, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_account_clear() {
+        let mut account = Account::new_executable(1_000, vec![9u8; 4], [7u8; 32]);
+        account.clear();
+
+        assert_eq!(account.lamports, 0);
+        assert!(account.data.is_empty());
+        assert_eq!(account.owner, crate::system_program::SYSTEM_PROGRAM_ID);
+        assert!(!account.executable);
     }
 }
@@ -2,10 +2,16 @@
 /// Runs entirely in the browser with real-time metrics and interactive features
 
 use crate::{Result, TerminatorError};
-use crate::types::{Account, Pubkey, ExecutionContext, TransactionResult};
+use crate::types::{Account, AccountDelta, InstructionAccounts, Pubkey, ExecutionContext, GenesisConfig, LogLevel, RuntimeConfig, TransactionResult, distinct_accounts};
+use crate::integrated_runtime::{account_modified, touched_account_keys};
+use crate::memo::{transaction_memos, MemoProgram, MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID};
+use crate::precompiles::{Ed25519Program, Secp256k1Program, ED25519_PROGRAM_ID, SECP256K1_PROGRAM_ID};
 use crate::system_program::{SystemProgram, SYSTEM_PROGRAM_ID};
-use crate::solana_format::{SolanaTransaction, SolanaTransactionParser, SolanaPubkey, SolanaHash};
+use crate::solana_format::{account_metas, MessageHeader, SolanaTransaction, SolanaTransactionParser, SolanaPubkey, SolanaHash};
 use crate::crypto::SolanaCrypto;
+use crate::real_bpf_vm::{BpfBackend, RealBpfVm};
+use crate::describe::{describe_instruction, program_name};
+use serde::Serialize;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 use web_sys::{console, Performance};
@@ -15,9 +21,14 @@ use web_sys::{console, Performance};
 pub struct WasmRuntime {
     accounts: HashMap<Pubkey, Account>,
     compute_budget: u64,
+    log_level: LogLevel,
     transaction_count: u64,
     total_execution_time: f64,
     performance: Performance,
+    /// Ledger the runtime starts from and returns to on `reset`
+    genesis: GenesisConfig,
+    /// Runs executable accounts' programs with the pure-Rust interpreter
+    bpf_vm: RealBpfVm,
 }
 
 /// Performance metrics for real-time display
@@ -34,29 +45,19 @@ pub struct PerformanceMetrics {
 
 #[wasm_bindgen]
 impl WasmRuntime {
-    /// Create new WASM runtime instance
+    /// Create new WASM runtime instance with the default (demo) genesis ledger
     #[wasm_bindgen(constructor)]
     pub fn new() -> std::result::Result<WasmRuntime, JsValue> {
-        console::log_1(&"🚀 Initializing Terminator-Dancer WASM Runtime".into());
-        
-        // Get browser performance API
-        let window = web_sys::window().ok_or("No window object")?;
-        let performance = window.performance().ok_or("No performance API")?;
-        
-        let mut runtime = WasmRuntime {
-            accounts: HashMap::new(),
-            compute_budget: 1_400_000,
-            transaction_count: 0,
-            total_execution_time: 0.0,
-            performance,
-        };
-        
-        // Initialize default accounts
-        runtime.initialize_default_accounts()
+        Self::from_genesis(GenesisConfig::default())
+    }
+    
+    /// Create a WASM runtime whose ledger starts with the accounts in a
+    /// JSON-encoded `GenesisConfig`
+    #[wasm_bindgen]
+    pub fn with_genesis(genesis_json: &str) -> std::result::Result<WasmRuntime, JsValue> {
+        let genesis = GenesisConfig::from_json(genesis_json)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
-        console::log_1(&"✅ WASM Runtime initialized successfully".into());
-        Ok(runtime)
+        Self::from_genesis(genesis)
     }
     
     /// Execute a single Solana transaction and return metrics
@@ -65,23 +66,11 @@ impl WasmRuntime {
         let start_time = self.performance.now();
         
         // Parse addresses
-        let from_bytes = hex::decode(from_hex)
+        let from_pubkey = Pubkey::from_hex(from_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid from address: {}", e)))?;
-        let to_bytes = hex::decode(to_hex)
+        let to_pubkey = Pubkey::from_hex(to_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid to address: {}", e)))?;
         
-        if from_bytes.len() != 32 || to_bytes.len() != 32 {
-            return Err(JsValue::from_str("Addresses must be 32 bytes"));
-        }
-        
-        let mut from_array = [0u8; 32];
-        let mut to_array = [0u8; 32];
-        from_array.copy_from_slice(&from_bytes);
-        to_array.copy_from_slice(&to_bytes);
-        
-        let from_pubkey = Pubkey::new(from_array);
-        let to_pubkey = Pubkey::new(to_array);
-        
         // Create transaction
         let tx = self.create_transfer_transaction(&from_pubkey, &to_pubkey, amount)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
@@ -116,21 +105,10 @@ impl WasmRuntime {
         let mut failed = 0;
         
         for i in 0..count {
-            let from_array = [1u8; 32];
-            let mut to_array = [2u8; 32];
-            to_array[31] = (i % 256) as u8; // Vary recipient
-            
-            let from_pubkey = Pubkey::new(from_array);
-            let to_pubkey = Pubkey::new(to_array);
-            
-            // Ensure recipient account exists
-            if !self.accounts.contains_key(&to_pubkey) {
-                self.accounts.insert(to_pubkey, Account::new(0, vec![], SYSTEM_PROGRAM_ID));
-            }
-            
-            match self.create_and_execute_transfer(&from_pubkey, &to_pubkey, amount) {
-                Ok(_) => successful += 1,
-                Err(_) => failed += 1,
+            if self.execute_batch_transfer(i, amount) {
+                successful += 1;
+            } else {
+                failed += 1;
             }
         }
         
@@ -143,6 +121,30 @@ impl WasmRuntime {
         Ok(self.get_performance_metrics())
     }
     
+    /// Execute a batch in chunks of `chunk_size`, calling `on_progress` with
+    /// interim metrics after each chunk and yielding to the event loop in
+    /// between so the page stays responsive
+    ///
+    /// The runtime stays borrowed until the returned promise settles.
+    #[wasm_bindgen]
+    pub async fn execute_batch_streaming(
+        &mut self,
+        count: u32,
+        amount: u64,
+        chunk_size: u32,
+        on_progress: js_sys::Function,
+    ) -> std::result::Result<PerformanceMetrics, JsValue> {
+        for chunk in batch_chunks(count, chunk_size) {
+            for i in chunk {
+                self.execute_batch_transfer(i, amount);
+            }
+            on_progress.call1(&JsValue::NULL, &self.get_performance_metrics().into())?;
+            gloo_timers::future::TimeoutFuture::new(0).await;
+        }
+        
+        Ok(self.get_performance_metrics())
+    }
+    
     /// Get current performance metrics
     #[wasm_bindgen]
     pub fn get_metrics(&self) -> PerformanceMetrics {
@@ -152,32 +154,29 @@ impl WasmRuntime {
     /// Get account balance by hex address
     #[wasm_bindgen]
     pub fn get_balance(&self, address_hex: &str) -> std::result::Result<u64, JsValue> {
-        let address_bytes = hex::decode(address_hex)
+        let pubkey = Pubkey::from_hex(address_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid address: {}", e)))?;
         
-        if address_bytes.len() != 32 {
-            return Err(JsValue::from_str("Address must be 32 bytes"));
-        }
-        
-        let mut address_array = [0u8; 32];
-        address_array.copy_from_slice(&address_bytes);
-        let pubkey = Pubkey::new(address_array);
-        
         Ok(self.accounts.get(&pubkey).map(|acc| acc.lamports).unwrap_or(0))
     }
     
+    /// Set program log verbosity: "off", "error", "info" or "debug"
+    #[wasm_bindgen]
+    pub fn set_log_level(&mut self, level: &str) -> std::result::Result<(), JsValue> {
+        self.log_level = level.parse()
+            .map_err(|e: TerminatorError| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+    
     /// Reset runtime state for fresh demo
     #[wasm_bindgen]
     pub fn reset(&mut self) -> std::result::Result<(), JsValue> {
         console::log_1(&"🔄 Resetting runtime state".into());
         
-        self.accounts.clear();
+        self.accounts = self.genesis.accounts.iter().cloned().collect();
         self.transaction_count = 0;
         self.total_execution_time = 0.0;
         
-        self.initialize_default_accounts()
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
         console::log_1(&"✅ Runtime reset complete".into());
         Ok(())
     }
@@ -207,24 +206,38 @@ impl WasmRuntime {
 
 // Internal implementation
 impl WasmRuntime {
-    fn initialize_default_accounts(&mut self) -> Result<()> {
-        // System program account
-        let system_program_key = Pubkey::new(SYSTEM_PROGRAM_ID);
-        let system_account = Account::new_executable(1, vec![], SYSTEM_PROGRAM_ID);
-        self.accounts.insert(system_program_key, system_account);
-        
-        // Create funded test account
-        let test_account_key = Pubkey::new([1u8; 32]);
-        let test_account = Account::new(10_000_000_000, vec![], SYSTEM_PROGRAM_ID); // 10 SOL
-        self.accounts.insert(test_account_key, test_account);
-        
-        console::log_1(&"✅ Default accounts initialized".into());
-        Ok(())
+    /// Runtime on the browser's clock, starting from `genesis`
+    fn from_genesis(genesis: GenesisConfig) -> std::result::Result<WasmRuntime, JsValue> {
+        console::log_1(&"🚀 Initializing Terminator-Dancer WASM Runtime".into());
+        
+        // Get browser performance API
+        let window = web_sys::window().ok_or("No window object")?;
+        let performance = window.performance().ok_or("No performance API")?;
+        
+        let config = RuntimeConfig::default();
+        let log_level = config.logging.level.parse()
+            .map_err(|e: TerminatorError| JsValue::from_str(&e.to_string()))?;
+        
+        let runtime = WasmRuntime {
+            accounts: genesis.accounts.iter().cloned().collect(),
+            compute_budget: config.runtime.compute_budget,
+            log_level,
+            transaction_count: 0,
+            total_execution_time: 0.0,
+            performance,
+            genesis,
+            bpf_vm: RealBpfVm::new()
+                .map_err(|e| JsValue::from_str(&e.to_string()))?
+                .with_backend(BpfBackend::Interpreter),
+        };
+        
+        console::log_1(&"✅ WASM Runtime initialized successfully".into());
+        Ok(runtime)
     }
     
     fn create_transfer_transaction(&self, from: &Pubkey, to: &Pubkey, lamports: u64) -> Result<SolanaTransaction> {
-        let from_solana = SolanaPubkey::new(from.0);
-        let to_solana = SolanaPubkey::new(to.0);
+        let from_solana = SolanaPubkey::from(*from);
+        let to_solana = SolanaPubkey::from(*to);
         let blockhash = SolanaHash([0u8; 32]); // Mock blockhash for demo
         
         Ok(SolanaTransactionParser::create_transfer_transaction(
@@ -241,22 +254,26 @@ impl WasmRuntime {
     }
     
     fn execute_solana_transaction_internal(&mut self, solana_tx: &SolanaTransaction) -> Result<TransactionResult> {
-        let mut context = ExecutionContext::new(self.compute_budget);
+        let mut context = ExecutionContext::new(self.compute_budget)
+            .with_log_level(self.log_level)
+            .with_instructions(solana_tx.message.instructions.clone());
+        let touched = touched_account_keys(solana_tx);
+        let pre_state: Vec<Option<Account>> = touched.iter()
+            .map(|pubkey| self.accounts.get(pubkey).cloned())
+            .collect();
         
         // Process each instruction
-        for instruction in &solana_tx.message.instructions {
+        for (i, instruction) in solana_tx.message.instructions.iter().enumerate() {
+            context.current_instruction = i;
+            
             // Check compute budget
-            if !context.consume_compute_units(1000) {
-                return Err(TerminatorError::TransactionExecutionFailed(
-                    "Compute budget exceeded".to_string()
-                ));
+            if !context.consume_compute_units(context.cost_model.instruction_base) {
+                return Err(TerminatorError::ComputeBudgetExceeded);
             }
             
             // Get program ID
             if instruction.program_id_index >= solana_tx.message.account_keys.len() as u8 {
-                return Err(TerminatorError::TransactionExecutionFailed(
-                    "Invalid program_id_index".to_string()
-                ));
+                return Err(TerminatorError::InvalidAccountIndex);
             }
             
             let program_id = solana_tx.message.account_keys[instruction.program_id_index as usize].0;
@@ -265,6 +282,7 @@ impl WasmRuntime {
             self.execute_instruction(
                 &program_id,
                 &instruction.data,
+                &solana_tx.message.header,
                 &solana_tx.message.account_keys,
                 &instruction.accounts,
                 &mut context,
@@ -276,6 +294,11 @@ impl WasmRuntime {
             compute_units_consumed: self.compute_budget - context.compute_units_remaining,
             logs: context.log_messages,
             error: None,
+            account_deltas: touched.iter()
+                .zip(&pre_state)
+                .map(|(pubkey, before)| AccountDelta::between(*pubkey, before.as_ref(), self.accounts.get(pubkey)))
+                .collect(),
+            memos: transaction_memos(solana_tx),
         })
     }
     
@@ -283,69 +306,107 @@ impl WasmRuntime {
         &mut self,
         program_id: &[u8; 32],
         instruction_data: &[u8],
+        header: &MessageHeader,
         account_keys: &[SolanaPubkey],
         account_indices: &[u8],
         context: &mut ExecutionContext,
     ) -> Result<()> {
-        // Convert account keys
-        let pubkeys: Vec<Pubkey> = account_keys.iter()
-            .map(|pk| Pubkey::new(pk.0))
-            .collect();
-        
-        // Ensure accounts exist
-        for &index in account_indices {
-            if index >= pubkeys.len() as u8 {
-                return Err(TerminatorError::TransactionExecutionFailed(
-                    "Invalid account index".to_string()
-                ));
-            }
-            
-            let pubkey = &pubkeys[index as usize];
-            
-            if !self.accounts.contains_key(pubkey) {
-                let new_account = Account::new(0, vec![], SYSTEM_PROGRAM_ID);
-                self.accounts.insert(*pubkey, new_account);
-            }
-        }
+        let account_metas = account_metas(account_indices, header, account_keys)?;
         
         // Execute based on program
         match *program_id {
             SYSTEM_PROGRAM_ID => {
-                // Get account references for system program
-                let mut account_infos: Vec<Account> = account_indices.iter()
-                    .map(|&index| {
-                        let pubkey = &pubkeys[index as usize];
-                        self.accounts.get(pubkey).cloned().unwrap()
+                // Load each distinct account once; repeated positions share it.
+                // Accounts that don't exist yet are only created if written to
+                let (keys, positions) = distinct_accounts(&account_metas);
+                let mut account_infos: Vec<Account> = keys.iter()
+                    .map(|key| {
+                        self.accounts.get(key)
+                            .cloned()
+                            .unwrap_or_else(|| Account::new(0, vec![], SYSTEM_PROGRAM_ID))
                     })
                     .collect();
                 
-                let mut account_refs: Vec<&mut Account> = account_infos.iter_mut().collect();
+                let original_infos = account_infos.clone();
+                let mut instruction_accounts = InstructionAccounts::aliased(account_infos.iter_mut().collect(), positions.clone())?;
                 
                 // Execute system program instruction
                 SystemProgram::process_instruction(
                     instruction_data,
-                    &pubkeys,
-                    &mut account_refs,
+                    &account_metas,
+                    &mut instruction_accounts,
                     context,
                 )?;
                 
-                // Update accounts back to storage
-                for (i, &index) in account_indices.iter().enumerate() {
-                    let pubkey = &pubkeys[index as usize];
-                    self.accounts.insert(*pubkey, account_infos[i].clone());
+                // Reject mutations of accounts the message header marks readonly
+                let writable = |k: usize| positions.iter().zip(&account_metas).any(|(&p, meta)| p == k && meta.is_writable);
+                for (k, (original, account)) in original_infos.iter().zip(&account_infos).enumerate() {
+                    if !writable(k) && account_modified(original, account) {
+                        return Err(TerminatorError::ReadonlyAccountModified);
+                    }
                 }
+                
+                // Store only the writable accounts the instruction changed
+                for (k, (original, account)) in original_infos.iter().zip(account_infos).enumerate() {
+                    if writable(k) && account_modified(original, &account) {
+                        self.accounts.insert(keys[k], account);
+                    }
+                }
+            }
+            MEMO_PROGRAM_ID | MEMO_V1_PROGRAM_ID => {
+                MemoProgram::process_instruction(instruction_data, &account_metas, context)?;
+            }
+            ED25519_PROGRAM_ID => {
+                Ed25519Program::process_instruction(instruction_data, context)?;
+            }
+            SECP256K1_PROGRAM_ID => {
+                Secp256k1Program::process_instruction(instruction_data, context)?;
             }
             _ => {
-                // WASM limitation: Real BPF VM not available in browser (native dependencies)
-                context.log(format!("🌐 WASM BPF simulation: {:?}", program_id));
-                context.log("⚠️ Real BPF execution available in native runtime only".to_string());
-                context.consume_compute_units(1000);
+                let program_pubkey = Pubkey::new(*program_id);
+                match self.accounts.get(&program_pubkey).filter(|account| account.executable) {
+                    Some(program) => {
+                        // The native VM has native dependencies; interpret the program instead
+                        self.bpf_vm.load_program(&program_pubkey, &program.data)?;
+                        let mut program_logs = Vec::new();
+                        let outcome = self.bpf_vm.execute_program(
+                            &program_pubkey,
+                            instruction_data,
+                            &mut [],
+                            &mut context.compute_units_remaining,
+                            &mut program_logs,
+                        );
+                        for message in program_logs {
+                            context.log(message);
+                        }
+                        outcome?;
+                    }
+                    None => {
+                        context.log_debug(format!("🌐 WASM BPF simulation: {:?}", program_id));
+                        context.log_debug("⚠️ No executable account for this program".to_string());
+                        context.consume_compute_units(context.cost_model.instruction_base);
+                    }
+                }
             }
         }
         
         Ok(())
     }
     
+    /// Transfer `amount` from the default funded account to batch recipient
+    /// `i`, creating the recipient if needed; returns whether it succeeded
+    fn execute_batch_transfer(&mut self, i: u32, amount: u64) -> bool {
+        let from_pubkey = Pubkey::new([1u8; 32]);
+        let mut to_array = [2u8; 32];
+        to_array[31] = (i % 256) as u8; // Vary recipient
+        let to_pubkey = Pubkey::new(to_array);
+        
+        // Ensure recipient account exists
+        self.accounts.entry(to_pubkey).or_insert_with(|| Account::new(0, vec![], SYSTEM_PROGRAM_ID));
+        
+        self.create_and_execute_transfer(&from_pubkey, &to_pubkey, amount).is_ok()
+    }
+    
     fn get_performance_metrics(&self) -> PerformanceMetrics {
         let tps = if self.total_execution_time > 0.0 {
             (self.transaction_count as f64) / (self.total_execution_time / 1000.0)
@@ -370,6 +431,13 @@ impl WasmRuntime {
     }
 }
 
+/// Index ranges splitting a batch of `count` into chunks of at most
+/// `chunk_size` (a zero size is treated as one)
+fn batch_chunks(count: u32, chunk_size: u32) -> impl Iterator<Item = std::ops::Range<u32>> {
+    let chunk_size = chunk_size.max(1);
+    (0..count).step_by(chunk_size as usize).map(move |start| start..count.min(start.saturating_add(chunk_size)))
+}
+
 #[wasm_bindgen]
 impl PerformanceMetrics {
     #[wasm_bindgen(getter)]
@@ -391,9 +459,160 @@ impl PerformanceMetrics {
     pub fn failed_transactions(&self) -> u64 { self.failed_transactions }
 }
 
+/// SHA-256 of `data`
+#[wasm_bindgen]
+pub fn wasm_sha256(data: &[u8]) -> Vec<u8> {
+    SolanaCrypto::sha256_hash(data).to_vec()
+}
+
+/// `RuntimeCapabilities::to_json` for this build, for capability badges
+#[wasm_bindgen]
+pub fn wasm_capabilities() -> String {
+    crate::RuntimeCapabilities::detect().to_json()
+}
+
+/// BLAKE3 of `data`
+#[wasm_bindgen]
+pub fn wasm_blake3(data: &[u8]) -> Vec<u8> {
+    SolanaCrypto::blake3_hash(data).to_vec()
+}
+
+/// Verify an ed25519 signature over `message`
+///
+/// A signature that isn't 64 bytes, or a public key that isn't a 32-byte
+/// curve point, is an error rather than `false`.
+#[wasm_bindgen]
+pub fn wasm_verify_ed25519(signature: &[u8], message: &[u8], pubkey: &[u8]) -> std::result::Result<bool, JsValue> {
+    verify_ed25519(signature, message, pubkey).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// `wasm_verify_ed25519` without the `JsValue` error, so it runs natively
+fn verify_ed25519(signature: &[u8], message: &[u8], pubkey: &[u8]) -> Result<bool> {
+    let signature: &[u8; 64] = signature.try_into().map_err(|_| {
+        TerminatorError::SerializationError(format!("Signature must be 64 bytes, got {}", signature.len()))
+    })?;
+    let pubkey: &[u8; 32] = pubkey.try_into().map_err(|_| {
+        TerminatorError::SerializationError(format!("Public key must be 32 bytes, got {}", pubkey.len()))
+    })?;
+    SolanaCrypto::verify_ed25519_signature(signature, message, pubkey)
+}
+
+/// Decoded view of a transaction, as rendered by the browser inspector
+#[derive(Debug, Serialize)]
+struct InspectedTransaction {
+    signatures: Vec<String>,
+    recent_blockhash: String,
+    instructions: Vec<InspectedInstruction>,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectedInstruction {
+    program_id: String,
+    /// Display name, when the program is recognized
+    program: Option<&'static str>,
+    accounts: Vec<InspectedAccount>,
+    description: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectedAccount {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// Decode a base64 wire-format transaction and decompile it to JSON, with
+/// each instruction's program, account roles and a plain-language summary
+#[wasm_bindgen]
+pub fn parse_transaction_to_json(base64: &str) -> std::result::Result<String, JsValue> {
+    inspect_transaction(base64).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// `parse_transaction_to_json` without the `JsValue` error, so it runs natively
+fn inspect_transaction(base64: &str) -> Result<String> {
+    let tx = SolanaTransactionParser::parse_transaction_base64(base64)?;
+    let instructions = tx.message.decompile_all()?
+        .into_iter()
+        .map(|instruction| InspectedInstruction {
+            program_id: instruction.program_id.to_string(),
+            program: program_name(&instruction.program_id.0),
+            accounts: instruction.accounts.iter()
+                .map(|meta| InspectedAccount {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            description: describe_instruction(&instruction.program_id.0, &instruction.data),
+        })
+        .collect();
+    let inspected = InspectedTransaction {
+        signatures: tx.signatures.iter().map(|sig| bs58::encode(&sig.0).into_string()).collect(),
+        recent_blockhash: bs58::encode(&tx.message.recent_blockhash.0).into_string(),
+        instructions,
+    };
+    serde_json::to_string(&inspected).map_err(|e| TerminatorError::SerializationError(e.to_string()))
+}
+
 /// Initialize WASM runtime - called from JavaScript
 #[wasm_bindgen(start)]
 pub fn main() {
     console::log_1(&"🤖 Terminator-Dancer WASM Module Loaded!".into());
     console::log_1(&"Ready to execute blockchain transactions in your browser!".into());
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use ed25519_dalek::Signer;
+
+    #[test]
+    fn test_verify_ed25519() {
+        let keypair = testing::alice();
+        let pubkey = testing::pubkey(&keypair).0;
+        let signature = keypair.sign(b"browser").to_bytes();
+
+        assert!(verify_ed25519(&signature, b"browser", &pubkey).unwrap());
+        assert!(!verify_ed25519(&signature, b"tampered", &pubkey).unwrap());
+        assert!(verify_ed25519(&signature[..63], b"browser", &pubkey).is_err());
+        assert!(verify_ed25519(&signature, b"browser", &pubkey[..31]).is_err());
+        assert_eq!(wasm_sha256(b"browser"), SolanaCrypto::sha256_hash(b"browser").to_vec());
+    }
+
+    #[test]
+    fn test_inspect_mainnet_transfer() {
+        // Mainnet SOL transfer of 2_034_280 lamports, as checked in to `examples/debug_tx_bytes.rs`
+        let json = inspect_transaction("AWDBlrdyFjzjDgf9gWioXrCB/YJpHeENZcIEwNPzflGviVkElIKpUR7yvnwrNsz0cuq5MGm0FlR/7gf8piruIw6AAQABA/NGAeBeYMRrJvmYo4E2q+pEKIVjl40S0g00e/NP8G7JAGBZvnD3SSIz2B5EgB+fk5vSvVThak5kIyxG8n1zLKIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAVYxfd1NZLpMnJgbaVBboof2ZjR+cEKxQwMiWhlFusxAQICAAEMAgAAAGgKHwAAAAAAAA==").unwrap();
+        let inspected: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let transfer = &inspected["instructions"][0];
+        assert_eq!(transfer["program"], "System Program");
+        assert_eq!(transfer["description"], "Transfer 0.00203428 SOL");
+        assert_eq!(transfer["accounts"][0]["pubkey"], "HNdz9s5NTkDxQCU5ri3GTRskzc9wD62ftoMY1QneeuLt");
+        assert_eq!(transfer["accounts"][0]["is_signer"], true);
+        assert_eq!(transfer["accounts"][1]["is_signer"], false);
+        assert_eq!(transfer["accounts"][1]["is_writable"], true);
+
+        let err = inspect_transaction("AWDB!").unwrap_err().to_string();
+        assert!(err.contains("offset"), "{}", err);
+    }
+
+    #[test]
+    fn test_batch_chunks_report_after_each_chunk() {
+        let mut executed = Vec::new();
+        let mut progress_calls = 0;
+        for chunk in batch_chunks(10, 4) {
+            executed.extend(chunk);
+            progress_calls += 1;
+        }
+        assert_eq!(executed, (0..10).collect::<Vec<_>>());
+        assert_eq!(progress_calls, 3);
+
+        assert_eq!(batch_chunks(8, 4).count(), 2);
+        assert_eq!(batch_chunks(0, 4).count(), 0);
+        assert_eq!(batch_chunks(3, 0).count(), 3);
+        // The last chunk ends at `count` even when a full chunk would pass u32::MAX
+        assert_eq!(batch_chunks(u32::MAX, u32::MAX - 1).last(), Some(u32::MAX - 1..u32::MAX));
+    }
+}
@@ -0,0 +1,71 @@
+/// Wire Integer Encoding
+/// Solana encodes every integer on the wire little-endian; all reads and writes of amounts and fields go through here
+
+use crate::prelude::*;
+use crate::{Result, TerminatorError};
+
+/// The `N` bytes at `offset`, failing rather than panicking when `data` is too short
+fn read_le<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    offset.checked_add(N)
+        .and_then(|end| data.get(offset..end))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| TerminatorError::SerializationError(format!(
+            "Truncated {}-byte integer at offset {} of {}-byte data", N, offset, data.len()
+        )))
+}
+
+pub fn read_u16_le(data: &[u8], offset: usize) -> Result<u16> {
+    read_le(data, offset).map(u16::from_le_bytes)
+}
+
+pub fn read_u32_le(data: &[u8], offset: usize) -> Result<u32> {
+    read_le(data, offset).map(u32::from_le_bytes)
+}
+
+pub fn read_u64_le(data: &[u8], offset: usize) -> Result<u64> {
+    read_le(data, offset).map(u64::from_le_bytes)
+}
+
+pub fn write_u16_le(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn write_u32_le(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn write_u64_le(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamports_are_little_endian() {
+        // The mainnet transfer amount as it appears after the system instruction tag
+        let wire = [2, 0, 0, 0, 0x68, 0x0a, 0x1f, 0, 0, 0, 0, 0];
+        assert_eq!(read_u32_le(&wire, 0).unwrap(), 2);
+        assert_eq!(read_u64_le(&wire, 4).unwrap(), 2_034_280);
+
+        let mut buffer = Vec::new();
+        write_u32_le(&mut buffer, 2);
+        write_u64_le(&mut buffer, 2_034_280);
+        assert_eq!(buffer, wire);
+
+        write_u16_le(&mut buffer, 0x0102);
+        assert_eq!(read_u16_le(&buffer, 12).unwrap(), 0x0102);
+        assert_eq!(&buffer[12..], &[0x02, 0x01]);
+    }
+
+    #[test]
+    fn test_truncated_reads_rejected() {
+        let data = [0u8; 8];
+        assert!(read_u64_le(&data, 0).is_ok());
+        assert!(read_u64_le(&data, 1).is_err());
+        assert!(read_u64_le(&data[..7], 0).is_err());
+        assert!(read_u32_le(&data, 5).is_err());
+        assert!(read_u16_le(&data, usize::MAX).is_err());
+    }
+}
@@ -41,7 +41,8 @@ int fd_sbpf_vm_exec(
     unsigned char *input,          // input data
     unsigned long input_sz,        // input size
     unsigned char *output,         // output buffer
-    unsigned long *output_sz       // output size (in/out)
+    unsigned long *output_sz,      // output size (in/out)
+    unsigned long *compute_units   // compute units consumed (out)
 );
 
 void fd_sbpf_program_delete(void *program);
@@ -60,4 +61,12 @@ int fd_acc_mgr_modify(
     void *mgr,
     const unsigned char *address,  // 32-byte account address
     const void *record             // account record input
-); 
\ No newline at end of file
+);
+
+// Like fd_acc_mgr_modify, but deep-copies record->data into manager-owned
+// storage so the caller's buffer may be freed once the call returns
+int fd_acc_mgr_modify_copy(
+    void *mgr,
+    const unsigned char *address,  // 32-byte account address
+    const void *record             // account record input
+);